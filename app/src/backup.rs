@@ -0,0 +1,496 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use rex_db::models::{FetchNature, FullTx};
+use rex_db::{backup_db, try_get_connection};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tempfile::TempDir;
+
+use crate::conn::{DbConn, try_get_conn_old};
+use crate::crypto::{CryptoError, EncryptedDb};
+use crate::error::RexResult;
+use crate::views::BalancesJson;
+
+const BACKUP_PREFIX: &str = "rex-";
+const SAFETY_PREFIX: &str = "rex-safety-";
+const BACKUP_EXTENSION: &str = ".sqlite";
+const ENCRYPTED_EXTENSION: &str = ".sqlite.enc";
+const TIME_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// A timestamped backup such as `rex-20250301-184512.sqlite`. Backups made in the same second
+/// get a number after the time such as `rex-20250301-184512-1.sqlite`. Safety copies saved
+/// before a restore start with `rex-safety-` and are never deleted by the retention
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackupFile {
+    pub path: PathBuf,
+    pub created: NaiveDateTime,
+    /// Orders backups made in the same second. 0 for the first one
+    pub sequence: u32,
+    pub encrypted: bool,
+    pub safety: bool,
+}
+
+impl BackupFile {
+    /// Reads the backup time from the file name. Returns None for any other file
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+
+        let (name, safety) = if let Some(name) = name.strip_prefix(SAFETY_PREFIX) {
+            (name, true)
+        } else {
+            (name.strip_prefix(BACKUP_PREFIX)?, false)
+        };
+
+        let (name, encrypted) = if let Some(name) = name.strip_suffix(ENCRYPTED_EXTENSION) {
+            (name, true)
+        } else {
+            (name.strip_suffix(BACKUP_EXTENSION)?, false)
+        };
+
+        // The time is always 15 characters followed by an optional -N
+        let time = name.get(..15)?;
+
+        let sequence = match &name[15..] {
+            "" => 0,
+            rest => rest.strip_prefix('-')?.parse().ok().filter(|n| *n > 0)?,
+        };
+
+        let created = NaiveDateTime::parse_from_str(time, TIME_FORMAT).ok()?;
+
+        Some(Self {
+            path: path.to_path_buf(),
+            created,
+            sequence,
+            encrypted,
+            safety,
+        })
+    }
+
+    #[must_use]
+    pub fn file_name(created: NaiveDateTime, encrypted: bool) -> String {
+        Self::name(BACKUP_PREFIX, created, 0, encrypted)
+    }
+
+    fn name(prefix: &str, created: NaiveDateTime, sequence: u32, encrypted: bool) -> String {
+        let extension = if encrypted {
+            ENCRYPTED_EXTENSION
+        } else {
+            BACKUP_EXTENSION
+        };
+
+        let sequence = if sequence == 0 {
+            String::new()
+        } else {
+            format!("-{sequence}")
+        };
+
+        format!(
+            "{prefix}{}{sequence}{extension}",
+            created.format(TIME_FORMAT)
+        )
+    }
+}
+
+/// Creates an empty file at the first free backup name in the directory and returns its path.
+/// Claiming the name makes a backup made in the same second pick the next number instead of
+/// replacing this one. The file is then replaced by the backup
+pub fn reserve_backup_path(
+    dir: &Path,
+    created: NaiveDateTime,
+    encrypted: bool,
+) -> RexResult<PathBuf> {
+    reserve_path(dir, BACKUP_PREFIX, created, encrypted)
+}
+
+fn reserve_path(
+    dir: &Path,
+    prefix: &str,
+    created: NaiveDateTime,
+    encrypted: bool,
+) -> RexResult<PathBuf> {
+    let mut sequence = 0;
+
+    loop {
+        let path = dir.join(BackupFile::name(prefix, created, sequence, encrypted));
+
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => return Ok(path),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => sequence += 1,
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// How many timestamped backups are kept in each backup path. A backup is kept if any of the
+/// rules keeps it. The daily, weekly and monthly rules keep the newest backup of that many
+/// recent days, weeks and months that have a backup
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct Retention {
+    pub keep_last: usize,
+    pub daily: usize,
+    pub weekly: usize,
+    pub monthly: usize,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self {
+            keep_last: 10,
+            daily: 7,
+            weekly: 4,
+            monthly: 12,
+        }
+    }
+}
+
+impl Retention {
+    /// Backups that none of the rules keep. `backups` must be sorted newest first. The newest
+    /// backup is always kept
+    #[must_use]
+    pub fn to_remove<'a>(&self, backups: &'a [BackupFile]) -> Vec<&'a BackupFile> {
+        let mut keep = HashSet::new();
+
+        keep.extend(0..self.keep_last.max(1).min(backups.len()));
+
+        keep_newest_per(backups, self.daily, |b| b.created.date(), &mut keep);
+        keep_newest_per(backups, self.weekly, |b| b.created.iso_week(), &mut keep);
+        keep_newest_per(
+            backups,
+            self.monthly,
+            |b| (b.created.year(), b.created.month()),
+            &mut keep,
+        );
+
+        backups
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !keep.contains(index))
+            .map(|(_, backup)| backup)
+            .collect()
+    }
+}
+
+/// When a backup is made while the TUI is running. A backup is due once either rule is met
+/// and the DB has changed since the last one. A rule set to 0 is turned off
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct AutoBackup {
+    /// Minutes since the last backup
+    pub interval_minutes: u64,
+    /// Changes since the last backup. See [`crate::conn::DbConn::changes`]
+    pub changes: usize,
+}
+
+impl Default for AutoBackup {
+    fn default() -> Self {
+        Self {
+            interval_minutes: 15,
+            changes: 20,
+        }
+    }
+}
+
+impl AutoBackup {
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.interval_minutes > 0 || self.changes > 0
+    }
+
+    /// Whether a backup is due `elapsed` after the last one with `changes` made since then
+    #[must_use]
+    pub fn is_due(&self, elapsed: Duration, changes: usize) -> bool {
+        if changes == 0 {
+            return false;
+        }
+
+        let interval_passed =
+            self.interval_minutes > 0 && elapsed >= Duration::from_secs(self.interval_minutes * 60);
+        let changes_passed = self.changes > 0 && changes >= self.changes;
+
+        interval_passed || changes_passed
+    }
+}
+
+/// Keeps the first backup of each period until `count` periods are covered
+fn keep_newest_per<K: PartialEq>(
+    backups: &[BackupFile],
+    count: usize,
+    period: impl Fn(&BackupFile) -> K,
+    keep: &mut HashSet<usize>,
+) {
+    let mut last_period = None;
+    let mut kept = 0;
+
+    for (index, backup) in backups.iter().enumerate() {
+        if kept == count {
+            break;
+        }
+
+        let current = period(backup);
+
+        if last_period.as_ref() != Some(&current) {
+            keep.insert(index);
+            kept += 1;
+            last_period = Some(current);
+        }
+    }
+}
+
+/// Timestamped backups in the directory, newest first
+pub fn list_backups(dir: &Path) -> RexResult<Vec<BackupFile>> {
+    let mut backups = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if let Some(backup) = BackupFile::from_path(&path) {
+            backups.push(backup);
+        }
+    }
+
+    backups.sort_by_key(|backup| Reverse((backup.created, backup.sequence)));
+
+    Ok(backups)
+}
+
+/// Deletes the backups in the directory the retention does not keep. Safety copies are left
+/// alone. Returns the deleted paths
+pub fn prune_backups(dir: &Path, retention: &Retention) -> RexResult<Vec<PathBuf>> {
+    let mut backups = list_backups(dir)?;
+    backups.retain(|backup| !backup.safety);
+
+    let mut removed = Vec::new();
+
+    for backup in retention.to_remove(&backups) {
+        fs::remove_file(&backup.path)?;
+        removed.push(backup.path.clone());
+    }
+
+    Ok(removed)
+}
+
+/// Copies the DB with SQLite's online backup API. The copy is made next to the target and
+/// renamed so a failed backup never replaces a good file
+pub fn copy_db(db_path: &Path, target: &Path) -> RexResult<()> {
+    let mut temp_path = target.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+
+    if temp_path.exists() {
+        fs::remove_file(&temp_path)?;
+    }
+
+    backup_db(&db_path.to_string_lossy(), &temp_path.to_string_lossy())?;
+    fs::rename(&temp_path, target)?;
+
+    Ok(())
+}
+
+/// Makes a new timestamped backup of the DB in the directory. Returns the backup path
+pub fn create_backup(db_path: &Path, dir: &Path, created: NaiveDateTime) -> RexResult<PathBuf> {
+    let target = reserve_backup_path(dir, created, false)?;
+
+    write_reserved(&target, || copy_db(db_path, &target))
+}
+
+/// Runs the write to a reserved path and removes the empty file if it fails
+fn write_reserved(target: &Path, write: impl FnOnce() -> RexResult<()>) -> RexResult<PathBuf> {
+    if let Err(e) = write() {
+        let _ = fs::remove_file(target);
+        return Err(e);
+    }
+
+    Ok(target.to_path_buf())
+}
+
+/// Tx count and final balances of a backup
+pub struct BackupSummary {
+    pub tx_count: usize,
+    pub balances: BalancesJson,
+}
+
+/// How the txs of the current DB change once a backup is restored. Txs are matched by id.
+/// `added` txs only exist in the backup and `removed` txs only exist in the current DB
+#[derive(Default)]
+pub struct TxDiff {
+    pub added: Vec<FullTx>,
+    pub removed: Vec<FullTx>,
+    /// The current tx paired with the one in the backup
+    pub changed: Vec<(FullTx, FullTx)>,
+}
+
+impl TxDiff {
+    #[must_use]
+    pub fn new(current: &[FullTx], backup: &[FullTx]) -> Self {
+        let mut diff = Self::default();
+
+        let backup_txs: HashMap<i32, &FullTx> = backup.iter().map(|tx| (tx.id, tx)).collect();
+        let mut matched = HashSet::new();
+
+        for tx in current {
+            match backup_txs.get(&tx.id) {
+                Some(backup_tx) => {
+                    matched.insert(tx.id);
+
+                    if !same_tx(tx, backup_tx) {
+                        diff.changed.push((tx.clone(), (*backup_tx).clone()));
+                    }
+                }
+                None => diff.removed.push(tx.clone()),
+            }
+        }
+
+        diff.added = backup
+            .iter()
+            .filter(|tx| !matched.contains(&tx.id))
+            .cloned()
+            .collect();
+
+        diff
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Whether the two txs have the same content. The display order is ignored
+fn same_tx(a: &FullTx, b: &FullTx) -> bool {
+    let tags = |tx: &FullTx| tx.tags.iter().map(|t| t.name.clone()).collect::<Vec<_>>();
+
+    a.date == b.date
+        && a.has_time == b.has_time
+        && a.details == b.details
+        && a.from_method.name == b.from_method.name
+        && a.to_method.as_ref().map(|m| &m.name) == b.to_method.as_ref().map(|m| &m.name)
+        && a.amount.value() == b.amount.value()
+        && a.tx_type.to_string() == b.tx_type.to_string()
+        && tags(a) == tags(b)
+}
+
+/// A backup opened for reading. The backup is copied, or decrypted, to a private temporary
+/// directory that is deleted once this is dropped. The backup file itself is never modified
+pub struct OpenedBackup {
+    db_path: PathBuf,
+    _temp_dir: TempDir,
+}
+
+impl OpenedBackup {
+    /// Opens the backup. Encrypted backups need the encrypted DB they were made from. The copy
+    /// is brought to the current schema so backups made by older versions can be read and
+    /// restored
+    pub fn open(backup: &BackupFile, encrypted_db: Option<&EncryptedDb>) -> RexResult<Self> {
+        let temp_dir = tempfile::Builder::new().prefix("rex-").tempdir()?;
+        let db_path = temp_dir.path().join("backup.sqlite");
+
+        if backup.encrypted {
+            let Some(encrypted_db) = encrypted_db else {
+                return Err(CryptoError::NoKey(backup.path.display().to_string()).into());
+            };
+
+            encrypted_db.decrypt_file(&backup.path, &db_path)?;
+        } else {
+            copy_db(&backup.path, &db_path)?;
+        }
+
+        drop(try_get_connection(&db_path.display().to_string())?);
+
+        Ok(Self {
+            db_path,
+            _temp_dir: temp_dir,
+        })
+    }
+
+    /// Location of the plain backup DB
+    #[must_use]
+    pub fn db_path(&self) -> &Path {
+        &self.db_path
+    }
+
+    /// Opens the copy of the backup. Changes made through it do not reach the backup
+    pub fn conn(&self) -> RexResult<DbConn> {
+        let mut conn = try_get_conn_old(&self.db_path.display().to_string())?;
+        conn.load_cache()?;
+
+        Ok(conn)
+    }
+
+    pub fn summary(&self) -> RexResult<BackupSummary> {
+        let mut conn = self.conn()?;
+
+        let txs = all_txs(&mut conn)?;
+
+        Ok(BackupSummary {
+            tx_count: txs.len(),
+            balances: conn.get_final_balances_json()?,
+        })
+    }
+
+    /// Compares every tx of the current DB with the backup
+    pub fn diff(&self, current: &mut DbConn) -> RexResult<TxDiff> {
+        let backup_txs = all_txs(&mut self.conn()?)?;
+        let current_txs = all_txs(current)?;
+
+        Ok(TxDiff::new(&current_txs, &backup_txs))
+    }
+}
+
+fn all_txs(conn: &mut DbConn) -> RexResult<Vec<FullTx>> {
+    Ok(FullTx::get_txs(
+        NaiveDate::default(),
+        FetchNature::All,
+        conn,
+    )?)
+}
+
+/// Saves a timestamped safety copy of the DB at `db_path` to the directory before it is
+/// replaced by a backup. The copy is encrypted if the DB is. Returns the path of the copy
+pub fn save_safety_copy(
+    db_path: &Path,
+    dir: &Path,
+    encrypted_db: Option<&EncryptedDb>,
+    created: NaiveDateTime,
+) -> RexResult<PathBuf> {
+    let target = reserve_path(dir, SAFETY_PREFIX, created, encrypted_db.is_some())?;
+
+    write_reserved(&target, || match encrypted_db {
+        Some(encrypted_db) => {
+            let snapshot = Snapshot::new(db_path)?;
+            encrypted_db.encrypt_file(snapshot.path(), &target)
+        }
+        None => copy_db(db_path, &target),
+    })
+}
+
+/// A consistent copy of a DB that is still being written to, kept in a private temporary
+/// directory until this is dropped. Used to encrypt the DB without reading a half written file
+pub struct Snapshot {
+    path: PathBuf,
+    _temp_dir: TempDir,
+}
+
+impl Snapshot {
+    pub fn new(db_path: &Path) -> RexResult<Self> {
+        let temp_dir = tempfile::Builder::new().prefix("rex-").tempdir()?;
+        let path = temp_dir.path().join("snapshot.sqlite");
+
+        copy_db(db_path, &path)?;
+
+        Ok(Self {
+            path,
+            _temp_dir: temp_dir,
+        })
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
@@ -1,32 +1,66 @@
-use anyhow::{Error, Result};
-use chrono::NaiveDate;
+use crate::error::{RexError, RexResult};
+use chrono::{Local, NaiveDate};
+use diesel::result::Error as DieselError;
 use diesel::{Connection, SqliteConnection};
 pub use rex_db::models::FetchNature;
 use rex_db::models::{Balance, FullTx, NewSearch, NewTx, Tag, Tx, TxMethod};
-use rex_db::{Cache, ConnCache, get_connection, get_connection_no_migrations};
+use rex_db::{
+    Cache, ConnCache, IN_MEMORY, NotFoundError, replace_rows, try_copy_to_memory,
+    try_get_connection, try_get_connection_no_migrations,
+};
+use rex_shared::models::{Cent, Dollar};
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
+use crate::backup::copy_db;
 use crate::modifier::{
-    activity_delete_tx, activity_edit_tx, activity_new_tx, activity_search_tx,
-    activity_swap_position, add_new_tx, add_new_tx_methods, delete_tx,
+    TxBuilder, activity_delete_tx, activity_edit_tx, activity_new_tx, activity_search_tx,
+    activity_swap_position, add_new_tx, add_new_tx_methods, delete_tx, split_tags,
+};
+use crate::ui_helper::{
+    Autofiller, Stepper, TagClassifier, TagSuggestion, Verifier, VerifierError,
 };
-use crate::ui_helper::{Autofiller, Stepper, Verifier};
 use crate::utils::month_name_to_num;
 use crate::views::{
-    ActivityView, ChartView, SearchView, SummaryView, TxViewGroup, get_activity_view,
-    get_chart_view, get_search_txs, get_summary, get_txs,
+    ActivityView, BalancesJson, ChartView, ForecastView, SearchView, SummaryView, TxViewGroup,
+    YearlyReport, get_activity_view, get_chart_view, get_forecast_view, get_search_txs,
+    get_summary, get_txs, get_yearly_report,
 };
 
+/// Turns the month name and year of the TUI tabs to the first day of the month
+fn month_year_to_date(month: &str, year: &str) -> RexResult<NaiveDate> {
+    let year = year
+        .parse::<i32>()
+        .map_err(|_| VerifierError::InvalidYear)?;
+    let month = month_name_to_num(month).ok_or(VerifierError::InvalidMonth)?;
+
+    Ok(NaiveDate::from_ymd_opt(year, month, 1).ok_or(VerifierError::NonExistingDate)?)
+}
+
+/// # Panics
+///
+/// Panics if the DB cannot be opened or migrated. Use [`try_get_conn`] to handle it
 #[must_use]
 pub fn get_conn(location: &str) -> DbConn {
     DbConn::new(location)
 }
 
+pub fn try_get_conn(location: &str) -> RexResult<DbConn> {
+    DbConn::try_new(location)
+}
+
+/// # Panics
+///
+/// Panics if the DB cannot be opened. Use [`try_get_conn_old`] to handle it
 #[must_use]
 pub fn get_conn_old(location: &str) -> DbConn {
     DbConn::new_no_migrations(location)
 }
 
+pub fn try_get_conn_old(location: &str) -> RexResult<DbConn> {
+    DbConn::try_new_no_migrations(location)
+}
+
 pub struct MutDbConn<'a> {
     conn: &'a mut SqliteConnection,
     cache: &'a Cache,
@@ -56,6 +90,10 @@ impl ConnCache for MutDbConn<'_> {
 pub struct DbConn {
     pub conn: SqliteConnection,
     pub cache: Cache,
+    /// Trained on first use and updated as txs change afterwards
+    classifier: Option<TagClassifier>,
+    /// Number of changes made through this connection
+    changes: usize,
 }
 
 impl ConnCache for DbConn {
@@ -69,30 +107,89 @@ impl ConnCache for DbConn {
 }
 
 impl DbConn {
+    /// # Panics
+    ///
+    /// Panics if the DB cannot be opened, migrated or read. Use [`DbConn::try_new`] to handle it
     #[must_use]
     pub fn new(db_url: &str) -> Self {
-        let conn = get_connection(db_url);
+        Self::try_new(db_url).expect("Failed to open the database")
+    }
 
-        let mut to_return = DbConn {
-            conn,
-            cache: Cache {
-                tags: HashMap::new(),
-                tx_methods: HashMap::new(),
-                txs: None,
-                details: HashSet::new(),
-            },
-        };
+    /// Opens the DB, runs any pending migrations and loads the cache
+    pub fn try_new(db_url: &str) -> RexResult<Self> {
+        let conn = try_get_connection(db_url)?;
 
-        to_return.reload_methods();
-        to_return.reload_tags();
-        to_return.reload_details();
+        Self::with_cache(conn)
+    }
 
-        to_return
+    /// Opens a new empty DB living in memory with every migration applied. Nothing touches
+    /// the disk and every change is gone once the connection drops
+    pub fn in_memory() -> RexResult<Self> {
+        Self::try_new(IN_MEMORY)
     }
 
+    /// Opens an in-memory copy of the DB at `db_url`. The file is only read from so every
+    /// change made through the connection is discarded once it drops
+    pub fn in_memory_copy(db_url: &str) -> RexResult<Self> {
+        let conn = try_copy_to_memory(db_url)?;
+
+        Self::with_cache(conn)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the DB cannot be opened. Use [`DbConn::try_new_no_migrations`] to handle it
     #[must_use]
     pub fn new_no_migrations(db_url: &str) -> Self {
-        let conn = get_connection_no_migrations(db_url);
+        Self::try_new_no_migrations(db_url).expect("Failed to open the database")
+    }
+
+    /// Opens the DB as it is without running migrations or loading the cache
+    pub fn try_new_no_migrations(db_url: &str) -> RexResult<Self> {
+        let conn = try_get_connection_no_migrations(db_url)?;
+
+        Ok(Self::with_conn(conn))
+    }
+
+    fn with_cache(conn: SqliteConnection) -> RexResult<Self> {
+        let mut to_return = Self::with_conn(conn);
+
+        to_return.load_cache()?;
+
+        Ok(to_return)
+    }
+
+    /// Loads the methods, tags and details from the DB. Needed before reading txs from a
+    /// connection opened with [`DbConn::try_new_no_migrations`]
+    pub fn load_cache(&mut self) -> RexResult<()> {
+        self.reload_methods()?;
+        self.reload_tags()?;
+        self.reload_details()?;
+
+        self.classifier = None;
+
+        Ok(())
+    }
+
+    /// Replaces every row with the rows of the DB at `db_path`, such as a backup. A migrated
+    /// copy of it is made first so older backups work and the file itself is only read from.
+    /// Either everything is replaced or nothing changes
+    pub fn restore_from(&mut self, db_path: &Path) -> RexResult<()> {
+        let temp_dir = tempfile::Builder::new().prefix("rex-").tempdir()?;
+        let copy_path = temp_dir.path().join("restore.sqlite");
+
+        copy_db(db_path, &copy_path)?;
+
+        let copy_location = copy_path.display().to_string();
+
+        drop(try_get_connection(&copy_location)?);
+        replace_rows(&mut self.conn, &copy_location)?;
+        self.changes += 1;
+
+        self.load_cache()
+    }
+
+    fn with_conn(conn: SqliteConnection) -> Self {
         DbConn {
             conn,
             cache: Cache {
@@ -101,45 +198,70 @@ impl DbConn {
                 txs: None,
                 details: HashSet::new(),
             },
+            classifier: None,
+            changes: 0,
         }
     }
 
-    pub(crate) fn reload_methods(&mut self) {
-        let tx_methods = TxMethod::get_all(self)
-            .unwrap()
+    /// Number of txs and methods added, edited, deleted or moved plus the backups restored
+    /// through this connection. Can be compared with an earlier value to see if the DB changed
+    #[must_use]
+    pub fn changes(&self) -> usize {
+        self.changes
+    }
+
+    pub(crate) fn reload_methods(&mut self) -> RexResult<()> {
+        let tx_methods = TxMethod::get_all(self)?
             .into_iter()
             .map(|t| (t.id, t))
             .collect();
 
         self.cache.tx_methods = tx_methods;
+
+        Ok(())
     }
 
-    pub(crate) fn reload_tags(&mut self) {
-        let tags = Tag::get_all(self)
-            .unwrap()
-            .into_iter()
-            .map(|t| (t.id, t))
-            .collect();
+    pub(crate) fn reload_tags(&mut self) -> RexResult<()> {
+        let tags = Tag::get_all(self)?.into_iter().map(|t| (t.id, t)).collect();
 
         self.cache.tags = tags;
+
+        Ok(())
     }
 
-    pub(crate) fn reload_details(&mut self) {
-        self.cache.details = Tx::get_all_details(self).unwrap().into_iter().collect();
+    pub(crate) fn reload_details(&mut self) -> RexResult<()> {
+        self.cache.details = Tx::get_all_details(self)?.into_iter().collect();
+
+        Ok(())
     }
 
-    pub fn add_new_tx(&mut self, tx: NewTx, tags: &str) -> Result<()> {
-        self.conn.transaction::<_, Error, _>(|conn| {
+    /// Adds a tx from [`crate::modifier::parse_tx_fields`] with comma separated tags
+    pub fn add_new_tx(&mut self, tx: NewTx, tags: &str) -> RexResult<()> {
+        self.insert_tx(tx, &split_tags(tags))
+    }
+
+    /// Adds a tx made with a [`TxBuilder`]. Nothing is saved if the builder is not valid
+    pub fn add_tx(&mut self, tx: TxBuilder) -> RexResult<()> {
+        let new_tx = tx.validate(self)?;
+
+        self.insert_tx(new_tx, &tx.tag_list())
+    }
+
+    fn insert_tx(&mut self, tx: NewTx, tag_list: &[String]) -> RexResult<()> {
+        self.conn.transaction::<_, RexError, _>(|conn| {
             let mut db_conn = MutDbConn::new(conn, &self.cache);
 
-            let new_tags = add_new_tx(tx.clone(), tags, None, &mut db_conn)?;
+            let new_tags = add_new_tx(tx.clone(), tag_list, None, &mut db_conn)?;
 
-            activity_new_tx(&tx, tags, &mut db_conn)?;
+            activity_new_tx(&tx, tag_list, &mut db_conn)?;
             self.cache.new_tags(new_tags);
 
             Ok(())
         })?;
 
+        self.changes += 1;
+        self.learn_tx(&tx, tag_list);
+
         if let Some(details) = tx.details {
             self.cache.new_details(details.to_string());
         }
@@ -147,8 +269,8 @@ impl DbConn {
         Ok(())
     }
 
-    pub fn delete_tx(&mut self, tx: &FullTx) -> Result<()> {
-        self.conn.transaction::<_, Error, _>(|conn| {
+    pub fn delete_tx(&mut self, tx: &FullTx) -> RexResult<()> {
+        self.conn.transaction::<_, RexError, _>(|conn| {
             let mut db_conn = MutDbConn::new(conn, &self.cache);
 
             delete_tx(tx, &mut db_conn)?;
@@ -157,25 +279,45 @@ impl DbConn {
             Ok(())
         })?;
 
+        self.changes += 1;
+        self.forget_tx(tx);
+
         Ok(())
     }
 
-    pub fn edit_tx(&mut self, old_tx: &FullTx, new_tx: NewTx, tags: &str) -> Result<()> {
-        self.conn.transaction::<_, Error, _>(|conn| {
+    /// Replaces the old tx with the new one while keeping the tx id
+    pub fn edit_tx(&mut self, old_tx: &FullTx, new_tx: NewTx, tags: &str) -> RexResult<()> {
+        self.replace_tx(old_tx, new_tx, &split_tags(tags))
+    }
+
+    /// Same as [`DbConn::edit_tx`] with the new tx made with a [`TxBuilder`]. Nothing is saved
+    /// if the builder is not valid
+    pub fn edit_tx_with(&mut self, old_tx: &FullTx, new_tx: TxBuilder) -> RexResult<()> {
+        let tx = new_tx.validate(self)?;
+
+        self.replace_tx(old_tx, tx, &new_tx.tag_list())
+    }
+
+    fn replace_tx(&mut self, old_tx: &FullTx, new_tx: NewTx, tag_list: &[String]) -> RexResult<()> {
+        self.conn.transaction::<_, RexError, _>(|conn| {
             let mut db_conn = MutDbConn::new(conn, &self.cache);
 
             let old_tx_id = old_tx.id;
             delete_tx(old_tx, &mut db_conn)?;
 
-            let new_tags = add_new_tx(new_tx.clone(), tags, Some(old_tx_id), &mut db_conn)?;
+            let new_tags = add_new_tx(new_tx.clone(), tag_list, Some(old_tx_id), &mut db_conn)?;
 
-            activity_edit_tx(old_tx, &new_tx, tags, &mut db_conn)?;
+            activity_edit_tx(old_tx, &new_tx, tag_list, &mut db_conn)?;
 
             self.cache.new_tags(new_tags);
 
             Ok(())
         })?;
 
+        self.changes += 1;
+        self.forget_tx(old_tx);
+        self.learn_tx(&new_tx, tag_list);
+
         if let Some(details) = new_tx.details {
             self.cache.new_details(details.to_string());
         }
@@ -183,8 +325,73 @@ impl DbConn {
         Ok(())
     }
 
-    pub fn add_new_methods(&mut self, method_list: &[String]) -> Result<()> {
-        self.conn.transaction::<_, Error, _>(|conn| {
+    /// Ranks the existing tags by how likely they are to belong to a tx with the given fields.
+    /// The classifier is trained from all existing txs on the first call.
+    pub fn suggest_tags(
+        &mut self,
+        details: &str,
+        method: &str,
+        amount: &str,
+    ) -> RexResult<Vec<TagSuggestion>> {
+        if self.classifier.is_none() {
+            let txs = FullTx::get_txs(Local::now().date_naive(), FetchNature::All, self)?;
+            self.classifier = Some(TagClassifier::from_txs(&txs));
+        }
+
+        let details = details.trim();
+        let details = if details.is_empty() {
+            None
+        } else {
+            Some(details)
+        };
+
+        let amount = amount
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .map(|a| Dollar::new(a).cent());
+
+        Ok(self
+            .classifier
+            .as_ref()
+            .unwrap()
+            .rank(details, method, amount))
+    }
+
+    fn learn_tx(&mut self, tx: &NewTx, tag_list: &[String]) {
+        let Some(classifier) = self.classifier.as_mut() else {
+            return;
+        };
+
+        let Some(method) = self.cache.tx_methods.get(&tx.from_method) else {
+            return;
+        };
+
+        classifier.learn(
+            tx.details,
+            &method.name,
+            Some(Cent::new(tx.amount)),
+            tag_list,
+        );
+    }
+
+    fn forget_tx(&mut self, tx: &FullTx) {
+        let Some(classifier) = self.classifier.as_mut() else {
+            return;
+        };
+
+        let tags = tx.tags.iter().map(|t| t.name.clone()).collect::<Vec<_>>();
+
+        classifier.forget(
+            tx.details.as_deref(),
+            &tx.from_method.name,
+            Some(tx.amount),
+            &tags,
+        );
+    }
+
+    pub fn add_new_methods(&mut self, method_list: &[String]) -> RexResult<()> {
+        self.conn.transaction::<_, RexError, _>(|conn| {
             let mut db_conn = MutDbConn::new(conn, &self.cache);
 
             let new_methods = add_new_tx_methods(method_list, &mut db_conn)?;
@@ -194,11 +401,16 @@ impl DbConn {
             Ok(())
         })?;
 
+        self.changes += 1;
+
         Ok(())
     }
 
-    pub fn fetch_tx_with_id(&mut self, id: i32) -> Result<FullTx> {
-        let tx = FullTx::get_tx_by_id(id, self)?;
+    pub fn fetch_tx_with_id(&mut self, id: i32) -> RexResult<FullTx> {
+        let tx = FullTx::get_tx_by_id(id, self).map_err(|e| match e {
+            DieselError::NotFound => NotFoundError::Tx(id).into(),
+            e => RexError::from(e),
+        })?;
 
         Ok(tx)
     }
@@ -208,16 +420,13 @@ impl DbConn {
         month: &'a str,
         year: &'a str,
         nature: FetchNature,
-    ) -> Result<TxViewGroup> {
-        let result = self.conn.transaction::<TxViewGroup, Error, _>(|conn| {
+    ) -> RexResult<TxViewGroup> {
+        let result = self.conn.transaction::<TxViewGroup, RexError, _>(|conn| {
             let mut db_conn = MutDbConn::new(conn, &self.cache);
 
-            let year_num = year.parse::<i32>().unwrap();
-            let month_num = month_name_to_num(month);
-
-            let date = NaiveDate::from_ymd_opt(year_num, month_num, 1).unwrap();
+            let date = month_year_to_date(month, year)?;
 
-            get_txs(date, nature, &mut db_conn)
+            Ok(get_txs(date, nature, &mut db_conn)?)
         })?;
 
         Ok(result)
@@ -227,18 +436,18 @@ impl DbConn {
         &mut self,
         date: NaiveDate,
         nature: FetchNature,
-    ) -> Result<TxViewGroup> {
-        let result = self.conn.transaction::<TxViewGroup, Error, _>(|conn| {
+    ) -> RexResult<TxViewGroup> {
+        let result = self.conn.transaction::<TxViewGroup, RexError, _>(|conn| {
             let mut db_conn = MutDbConn::new(conn, &self.cache);
 
-            get_txs(date, nature, &mut db_conn)
+            Ok(get_txs(date, nature, &mut db_conn)?)
         })?;
 
         Ok(result)
     }
 
-    pub fn search_txs(&mut self, search: NewSearch) -> Result<SearchView> {
-        let result = self.conn.transaction::<SearchView, Error, _>(|conn| {
+    pub fn search_txs(&mut self, search: NewSearch) -> RexResult<SearchView> {
+        let result = self.conn.transaction::<SearchView, RexError, _>(|conn| {
             let mut db_conn = MutDbConn::new(conn, &self.cache);
 
             let search_view = get_search_txs(&search, &mut db_conn)?;
@@ -256,19 +465,40 @@ impl DbConn {
         month: &'a str,
         year: &'a str,
         nature: FetchNature,
-    ) -> Result<SummaryView> {
+    ) -> RexResult<SummaryView> {
         let (summary, txs) = self
             .conn
-            .transaction::<(SummaryView, Option<HashMap<i32, Vec<FullTx>>>), Error, _>(|conn| {
-                let mut db_conn = MutDbConn::new(conn, &self.cache);
+            .transaction::<(SummaryView, Option<HashMap<i32, Vec<FullTx>>>), RexError, _>(
+                |conn| {
+                    let mut db_conn = MutDbConn::new(conn, &self.cache);
+
+                    let date = month_year_to_date(month, year)?;
 
-                let year_num = year.parse::<i32>().unwrap();
-                let month_num = month_name_to_num(month);
+                    Ok(get_summary(date, nature, &mut db_conn)?)
+                },
+            )?;
+
+        if let Some(txs) = txs {
+            self.cache.set_txs(txs);
+        }
+
+        Ok(summary)
+    }
 
-                let date = NaiveDate::from_ymd_opt(year_num, month_num, 1).unwrap();
+    pub fn get_summary_with_date(
+        &mut self,
+        date: NaiveDate,
+        nature: FetchNature,
+    ) -> RexResult<SummaryView> {
+        let (summary, txs) = self
+            .conn
+            .transaction::<(SummaryView, Option<HashMap<i32, Vec<FullTx>>>), RexError, _>(
+                |conn| {
+                    let mut db_conn = MutDbConn::new(conn, &self.cache);
 
-                get_summary(date, nature, &mut db_conn)
-            })?;
+                    Ok(get_summary(date, nature, &mut db_conn)?)
+                },
+            )?;
 
         if let Some(txs) = txs {
             self.cache.set_txs(txs);
@@ -277,23 +507,36 @@ impl DbConn {
         Ok(summary)
     }
 
+    pub fn get_chart_view_with_date(
+        &mut self,
+        date: NaiveDate,
+        nature: FetchNature,
+    ) -> RexResult<ChartView> {
+        let result = self.conn.transaction::<ChartView, RexError, _>(|conn| {
+            let mut db_conn = MutDbConn::new(conn, &self.cache);
+
+            let tx_view = get_txs(date, nature, &mut db_conn)?;
+
+            Ok(get_chart_view(tx_view, nature))
+        })?;
+
+        Ok(result)
+    }
+
     pub fn get_chart_view_with_str<'a>(
         &mut self,
         month: &'a str,
         year: &'a str,
         nature: FetchNature,
-    ) -> Result<ChartView> {
-        let result = self.conn.transaction::<ChartView, Error, _>(|conn| {
+    ) -> RexResult<ChartView> {
+        let result = self.conn.transaction::<ChartView, RexError, _>(|conn| {
             let mut db_conn = MutDbConn::new(conn, &self.cache);
 
-            let year_num = year.parse::<i32>().unwrap();
-            let month_num = month_name_to_num(month);
-
-            let date = NaiveDate::from_ymd_opt(year_num, month_num, 1).unwrap();
+            let date = month_year_to_date(month, year)?;
 
             let tx_view = get_txs(date, nature, &mut db_conn)?;
 
-            let chart_view = get_chart_view(tx_view);
+            let chart_view = get_chart_view(tx_view, nature);
 
             Ok(chart_view)
         })?;
@@ -301,18 +544,37 @@ impl DbConn {
         Ok(result)
     }
 
+    /// Projects the method balances forward from the given date
+    pub fn get_forecast_view(&mut self, date: NaiveDate) -> RexResult<ForecastView> {
+        let result = self.conn.transaction::<ForecastView, RexError, _>(|conn| {
+            let mut db_conn = MutDbConn::new(conn, &self.cache);
+
+            Ok(get_forecast_view(date, &mut db_conn)?)
+        })?;
+
+        Ok(result)
+    }
+
+    /// Month by month report of the given year
+    pub fn get_yearly_report(&mut self, year: i32) -> RexResult<YearlyReport> {
+        let result = self.conn.transaction::<YearlyReport, RexError, _>(|conn| {
+            let mut db_conn = MutDbConn::new(conn, &self.cache);
+
+            Ok(get_yearly_report(year, &mut db_conn)?)
+        })?;
+
+        Ok(result)
+    }
+
     pub fn get_activity_view_with_str<'a>(
         &mut self,
         month: &'a str,
         year: &'a str,
-    ) -> Result<ActivityView> {
-        let result = self.conn.transaction::<ActivityView, Error, _>(|conn| {
+    ) -> RexResult<ActivityView> {
+        let result = self.conn.transaction::<ActivityView, RexError, _>(|conn| {
             let mut db_conn = MutDbConn::new(conn, &self.cache);
 
-            let year_num = year.parse::<i32>().unwrap();
-            let month_num = month_name_to_num(month);
-
-            let date = NaiveDate::from_ymd_opt(year_num, month_num, 1).unwrap();
+            let date = month_year_to_date(month, year)?;
 
             let activity_view = get_activity_view(date, &mut db_conn)?;
 
@@ -327,25 +589,31 @@ impl DbConn {
         index_1: usize,
         index_2: usize,
         tx_view_group: &mut TxViewGroup,
-    ) -> Result<bool> {
-        let result = self.conn.transaction::<bool, Error, _>(|conn| {
+    ) -> RexResult<bool> {
+        let result = self.conn.transaction::<bool, RexError, _>(|conn| {
             let mut db_conn = MutDbConn::new(conn, &self.cache);
 
             let result = tx_view_group.switch_tx_index(index_1, index_2, &mut db_conn)?;
 
-            let tx_1 = tx_view_group.get_tx(index_1);
-            let tx_2 = tx_view_group.get_tx(index_2);
+            if result {
+                let tx_1 = tx_view_group.get_tx(index_1);
+                let tx_2 = tx_view_group.get_tx(index_2);
 
-            activity_swap_position(tx_1, tx_2, &mut db_conn)?;
+                activity_swap_position(tx_1, tx_2, &mut db_conn)?;
+            }
 
             Ok(result)
         })?;
 
+        if result {
+            self.changes += 1;
+        }
+
         Ok(result)
     }
 
-    pub fn rename_tx_method(&mut self, old_name: &str, new_name: &str) -> Result<()> {
-        let id = self.conn.transaction::<i32, Error, _>(|conn| {
+    pub fn rename_tx_method(&mut self, old_name: &str, new_name: &str) -> RexResult<()> {
+        let id = self.conn.transaction::<i32, RexError, _>(|conn| {
             let mut db_conn = MutDbConn::new(conn, &self.cache);
 
             let target_method = db_conn.cache().get_method_by_name(old_name)?.id;
@@ -358,10 +626,12 @@ impl DbConn {
         let method = self.cache.tx_methods.get_mut(&id).unwrap();
         method.name = new_name.to_string();
 
+        self.changes += 1;
+
         Ok(())
     }
 
-    pub fn set_new_tx_method_positions(&mut self, new_format: &[String]) -> Result<()> {
+    pub fn set_new_tx_method_positions(&mut self, new_format: &[String]) -> RexResult<()> {
         let mut new_method_positions = Vec::new();
 
         for (ongoing_position, method) in new_format.iter().enumerate() {
@@ -371,7 +641,7 @@ impl DbConn {
             new_method_positions.push(target_method);
         }
 
-        self.conn.transaction::<_, Error, _>(|conn| {
+        self.conn.transaction::<_, RexError, _>(|conn| {
             let mut db_conn = MutDbConn::new(conn, &self.cache);
 
             for method in &new_method_positions {
@@ -384,6 +654,8 @@ impl DbConn {
         self.cache.tx_methods.clear();
         self.cache.new_tx_methods(new_method_positions);
 
+        self.changes += 1;
+
         Ok(())
     }
 
@@ -414,14 +686,26 @@ impl DbConn {
         self.cache.tx_methods.is_empty()
     }
 
-    pub fn get_tx_method_by_name(&mut self, name: &str) -> Result<&TxMethod> {
-        self.cache.get_method_by_name(name)
+    pub fn get_tx_method_by_name(&mut self, name: &str) -> RexResult<&TxMethod> {
+        Ok(self.cache.get_method_by_name(name)?)
+    }
+
+    /// Returns the year of the earliest and the latest tx, if there are any txs
+    pub fn get_tx_year_range(&mut self) -> RexResult<Option<(i32, i32)>> {
+        Ok(Tx::get_year_range(self)?)
     }
 
-    pub fn get_final_balances(&mut self) -> Result<HashMap<i32, Balance>> {
+    pub fn get_final_balances(&mut self) -> RexResult<HashMap<i32, Balance>> {
         Ok(Balance::get_final_balance(self)?)
     }
 
+    /// Final balance of every method in the order of their position
+    pub fn get_final_balances_json(&mut self) -> RexResult<BalancesJson> {
+        let balances = self.get_final_balances()?;
+
+        Ok(BalancesJson::new(&balances, &self.get_tx_methods_sorted()))
+    }
+
     pub fn autofill(&mut self) -> Autofiller<'_> {
         let db_conn = MutDbConn::new(&mut self.conn, &self.cache);
         Autofiller::new(db_conn)
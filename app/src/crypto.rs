@@ -0,0 +1,235 @@
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use thiserror::Error;
+
+use crate::error::RexResult;
+
+/// Every encrypted DB file starts with this
+const MAGIC: &[u8; 8] = b"REXCRYPT";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + SALT_LEN + NONCE_LEN;
+
+/// Name of the decrypted DB inside the temporary directory
+const DB_NAME: &str = "rex.sqlite";
+
+#[derive(PartialEq, Debug, Error)]
+pub enum CryptoError {
+    #[error("Wrong passphrase or the encrypted database is damaged")]
+    WrongPassphrase,
+    #[error("{0} is not an encrypted Rex database")]
+    NotEncrypted(String),
+    #[error("Passphrase cannot be empty")]
+    EmptyPassphrase,
+    #[error("Failed to derive the encryption key. {0}")]
+    KeyDerivation(String),
+    #[error("Failed to encrypt the database")]
+    Encryption,
+    #[error("{0} is encrypted but the database is not")]
+    NoKey(String),
+}
+
+/// Key derived from the passphrase with Argon2. The salt is saved in the file header so the
+/// passphrase gives back the same key when decrypting
+struct DbKey {
+    key: Key,
+    salt: [u8; SALT_LEN],
+}
+
+impl DbKey {
+    fn new(passphrase: &str) -> Result<Self, CryptoError> {
+        let mut salt = [0; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+
+        Self::with_salt(passphrase, salt)
+    }
+
+    fn with_salt(passphrase: &str, salt: [u8; SALT_LEN]) -> Result<Self, CryptoError> {
+        if passphrase.is_empty() {
+            return Err(CryptoError::EmptyPassphrase);
+        }
+
+        let mut key = Key::default();
+
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| CryptoError::KeyDerivation(e.to_string()))?;
+
+        Ok(Self { key, salt })
+    }
+
+    /// Encrypts with a new nonce every time. Output is the header followed by the ciphertext
+    fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let encrypted = ChaCha20Poly1305::new(&self.key)
+            .encrypt(&nonce, data)
+            .map_err(|_| CryptoError::Encryption)?;
+
+        let mut output = Vec::with_capacity(HEADER_LEN + encrypted.len());
+        output.extend_from_slice(MAGIC);
+        output.extend_from_slice(&self.salt);
+        output.extend_from_slice(&nonce);
+        output.extend_from_slice(&encrypted);
+
+        Ok(output)
+    }
+}
+
+/// Splits an encrypted file into the salt, the nonce and the ciphertext
+fn split_header<'a>(
+    data: &'a [u8],
+    path: &Path,
+) -> Result<([u8; SALT_LEN], &'a Nonce, &'a [u8]), CryptoError> {
+    if data.len() < HEADER_LEN || !data.starts_with(MAGIC) {
+        return Err(CryptoError::NotEncrypted(path.display().to_string()));
+    }
+
+    let salt_end = MAGIC.len() + SALT_LEN;
+
+    let salt = data[MAGIC.len()..salt_end].try_into().unwrap();
+    let nonce = Nonce::from_slice(&data[salt_end..HEADER_LEN]);
+
+    Ok((salt, nonce, &data[HEADER_LEN..]))
+}
+
+fn decrypt_with(key: &DbKey, nonce: &Nonce, encrypted: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    ChaCha20Poly1305::new(&key.key)
+        .decrypt(nonce, encrypted)
+        .map_err(|_| CryptoError::WrongPassphrase)
+}
+
+fn decrypt(data: &[u8], passphrase: &str, path: &Path) -> Result<(Vec<u8>, DbKey), CryptoError> {
+    let (salt, nonce, encrypted) = split_header(data, path)?;
+
+    let key = DbKey::with_salt(passphrase, salt)?;
+    let plain = decrypt_with(&key, nonce, encrypted)?;
+
+    Ok((plain, key))
+}
+
+/// Writes to a temporary file next to the target and renames it so a failed write never
+/// leaves a half written DB behind
+fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+
+    fs::write(&temp_path, data)?;
+    fs::rename(&temp_path, path)
+}
+
+/// Path of the encrypted file of a DB. `rex.sqlite` becomes `rex.sqlite.enc`
+#[must_use]
+pub fn encrypted_path(db_path: &Path) -> PathBuf {
+    let mut path = db_path.as_os_str().to_owned();
+    path.push(".enc");
+
+    path.into()
+}
+
+/// Whether the file starts with the encrypted DB header
+#[must_use]
+pub fn is_encrypted(path: &Path) -> bool {
+    let mut magic = [0; MAGIC.len()];
+
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .is_ok_and(|()| &magic == MAGIC)
+}
+
+/// A decrypted copy of an encrypted DB kept in a private temporary directory. The DB is opened
+/// from [`EncryptedDb::db_path`] and written back encrypted with [`EncryptedDb::save`]. The
+/// decrypted copy is deleted once this is dropped
+pub struct EncryptedDb {
+    path: PathBuf,
+    key: DbKey,
+    temp_dir: TempDir,
+}
+
+impl EncryptedDb {
+    /// Decrypts the encrypted DB at `path`
+    pub fn open(path: &Path, passphrase: &str) -> RexResult<Self> {
+        let data = fs::read(path)?;
+        let (plain, key) = decrypt(&data, passphrase, path)?;
+
+        Self::with_plain(path, key, &plain)
+    }
+
+    /// Encrypts the plain DB at `db_path` and saves it to `path`. The plain DB is left as it is
+    pub fn create(db_path: &Path, path: &Path, passphrase: &str) -> RexResult<Self> {
+        let plain = fs::read(db_path)?;
+        let key = DbKey::new(passphrase)?;
+
+        let db = Self::with_plain(path, key, &plain)?;
+        db.save()?;
+
+        Ok(db)
+    }
+
+    fn with_plain(path: &Path, key: DbKey, plain: &[u8]) -> RexResult<Self> {
+        // Only the current user can access the directory
+        let temp_dir = tempfile::Builder::new().prefix("rex-").tempdir()?;
+
+        fs::write(temp_dir.path().join(DB_NAME), plain)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            key,
+            temp_dir,
+        })
+    }
+
+    /// Location of the decrypted DB to open the connection with
+    #[must_use]
+    pub fn db_path(&self) -> PathBuf {
+        self.temp_dir.path().join(DB_NAME)
+    }
+
+    /// Location of the encrypted DB
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Encrypts the decrypted copy back to the encrypted DB. Any connection to the decrypted
+    /// copy should be closed first
+    pub fn save(&self) -> RexResult<()> {
+        self.save_to(&self.path)
+    }
+
+    /// Writes the encrypted DB to another location such as a backup path
+    pub fn save_to(&self, target: &Path) -> RexResult<()> {
+        self.encrypt_file(&self.db_path(), target)
+    }
+
+    /// Encrypts another plain DB, such as an old backup, with the same passphrase
+    pub fn encrypt_file(&self, plain_path: &Path, target: &Path) -> RexResult<()> {
+        let plain = fs::read(plain_path)?;
+        let encrypted = self.key.encrypt(&plain)?;
+
+        write_atomic(target, &encrypted)?;
+
+        Ok(())
+    }
+
+    /// Decrypts another file that was encrypted along with this DB, such as a backup. Files
+    /// from an older encryption of the DB give [`CryptoError::WrongPassphrase`]
+    pub fn decrypt_file(&self, path: &Path, target: &Path) -> RexResult<()> {
+        let data = fs::read(path)?;
+        let (salt, nonce, encrypted) = split_header(&data, path)?;
+
+        if salt != self.key.salt {
+            return Err(CryptoError::WrongPassphrase.into());
+        }
+
+        let plain = decrypt_with(&self.key, nonce, encrypted)?;
+        write_atomic(target, &plain)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+use diesel::ConnectionError;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use rex_db::{InvalidValueError, NotFoundError, SetupError};
+use std::io;
+use thiserror::Error;
+
+use crate::crypto::CryptoError;
+use crate::ui_helper::{TxBuildError, VerifierError};
+
+pub type RexResult<T> = Result<T, RexError>;
+
+#[derive(Debug, Error)]
+pub enum RexError {
+    #[error("{0}")]
+    NotFound(#[from] NotFoundError),
+    #[error(transparent)]
+    Validation(#[from] ValidationError),
+    #[error("{0}")]
+    Integrity(#[from] IntegrityError),
+    #[error("Database is locked by another process")]
+    Locked,
+    #[error("Failed to open the database. {0}")]
+    Open(ConnectionError),
+    #[error("Migration: {0}")]
+    Migration(String),
+    #[error("Migration: {0}")]
+    OldDbMigration(String),
+    #[error("{0}")]
+    SchemaMismatch(String),
+    #[error("Backup: {0}")]
+    Backup(String),
+    #[error("Encryption: {0}")]
+    Crypto(#[from] CryptoError),
+    #[error("I/O: {0}")]
+    Io(#[from] io::Error),
+    #[error("Database: {0}")]
+    Database(DieselError),
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum ValidationError {
+    #[error(transparent)]
+    Verifier(#[from] VerifierError),
+    #[error(transparent)]
+    TxBuild(#[from] TxBuildError),
+}
+
+/// A write the DB refused because it would break one of the table constraints
+#[derive(PartialEq, Debug, Error)]
+pub enum IntegrityError {
+    #[error("Integrity: A record with the same value already exists. {0}")]
+    Unique(String),
+    #[error("Integrity: The record is linked to data that does not exist or is still in use. {0}")]
+    ForeignKey(String),
+    #[error("Integrity: A required value is missing. {0}")]
+    NotNull(String),
+    #[error("Integrity: A value was rejected by the database. {0}")]
+    Check(String),
+    #[error("Integrity: {0}. The database may be corrupted")]
+    InvalidValue(#[from] InvalidValueError),
+}
+
+impl From<InvalidValueError> for RexError {
+    fn from(error: InvalidValueError) -> Self {
+        Self::Integrity(error.into())
+    }
+}
+
+impl From<SetupError> for RexError {
+    fn from(error: SetupError) -> Self {
+        match error {
+            SetupError::Open(e) => Self::Open(e),
+            SetupError::Migration(e) => Self::Migration(e),
+            e @ SetupError::SchemaMismatch => Self::SchemaMismatch(e.to_string()),
+            SetupError::Query(e) => e.into(),
+            SetupError::Backup(e) => Self::Backup(e),
+        }
+    }
+}
+
+impl From<VerifierError> for RexError {
+    fn from(error: VerifierError) -> Self {
+        Self::Validation(error.into())
+    }
+}
+
+impl From<TxBuildError> for RexError {
+    fn from(error: TxBuildError) -> Self {
+        Self::Validation(error.into())
+    }
+}
+
+impl From<DieselError> for RexError {
+    fn from(error: DieselError) -> Self {
+        match error {
+            DieselError::NotFound => Self::NotFound(NotFoundError::Record),
+            DieselError::DeserializationError(e) => match e.downcast::<InvalidValueError>() {
+                Ok(e) => (*e).into(),
+                Err(e) => Self::Database(DieselError::DeserializationError(e)),
+            },
+            DieselError::DatabaseError(kind, info) => {
+                let message = info.message().to_string();
+
+                match kind {
+                    DatabaseErrorKind::UniqueViolation => IntegrityError::Unique(message).into(),
+                    DatabaseErrorKind::ForeignKeyViolation => {
+                        IntegrityError::ForeignKey(message).into()
+                    }
+                    DatabaseErrorKind::NotNullViolation => IntegrityError::NotNull(message).into(),
+                    DatabaseErrorKind::CheckViolation => IntegrityError::Check(message).into(),
+                    // SQLite reports SQLITE_BUSY and SQLITE_LOCKED with these messages
+                    _ if message.contains("database is locked")
+                        || message.contains("database table is locked") =>
+                    {
+                        Self::Locked
+                    }
+                    _ => Self::Database(DieselError::DatabaseError(kind, info)),
+                }
+            }
+            error => Self::Database(error),
+        }
+    }
+}
+
+/// The modifier and view functions return anyhow errors. Gets back the typed error that
+/// was bubbled up so callers can still match on it
+impl From<anyhow::Error> for RexError {
+    fn from(error: anyhow::Error) -> Self {
+        let error = match error.downcast::<RexError>() {
+            Ok(error) => return error,
+            Err(error) => error,
+        };
+
+        let error = match error.downcast::<DieselError>() {
+            Ok(error) => return error.into(),
+            Err(error) => error,
+        };
+
+        let error = match error.downcast::<NotFoundError>() {
+            Ok(error) => return error.into(),
+            Err(error) => error,
+        };
+
+        let error = match error.downcast::<InvalidValueError>() {
+            Ok(error) => return error.into(),
+            Err(error) => error,
+        };
+
+        let error = match error.downcast::<VerifierError>() {
+            Ok(error) => return error.into(),
+            Err(error) => error,
+        };
+
+        let error = match error.downcast::<TxBuildError>() {
+            Ok(error) => return error.into(),
+            Err(error) => error,
+        };
+
+        match error.downcast::<io::Error>() {
+            Ok(error) => error.into(),
+            Err(error) => Self::Other(error),
+        }
+    }
+}
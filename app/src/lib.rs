@@ -1,4 +1,7 @@
+pub mod backup;
 pub mod conn;
+pub mod crypto;
+pub mod error;
 pub mod migration;
 pub mod modifier;
 pub mod ui_helper;
@@ -12,6 +12,7 @@ use std::collections::{HashMap, HashSet};
 use std::io::Write;
 
 use crate::conn::{DbConn, MutDbConn};
+use crate::error::{RexError, RexResult};
 use crate::modifier::add_new_tx_methods;
 use crate::utils::parse_amount_nature_cent;
 
@@ -73,7 +74,12 @@ struct OldActivityTx {
     insertion_id: i32,
 }
 
-pub fn start_migration(mut old_db_conn: DbConn, db_conn: &mut DbConn) -> Result<()> {
+/// Moves every tx, method and activity of the old DB to the new one
+pub fn start_migration(old_db_conn: DbConn, db_conn: &mut DbConn) -> RexResult<()> {
+    migrate(old_db_conn, db_conn).map_err(|e| RexError::OldDbMigration(e.to_string()))
+}
+
+fn migrate(mut old_db_conn: DbConn, db_conn: &mut DbConn) -> Result<()> {
     let mut columns: Vec<ColumnInfo> = sql_query("PRAGMA table_info(balance_all);")
         .load(old_db_conn.conn())
         .expect("Failed to fetch column info");
@@ -99,7 +105,7 @@ pub fn start_migration(mut old_db_conn: DbConn, db_conn: &mut DbConn) -> Result<
     let new_methods = add_new_tx_methods(&tx_methods, db_conn)?;
     db_conn.cache.new_tx_methods(new_methods);
 
-    let DbConn { conn, cache } = db_conn;
+    let DbConn { conn, cache, .. } = db_conn;
 
     let mut start_date = None;
 
@@ -220,7 +226,7 @@ pub fn start_migration(mut old_db_conn: DbConn, db_conn: &mut DbConn) -> Result<
         "\nAll activity transactions migrated successfully. Incompatible old activity transactions are skipped"
     );
 
-    db_conn.reload_tags();
+    db_conn.reload_tags()?;
 
     count = 0;
 
@@ -311,7 +317,7 @@ fn migrate_tx(
 
     let mut balance_to_update = Vec::new();
 
-    match tx_type.into() {
+    match TxType::try_from(tx_type)? {
         TxType::Income => {
             let mut target_balance = current_balance.remove(&from_method).unwrap();
 
@@ -3,9 +3,11 @@ mod new_activity;
 mod new_method;
 mod new_tx;
 mod shared;
+mod tx_builder;
 
 pub(crate) use delete_tx::*;
 pub(crate) use new_activity::*;
 pub(crate) use new_method::*;
 pub(crate) use new_tx::*;
 pub use shared::*;
+pub use tx_builder::*;
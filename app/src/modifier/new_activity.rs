@@ -4,34 +4,23 @@ use rex_db::models::{
     ActivityNature, ActivityTxTag, FullTx, NewActivity, NewActivityTx, NewSearch, NewTx, Tag,
 };
 
-pub(crate) fn activity_new_tx(tx: &NewTx, tags: &str, conn: &mut impl ConnCache) -> Result<()> {
+pub(crate) fn activity_new_tx(
+    tx: &NewTx,
+    tag_list: &[String],
+    conn: &mut impl ConnCache,
+) -> Result<()> {
     let activity_type = ActivityNature::AddTx;
 
     let new_activity = NewActivity::new(activity_type).insert(conn)?;
     let added_tx = NewActivityTx::new_from_new_tx(tx, new_activity.id).insert(conn)?;
 
-    let mut tag_list = Vec::new();
-
-    if tags.is_empty() {
-        tag_list.push("Unknown".to_string());
-    } else {
-        let split_tags = tags.split(',').collect::<Vec<&str>>();
-
-        for tag in split_tags {
-            let trimmed_tag = tag.trim();
-            if !trimmed_tag.is_empty() {
-                tag_list.push(trimmed_tag.to_string());
-            }
-        }
-    }
-
     let mut tx_tags = Vec::new();
 
     for tag in tag_list {
-        let tag_id = if let Ok(id) = conn.cache().get_tag_id(&tag) {
+        let tag_id = if let Ok(id) = conn.cache().get_tag_id(tag) {
             id
         } else {
-            let tag = Tag::get_by_name(conn, &tag)?.ok_or(anyhow!(
+            let tag = Tag::get_by_name(conn, tag)?.ok_or(anyhow!(
                 "Tag not found but it should have been in the DB by this point"
             ))?;
             tag.id
@@ -67,7 +56,7 @@ pub(crate) fn activity_delete_tx(tx: &FullTx, conn: &mut impl ConnCache) -> Resu
 pub(crate) fn activity_edit_tx(
     old_tx: &FullTx,
     new_tx: &NewTx,
-    tags: &str,
+    new_tag_list: &[String],
     conn: &mut impl ConnCache,
 ) -> Result<()> {
     let activity_type = ActivityNature::EditTx;
@@ -80,33 +69,19 @@ pub(crate) fn activity_edit_tx(
         NewActivityTx::new_from_full_tx(old_tx, false, new_activity.id).insert(conn)?;
 
     let mut old_tag_list = Vec::new();
-    let mut new_tag_list = Vec::new();
 
     for tag in &old_tx.tags {
         let tag = ActivityTxTag::new(old_tx_activity.id, tag.id);
         old_tag_list.push(tag);
     }
 
-    if tags.is_empty() {
-        new_tag_list.push("Unknown".to_string());
-    } else {
-        let split_tags = tags.split(',').collect::<Vec<&str>>();
-
-        for tag in split_tags {
-            let trimmed_tag = tag.trim();
-            if !trimmed_tag.is_empty() {
-                new_tag_list.push(trimmed_tag.to_string());
-            }
-        }
-    }
-
     let mut new_tags = Vec::new();
 
     for tag in new_tag_list {
-        let tag_id = if let Ok(id) = conn.cache().get_tag_id(&tag) {
+        let tag_id = if let Ok(id) = conn.cache().get_tag_id(tag) {
             id
         } else {
-            let tag = Tag::get_by_name(conn, &tag)?.ok_or(anyhow!(
+            let tag = Tag::get_by_name(conn, tag)?.ok_or(anyhow!(
                 "Tag not found but it should have been in the DB by this point"
             ))?;
             tag.id
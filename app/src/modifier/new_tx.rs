@@ -6,7 +6,7 @@ use crate::modifier::tidy_balances;
 
 pub(crate) fn add_new_tx(
     tx: NewTx,
-    tags: &str,
+    tag_list: &[String],
     maintain_id: Option<i32>,
     db_conn: &mut impl ConnCache,
 ) -> Result<Vec<Tag>> {
@@ -15,20 +15,6 @@ pub(crate) fn add_new_tx(
     let to_method = tx.to_method;
     let amount = tx.amount;
     let tx_type = tx.tx_type;
-    let mut tag_list = Vec::new();
-
-    if tags.is_empty() {
-        tag_list.push("Unknown".to_string());
-    } else {
-        let split_tags = tags.split(',').collect::<Vec<&str>>();
-
-        for tag in split_tags {
-            let trimmed_tag = tag.trim();
-            if !trimmed_tag.is_empty() {
-                tag_list.push(trimmed_tag.to_string());
-            }
-        }
-    }
 
     let mut current_balance = Balance::get_balance_map(date.date(), db_conn)?;
 
@@ -38,7 +24,7 @@ pub(crate) fn add_new_tx(
 
     let mut final_balance_updates = Vec::new();
 
-    match tx_type.into() {
+    match TxType::try_from(tx_type)? {
         TxType::Income | TxType::Borrow | TxType::LendRepay => {
             let mut balance = current_balance.remove(&from_method).unwrap();
             let mut final_balance_entry = final_balance.get(&from_method).unwrap().clone();
@@ -93,14 +79,14 @@ pub(crate) fn add_new_tx(
 
     let mut tx_tags = Vec::new();
 
-    for (index, tag) in tag_list.into_iter().enumerate() {
-        if let Ok(tag_id) = db_conn.cache().get_tag_id(&tag) {
+    for (index, tag) in tag_list.iter().enumerate() {
+        if let Ok(tag_id) = db_conn.cache().get_tag_id(tag) {
             let tx_tag = TxTag::new(added_tx.id, tag_id, index == 0);
             tx_tags.push(tx_tag);
             continue;
         }
 
-        let tag_data = NewTag::new(&tag)
+        let tag_data = NewTag::new(tag)
             .insert(db_conn)
             .context("Failed on new tag")?;
 
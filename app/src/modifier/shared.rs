@@ -1,9 +1,10 @@
-use anyhow::{Result, anyhow};
-use chrono::{Days, Local, Months, NaiveDate, NaiveTime};
+use anyhow::Result;
+use chrono::{Days, Months, NaiveDate, NaiveDateTime, NaiveTime};
 use rex_db::ConnCache;
 use rex_db::models::{Balance, DateNature, FetchNature, NewSearch, NewTx, Tx, TxType};
 use rex_shared::models::{Dollar, LAST_POSSIBLE_TIME};
 
+use crate::ui_helper::{Field, VerifierError};
 use crate::utils::parse_amount_nature_cent;
 
 pub(crate) fn tidy_balances(date: NaiveDate, db_conn: &mut impl ConnCache) -> Result<()> {
@@ -45,7 +46,7 @@ fn tidy_recursive(
     let mut last_balance = Balance::get_last_balance(date, nature, db_conn)?;
 
     for tx in txs {
-        match tx.tx_type.as_str().into() {
+        match TxType::try_from(tx.tx_type.as_str())? {
             TxType::Income | TxType::Borrow | TxType::LendRepay => {
                 let method_id = tx.from_method;
                 *last_balance.get_mut(&method_id).unwrap() += tx.amount;
@@ -98,15 +99,18 @@ pub fn parse_tx_fields<'a>(
     tx_type: &'a str,
     db_conn: &impl ConnCache,
 ) -> Result<NewTx<'a>> {
-    let date = date.parse::<NaiveDate>()?;
-
-    let local_now = Local::now().naive_local();
-
-    let new_date = if date == local_now.date() {
-        local_now
-    } else {
-        date.and_time(NaiveTime::MIN)
-    };
+    // The date may come with an optional HH:MM time after it. Without one the tx is saved at
+    // midnight and marked as untimed
+    let (new_date, has_time) =
+        if let Ok(date_time) = NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M") {
+            (date_time, true)
+        } else {
+            let date = date
+                .parse::<NaiveDate>()
+                .map_err(|_| VerifierError::InvalidDate)?;
+
+            (date.and_time(NaiveTime::MIN), false)
+        };
 
     let details = if details.is_empty() {
         None
@@ -114,7 +118,10 @@ pub fn parse_tx_fields<'a>(
         Some(details)
     };
 
-    let amount = Dollar::new(amount.parse()?).cent().value();
+    let amount = amount
+        .parse()
+        .map_err(|_| VerifierError::ParsingError(Field::Amount))?;
+    let amount = Dollar::new(amount).cent().value();
 
     let from_method = db_conn.cache().get_method_id(from_method)?;
     let to_method = if to_method.is_empty() {
@@ -123,7 +130,8 @@ pub fn parse_tx_fields<'a>(
         Some(db_conn.cache().get_method_id(to_method)?)
     };
 
-    let new_tx = NewTx::new(new_date, details, from_method, to_method, amount, tx_type);
+    let new_tx =
+        NewTx::new(new_date, details, from_method, to_method, amount, tx_type).with_time(has_time);
     Ok(new_tx)
 }
 
@@ -144,14 +152,16 @@ pub fn parse_search_fields<'a>(
 
         match split_date.len() {
             1 => {
-                let year = split_date[0].parse::<i32>()?;
+                let year = split_date[0]
+                    .parse::<i32>()
+                    .map_err(|_| VerifierError::InvalidYear)?;
 
                 let start_date = NaiveDate::from_ymd_opt(year, 1, 1)
-                    .ok_or_else(|| anyhow!("{year} is an invalid year"))?
+                    .ok_or(VerifierError::InvalidYear)?
                     .and_time(NaiveTime::MIN);
 
                 let end_date = NaiveDate::from_ymd_opt(year + 1, 1, 1)
-                    .ok_or_else(|| anyhow!("{year} is an invalid year"))?
+                    .ok_or(VerifierError::InvalidYear)?
                     .and_time(LAST_POSSIBLE_TIME);
 
                 Some(DateNature::ByYear {
@@ -160,11 +170,15 @@ pub fn parse_search_fields<'a>(
                 })
             }
             2 => {
-                let year = split_date[0].parse::<i32>()?;
-                let month = split_date[1].parse::<u32>()?;
+                let year = split_date[0]
+                    .parse::<i32>()
+                    .map_err(|_| VerifierError::InvalidYear)?;
+                let month = split_date[1]
+                    .parse::<u32>()
+                    .map_err(|_| VerifierError::InvalidMonth)?;
 
                 let start_date = NaiveDate::from_ymd_opt(year, month, 1)
-                    .ok_or_else(|| anyhow!("{year} or {month} value is invalid"))?;
+                    .ok_or(VerifierError::NonExistingDate)?;
 
                 let end_date = start_date + Months::new(1) - Days::new(1);
 
@@ -177,7 +191,10 @@ pub fn parse_search_fields<'a>(
                 })
             }
             3 => {
-                let date = date.parse::<NaiveDate>()?.and_time(NaiveTime::MIN);
+                let date = date
+                    .parse::<NaiveDate>()
+                    .map_err(|_| VerifierError::InvalidDate)?
+                    .and_time(NaiveTime::MIN);
                 Some(DateNature::Exact(date))
             }
             _ => None,
@@ -0,0 +1,217 @@
+use anyhow::Result;
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use rex_db::ConnCache;
+use rex_db::models::{NewTx, TxType};
+use rex_shared::models::Cent;
+
+use crate::ui_helper::TxBuildError;
+
+/// Largest amount in cents the Verifier accepts, 10 digits before the decimal point
+const MAX_AMOUNT: i64 = 999_999_999_999;
+
+/// Typed alternative to [`crate::modifier::parse_tx_fields`] for building a tx in code.
+///
+/// ```ignore
+/// let tx = TxBuilder::expense(Cent::new(1250))
+///     .on(date)
+///     .from(method_id)
+///     .tags(["Food"]);
+///
+/// conn.add_tx(tx)?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct TxBuilder {
+    date: Option<NaiveDateTime>,
+    has_time: bool,
+    details: Option<String>,
+    from_method: Option<i32>,
+    to_method: Option<i32>,
+    amount: Cent,
+    tx_type: TxType,
+    tags: Vec<String>,
+}
+
+impl TxBuilder {
+    #[must_use]
+    pub fn new(tx_type: TxType, amount: Cent) -> Self {
+        Self {
+            date: None,
+            has_time: false,
+            details: None,
+            from_method: None,
+            to_method: None,
+            amount,
+            tx_type,
+            tags: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn income(amount: Cent) -> Self {
+        Self::new(TxType::Income, amount)
+    }
+
+    #[must_use]
+    pub fn expense(amount: Cent) -> Self {
+        Self::new(TxType::Expense, amount)
+    }
+
+    #[must_use]
+    pub fn transfer(amount: Cent) -> Self {
+        Self::new(TxType::Transfer, amount)
+    }
+
+    #[must_use]
+    pub fn borrow(amount: Cent) -> Self {
+        Self::new(TxType::Borrow, amount)
+    }
+
+    #[must_use]
+    pub fn lend(amount: Cent) -> Self {
+        Self::new(TxType::Lend, amount)
+    }
+
+    #[must_use]
+    pub fn borrow_repay(amount: Cent) -> Self {
+        Self::new(TxType::BorrowRepay, amount)
+    }
+
+    #[must_use]
+    pub fn lend_repay(amount: Cent) -> Self {
+        Self::new(TxType::LendRepay, amount)
+    }
+
+    /// Sets the date. The tx is saved at midnight, same as a tx without a time in the tx fields.
+    /// Without a date the tx is saved on today's date
+    #[must_use]
+    pub fn on(mut self, date: NaiveDate) -> Self {
+        self.date = Some(date.and_time(NaiveTime::MIN));
+        self.has_time = false;
+        self
+    }
+
+    /// Sets the exact date and time
+    #[must_use]
+    pub fn at(mut self, date: NaiveDateTime) -> Self {
+        self.date = Some(date);
+        self.has_time = true;
+        self
+    }
+
+    #[must_use]
+    pub fn details(mut self, details: impl Into<String>) -> Self {
+        let details = details.into().trim().to_string();
+
+        self.details = if details.is_empty() {
+            None
+        } else {
+            Some(details)
+        };
+        self
+    }
+
+    #[must_use]
+    pub fn from(mut self, method_id: i32) -> Self {
+        self.from_method = Some(method_id);
+        self
+    }
+
+    /// The method receiving the amount. Only used by Transfer txs
+    #[must_use]
+    pub fn to(mut self, method_id: i32) -> Self {
+        self.to_method = Some(method_id);
+        self
+    }
+
+    /// Sets the tags in order. Tags are trimmed, empty and repeated tags are skipped
+    /// and no tag at all saves the tx with the Unknown tag
+    #[must_use]
+    pub fn tags<T: Into<String>>(mut self, tags: impl IntoIterator<Item = T>) -> Self {
+        self.tags.clear();
+
+        for tag in tags {
+            let tag = tag.into().trim().to_string();
+
+            if !tag.is_empty() && !self.tags.contains(&tag) {
+                self.tags.push(tag);
+            }
+        }
+        self
+    }
+
+    /// Checks the tx with the same rules the Verifier applies on the tx fields and returns
+    /// the tx ready to be inserted
+    pub fn validate(&self, db_conn: &impl ConnCache) -> Result<NewTx<'_>, TxBuildError> {
+        let date = self
+            .date
+            .unwrap_or_else(|| Local::now().date_naive().and_time(NaiveTime::MIN));
+
+        if !(1000..=9999).contains(&date.year()) {
+            return Err(TxBuildError::InvalidYear);
+        }
+
+        if self.amount.value() <= 0 {
+            return Err(TxBuildError::AmountBelowZero);
+        }
+
+        if self.amount.value() > MAX_AMOUNT {
+            return Err(TxBuildError::AmountTooBig);
+        }
+
+        let from_method = self.from_method.ok_or(TxBuildError::EmptyMethod)?;
+        check_method(from_method, db_conn)?;
+
+        if matches!(self.tx_type, TxType::Transfer) {
+            let to_method = self.to_method.ok_or(TxBuildError::EmptyMethod)?;
+            check_method(to_method, db_conn)?;
+
+            if from_method == to_method {
+                return Err(TxBuildError::SameTxMethod);
+            }
+        } else if self.to_method.is_some() {
+            return Err(TxBuildError::UnexpectedToMethod);
+        }
+
+        Ok(NewTx::new(
+            date,
+            self.details.as_deref(),
+            from_method,
+            self.to_method,
+            self.amount.value(),
+            self.tx_type.into(),
+        )
+        .with_time(self.has_time))
+    }
+
+    pub(crate) fn tag_list(&self) -> Vec<String> {
+        if self.tags.is_empty() {
+            vec!["Unknown".to_string()]
+        } else {
+            self.tags.clone()
+        }
+    }
+}
+
+fn check_method(method_id: i32, db_conn: &impl ConnCache) -> Result<(), TxBuildError> {
+    if db_conn.cache().tx_methods.contains_key(&method_id) {
+        Ok(())
+    } else {
+        Err(TxBuildError::InvalidTxMethod(method_id))
+    }
+}
+
+/// Splits comma separated tags. No tag at all becomes the Unknown tag
+pub(crate) fn split_tags(tags: &str) -> Vec<String> {
+    let mut tag_list = tags
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(ToString::to_string)
+        .collect::<Vec<String>>();
+
+    if tag_list.is_empty() {
+        tag_list.push("Unknown".to_string());
+    }
+
+    tag_list
+}
@@ -0,0 +1,388 @@
+use rex_shared::models::Cent;
+
+use crate::ui_helper::VerifierError;
+
+/// Characters that are skipped entirely while reading an amount
+const IGNORED_CHARS: [char; 7] = [',', '_', '$', '€', '£', '¥', '৳'];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Number(f64),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    OpenParen,
+    CloseParen,
+    /// `b`, the balance of the selected tx method
+    Balance,
+    /// `k`, multiplies the value before it by a thousand
+    Thousand,
+    /// `m`, multiplies the value before it by a million
+    Million,
+}
+
+impl Token {
+    fn describe(self) -> String {
+        match self {
+            Token::Number(n) => format!("number {n}"),
+            Token::Plus => "'+'".to_string(),
+            Token::Minus => "'-'".to_string(),
+            Token::Star => "'*'".to_string(),
+            Token::Slash => "'/'".to_string(),
+            Token::Percent => "'%'".to_string(),
+            Token::OpenParen => "'('".to_string(),
+            Token::CloseParen => "')'".to_string(),
+            Token::Balance => "'b'".to_string(),
+            Token::Thousand => "'k'".to_string(),
+            Token::Million => "'m'".to_string(),
+        }
+    }
+}
+
+/// A token along with the 1-based char position where it starts in the user input
+type Spanned = (Token, usize);
+
+fn syntax_error(position: usize, reason: impl Into<String>) -> VerifierError {
+    VerifierError::InvalidAmount {
+        position,
+        reason: reason.into(),
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Spanned>, VerifierError> {
+    let chars = input.chars().collect::<Vec<char>>();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+
+    while index < chars.len() {
+        let c = chars[index];
+        let position = index + 1;
+
+        if c.is_whitespace() || IGNORED_CHARS.contains(&c) {
+            index += 1;
+            continue;
+        }
+
+        if c.is_ascii_digit() || c == '.' {
+            let mut number = String::new();
+
+            while index < chars.len() {
+                let current = chars[index];
+
+                if current.is_ascii_digit() || current == '.' {
+                    number.push(current);
+                } else if !IGNORED_CHARS.contains(&current) {
+                    break;
+                }
+
+                index += 1;
+            }
+
+            let value = number
+                .parse::<f64>()
+                .map_err(|_| syntax_error(position, format!("Invalid number '{number}'")))?;
+
+            tokens.push((Token::Number(value), position));
+            continue;
+        }
+
+        let token = match c.to_ascii_lowercase() {
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '%' => Token::Percent,
+            '(' => Token::OpenParen,
+            ')' => Token::CloseParen,
+            'b' => Token::Balance,
+            'k' => Token::Thousand,
+            'm' => Token::Million,
+            _ => return Err(syntax_error(position, format!("Unexpected '{c}'"))),
+        };
+
+        tokens.push((token, position));
+        index += 1;
+    }
+
+    Ok(tokens)
+}
+
+/// A parsed value. Percent values are kept separate so `200 - 15%` can be
+/// resolved as 15% of 200 rather than 0.15
+#[derive(Clone, Copy)]
+struct Value {
+    amount: f64,
+    percent: bool,
+}
+
+impl Value {
+    fn plain(amount: f64) -> Self {
+        Self {
+            amount,
+            percent: false,
+        }
+    }
+
+    /// Turns a percent value into its fractional form
+    fn resolve(self) -> f64 {
+        if self.percent {
+            self.amount / 100.0
+        } else {
+            self.amount
+        }
+    }
+}
+
+/// A recursive descent parser for the amount field.
+///
+/// ```text
+/// expr    := term (('+' | '-') term)*
+/// term    := unary (('*' | '/') unary)*
+/// unary   := ('+' | '-') unary | postfix
+/// postfix := primary ('k' | 'm' | '%')*
+/// primary := number | 'b' | '(' expr ')'
+/// ```
+struct Parser {
+    tokens: Vec<Spanned>,
+    index: usize,
+    end_position: usize,
+    balance: Option<Cent>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.index).map(|(t, _)| *t)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.index)
+            .map_or(self.end_position, |(_, p)| *p)
+    }
+
+    fn unexpected(&self) -> VerifierError {
+        match self.peek() {
+            Some(token) => {
+                syntax_error(self.position(), format!("Unexpected {}", token.describe()))
+            }
+            None => syntax_error(self.position(), "Unexpected end of input"),
+        }
+    }
+
+    fn expr(&mut self) -> Result<Value, VerifierError> {
+        let first = self.term()?;
+
+        if !matches!(self.peek(), Some(Token::Plus | Token::Minus)) {
+            return Ok(first);
+        }
+
+        let mut total = first.resolve();
+
+        while let Some(token @ (Token::Plus | Token::Minus)) = self.peek() {
+            self.index += 1;
+
+            let value = self.term()?;
+
+            // x + 10% adds 10% of x instead of 0.10
+            let change = if value.percent {
+                total * value.amount / 100.0
+            } else {
+                value.amount
+            };
+
+            if token == Token::Plus {
+                total += change;
+            } else {
+                total -= change;
+            }
+        }
+
+        Ok(Value::plain(total))
+    }
+
+    fn term(&mut self) -> Result<Value, VerifierError> {
+        let first = self.unary()?;
+
+        if !matches!(self.peek(), Some(Token::Star | Token::Slash)) {
+            return Ok(first);
+        }
+
+        let mut total = first.resolve();
+
+        while let Some(token @ (Token::Star | Token::Slash)) = self.peek() {
+            let position = self.position();
+            self.index += 1;
+
+            let value = self.unary()?.resolve();
+
+            if token == Token::Star {
+                total *= value;
+            } else {
+                if value == 0.0 {
+                    return Err(syntax_error(position, "Division by zero"));
+                }
+                total /= value;
+            }
+        }
+
+        Ok(Value::plain(total))
+    }
+
+    fn unary(&mut self) -> Result<Value, VerifierError> {
+        match self.peek() {
+            Some(Token::Minus) => {
+                self.index += 1;
+                let value = self.unary()?;
+                Ok(Value {
+                    amount: -value.amount,
+                    percent: value.percent,
+                })
+            }
+            Some(Token::Plus) => {
+                self.index += 1;
+                self.unary()
+            }
+            _ => self.postfix(),
+        }
+    }
+
+    fn postfix(&mut self) -> Result<Value, VerifierError> {
+        let mut value = Value::plain(self.primary()?);
+
+        loop {
+            match self.peek() {
+                Some(Token::Thousand) if !value.percent => value.amount *= 1_000.0,
+                Some(Token::Million) if !value.percent => value.amount *= 1_000_000.0,
+                Some(Token::Percent) if !value.percent => value.percent = true,
+                _ => break,
+            }
+            self.index += 1;
+        }
+
+        Ok(value)
+    }
+
+    fn primary(&mut self) -> Result<f64, VerifierError> {
+        match self.peek() {
+            Some(Token::Number(n)) => {
+                self.index += 1;
+                Ok(n)
+            }
+            Some(Token::Balance) => {
+                let position = self.position();
+                self.index += 1;
+
+                let Some(balance) = self.balance else {
+                    return Err(syntax_error(
+                        position,
+                        "Value of 'b' is unknown without a Tx Method",
+                    ));
+                };
+
+                Ok(balance.dollar().value())
+            }
+            Some(Token::OpenParen) => {
+                let position = self.position();
+                self.index += 1;
+
+                let value = self.expr()?.resolve();
+
+                if self.peek() != Some(Token::CloseParen) {
+                    if self.peek().is_none() {
+                        return Err(syntax_error(position, "Unclosed '('"));
+                    }
+                    return Err(self.unexpected());
+                }
+
+                self.index += 1;
+                Ok(value)
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+}
+
+/// Evaluates the amount expression entered by the user. `balance` is the value
+/// `b` gets replaced with, if any.
+pub(crate) fn evaluate_amount(input: &str, balance: Option<Cent>) -> Result<f64, VerifierError> {
+    let tokens = tokenize(input)?;
+
+    let mut parser = Parser {
+        tokens,
+        index: 0,
+        end_position: input.chars().count() + 1,
+        balance,
+    };
+
+    let value = parser.expr()?.resolve();
+
+    if parser.peek().is_some() {
+        return Err(parser.unexpected());
+    }
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(input: &str) -> f64 {
+        evaluate_amount(input, None).unwrap()
+    }
+
+    fn error_position(input: &str) -> usize {
+        match evaluate_amount(input, None) {
+            Err(VerifierError::InvalidAmount { position, .. }) => position,
+            other => panic!("expected a syntax error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_precedence_and_parentheses() {
+        assert_eq!(eval("1+2*3"), 7.0);
+        assert_eq!(eval("(1+2)*3"), 9.0);
+        assert_eq!(eval("10-4-3"), 3.0);
+        assert_eq!(eval("100/(2+3)"), 20.0);
+    }
+
+    #[test]
+    fn test_unary_minus() {
+        assert_eq!(eval("-5"), -5.0);
+        assert_eq!(eval("10*-2"), -20.0);
+        assert_eq!(eval("-(2+3)"), -5.0);
+    }
+
+    #[test]
+    fn test_percentages() {
+        assert_eq!(eval("200-15%"), 170.0);
+        assert_eq!(eval("200+10%"), 220.0);
+        assert_eq!(eval("200*15%"), 30.0);
+        assert_eq!(eval("50%"), 0.5);
+    }
+
+    #[test]
+    fn test_suffixes_and_balance() {
+        assert_eq!(eval("1.2k"), 1200.0);
+        assert_eq!(eval("2m"), 2_000_000.0);
+        assert_eq!(eval("(1+1)k"), 2000.0);
+        assert_eq!(
+            evaluate_amount("b/2", Some(Cent::new(10_000))).unwrap(),
+            50.0
+        );
+        assert!(evaluate_amount("b", None).is_err());
+    }
+
+    #[test]
+    fn test_error_positions() {
+        assert_eq!(error_position("1+"), 3);
+        assert_eq!(error_position("1 + * 2"), 5);
+        assert_eq!(error_position("(1+2"), 1);
+        assert_eq!(error_position("1k1"), 3);
+        assert_eq!(error_position("5/0"), 2);
+        assert_eq!(error_position("12a"), 3);
+        assert_eq!(error_position("1..2"), 1);
+    }
+}
@@ -0,0 +1,198 @@
+use rex_db::models::FullTx;
+use rex_shared::models::Cent;
+use std::collections::{HashMap, HashSet};
+
+/// A tag along with how confident the classifier is that it belongs to the tx
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagSuggestion {
+    pub name: String,
+    pub confidence: f64,
+}
+
+/// A multinomial naive Bayes classifier that ranks tags for a tx based on
+/// its details, the method used and the size of the amount. Trained locally
+/// from the existing txs and kept up to date as txs are added.
+#[derive(Default, Clone)]
+pub struct TagClassifier {
+    /// Total tx count trained on
+    total_txs: u32,
+    /// Tag name -> how many txs had this tag
+    tag_counts: HashMap<String, u32>,
+    /// Tag name -> token -> how many times the token appeared with the tag
+    token_counts: HashMap<String, HashMap<String, u32>>,
+    /// Tag name -> sum of all token appearances with the tag
+    token_totals: HashMap<String, u32>,
+    /// Every token ever seen
+    vocabulary: HashSet<String>,
+}
+
+impl TagClassifier {
+    pub(crate) fn from_txs(txs: &[FullTx]) -> Self {
+        let mut classifier = Self::default();
+
+        for tx in txs {
+            let tags = tx.tags.iter().map(|t| t.name.clone()).collect::<Vec<_>>();
+            classifier.learn(
+                tx.details.as_deref(),
+                &tx.from_method.name,
+                Some(tx.amount),
+                &tags,
+            );
+        }
+
+        classifier
+    }
+
+    /// Turns the tx fields into the tokens the classifier works with
+    fn features(details: Option<&str>, method: &str, amount: Option<Cent>) -> Vec<String> {
+        let mut tokens = Vec::new();
+
+        if let Some(details) = details {
+            for word in details
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|w| !w.is_empty())
+            {
+                tokens.push(format!("word:{}", word.to_lowercase()));
+            }
+        }
+
+        let method = method.trim();
+        if !method.is_empty() {
+            tokens.push(format!("method:{method}"));
+        }
+
+        // Bucket the amount by the number of digits in the whole dollar part
+        // so 5 and 8 look the same while 5 and 500 don't
+        if let Some(amount) = amount {
+            let whole = amount.value().abs() / 100;
+            tokens.push(format!("amount:{}", whole.to_string().len()));
+        }
+
+        tokens
+    }
+
+    pub(crate) fn learn(
+        &mut self,
+        details: Option<&str>,
+        method: &str,
+        amount: Option<Cent>,
+        tags: &[String],
+    ) {
+        self.update(details, method, amount, tags, true);
+    }
+
+    pub(crate) fn forget(
+        &mut self,
+        details: Option<&str>,
+        method: &str,
+        amount: Option<Cent>,
+        tags: &[String],
+    ) {
+        self.update(details, method, amount, tags, false);
+    }
+
+    fn update(
+        &mut self,
+        details: Option<&str>,
+        method: &str,
+        amount: Option<Cent>,
+        tags: &[String],
+        add: bool,
+    ) {
+        if tags.is_empty() {
+            return;
+        }
+
+        let tokens = Self::features(details, method, amount);
+
+        let apply = |value: &mut u32, by: u32| {
+            if add {
+                *value += by;
+            } else {
+                *value = value.saturating_sub(by);
+            }
+        };
+
+        apply(&mut self.total_txs, 1);
+
+        for tag in tags {
+            apply(self.tag_counts.entry(tag.clone()).or_default(), 1);
+            apply(
+                self.token_totals.entry(tag.clone()).or_default(),
+                tokens.len() as u32,
+            );
+
+            let tag_tokens = self.token_counts.entry(tag.clone()).or_default();
+
+            for token in &tokens {
+                apply(tag_tokens.entry(token.clone()).or_default(), 1);
+            }
+
+            if self.tag_counts[tag] == 0 {
+                self.tag_counts.remove(tag);
+                self.token_totals.remove(tag);
+                self.token_counts.remove(tag);
+            }
+        }
+
+        if add {
+            self.vocabulary.extend(tokens);
+        }
+    }
+
+    /// Ranks all known tags for the given fields, highest confidence first.
+    /// Confidences across all tags add up to 1.
+    #[must_use]
+    pub fn rank(
+        &self,
+        details: Option<&str>,
+        method: &str,
+        amount: Option<Cent>,
+    ) -> Vec<TagSuggestion> {
+        let tokens = Self::features(details, method, amount);
+
+        if self.total_txs == 0 || tokens.is_empty() {
+            return Vec::new();
+        }
+
+        let vocabulary_size = self.vocabulary.len().max(1) as f64;
+
+        // Log probabilities with Laplace smoothing so unseen tokens don't zero out a tag
+        let mut scores = self
+            .tag_counts
+            .iter()
+            .map(|(tag, count)| {
+                let prior = f64::from(*count) / f64::from(self.total_txs);
+                let total = f64::from(self.token_totals[tag]);
+                let tag_tokens = &self.token_counts[tag];
+
+                let likelihood: f64 = tokens
+                    .iter()
+                    .map(|token| {
+                        let seen = f64::from(tag_tokens.get(token).copied().unwrap_or(0));
+                        ((seen + 1.0) / (total + vocabulary_size)).ln()
+                    })
+                    .sum();
+
+                (tag.clone(), prior.ln() + likelihood)
+            })
+            .collect::<Vec<(String, f64)>>();
+
+        let Some(highest) = scores.iter().map(|(_, s)| *s).reduce(f64::max) else {
+            return Vec::new();
+        };
+
+        let normalizer: f64 = scores.iter().map(|(_, s)| (s - highest).exp()).sum();
+
+        for (_, score) in &mut scores {
+            *score = (*score - highest).exp() / normalizer;
+        }
+
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        scores
+            .into_iter()
+            .map(|(name, confidence)| TagSuggestion { name, confidence })
+            .collect()
+    }
+}
@@ -0,0 +1,235 @@
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+
+use crate::ui_helper::DateType;
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    let weekday = match value {
+        "mon" | "monday" => Weekday::Mon,
+        "tue" | "tues" | "tuesday" => Weekday::Tue,
+        "wed" | "wednesday" => Weekday::Wed,
+        "thu" | "thur" | "thurs" | "thursday" => Weekday::Thu,
+        "fri" | "friday" => Weekday::Fri,
+        "sat" | "saturday" => Weekday::Sat,
+        "sun" | "sunday" => Weekday::Sun,
+        _ => return None,
+    };
+
+    Some(weekday)
+}
+
+/// Parses relative offsets like `-3d`, `+1w`, `-2m` and `+1y`
+fn parse_offset(value: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let mut chars = value.chars();
+
+    let sign = chars.next()?;
+    let unit = chars.next_back()?;
+
+    let amount = chars.as_str().parse::<u32>().ok()?;
+
+    let forward = match sign {
+        '+' => true,
+        '-' => false,
+        _ => return None,
+    };
+
+    match unit {
+        'd' | 'w' => {
+            let days = Days::new(if unit == 'w' {
+                u64::from(amount) * 7
+            } else {
+                u64::from(amount)
+            });
+
+            if forward {
+                today.checked_add_days(days)
+            } else {
+                today.checked_sub_days(days)
+            }
+        }
+        'm' | 'y' => {
+            let months = Months::new(if unit == 'y' { amount * 12 } else { amount });
+
+            if forward {
+                today.checked_add_months(months)
+            } else {
+                today.checked_sub_months(months)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Resolves a weekday relative to today. `last` goes strictly before today,
+/// `next` strictly after and a bare weekday picks the latest one including today.
+fn resolve_weekday(value: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let (direction, day) = match value.split_once(' ') {
+        Some((direction, day)) => (Some(direction), day.trim()),
+        None => (None, value),
+    };
+
+    let target = parse_weekday(day)?;
+
+    let today_num = today.weekday().num_days_from_monday();
+    let target_num = target.num_days_from_monday();
+
+    match direction {
+        Some("last") => {
+            let gap = (today_num + 7 - target_num) % 7;
+            let gap = if gap == 0 { 7 } else { gap };
+            today.checked_sub_days(Days::new(u64::from(gap)))
+        }
+        Some("next") => {
+            let gap = (target_num + 7 - today_num) % 7;
+            let gap = if gap == 0 { 7 } else { gap };
+            today.checked_add_days(Days::new(u64::from(gap)))
+        }
+        None => {
+            let gap = (today_num + 7 - target_num) % 7;
+            today.checked_sub_days(Days::new(u64::from(gap)))
+        }
+        _ => None,
+    }
+}
+
+fn format_date(date: NaiveDate, date_type: DateType) -> String {
+    match date_type {
+        DateType::Exact => date.format("%Y-%m-%d").to_string(),
+        DateType::Monthly => date.format("%Y-%m").to_string(),
+        DateType::Yearly => date.format("%Y").to_string(),
+    }
+}
+
+/// Turns shorthand and natural language dates into the regular date format of
+/// the given date type. Returns None if the input is not a recognized shorthand
+/// so it can go through the regular date verification.
+///
+/// Supported values:
+///
+/// - `today`, `yesterday`, `tomorrow`
+/// - `-3d`, `+1w`, `-2m`, `+1y`
+/// - `friday`, `last friday`, `next fri`
+/// - `15` as the day of the current month, on exact dates
+/// - `03-15` as the month and day of the current year, on exact dates
+/// - `3` as the month of the current year, on monthly dates
+pub(crate) fn resolve_date_shorthand(
+    user_date: &str,
+    today: NaiveDate,
+    date_type: DateType,
+) -> Option<String> {
+    let value = user_date
+        .trim()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+
+    let resolved = match value.as_str() {
+        "today" | "now" => Some(today),
+        "yesterday" => today.pred_opt(),
+        "tomorrow" => today.succ_opt(),
+        _ => None,
+    };
+
+    let resolved = resolved
+        .or_else(|| parse_offset(&value, today))
+        .or_else(|| resolve_weekday(&value, today));
+
+    if let Some(date) = resolved {
+        return Some(format_date(date, date_type));
+    }
+
+    let is_short_num =
+        |v: &str| !v.is_empty() && v.len() <= 2 && v.chars().all(|c| c.is_ascii_digit());
+
+    match date_type {
+        DateType::Exact => {
+            if is_short_num(&value) {
+                return Some(format!("{}-{:02}-{value:0>2}", today.year(), today.month()));
+            }
+
+            if let Some((month, day)) = value.split_once('-')
+                && is_short_num(month)
+                && is_short_num(day)
+            {
+                return Some(format!("{}-{month:0>2}-{day:0>2}", today.year()));
+            }
+
+            None
+        }
+        DateType::Monthly => {
+            if is_short_num(&value) {
+                return Some(format!("{}-{value:0>2}", today.year()));
+            }
+            None
+        }
+        DateType::Yearly => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A Wednesday
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, 19).unwrap()
+    }
+
+    fn exact(value: &str) -> Option<String> {
+        resolve_date_shorthand(value, today(), DateType::Exact)
+    }
+
+    #[test]
+    fn test_keywords() {
+        assert_eq!(exact("today").unwrap(), "2025-03-19");
+        assert_eq!(exact(" Yesterday ").unwrap(), "2025-03-18");
+        assert_eq!(exact("tomorrow").unwrap(), "2025-03-20");
+    }
+
+    #[test]
+    fn test_offsets() {
+        assert_eq!(exact("-3d").unwrap(), "2025-03-16");
+        assert_eq!(exact("+1w").unwrap(), "2025-03-26");
+        assert_eq!(exact("-1m").unwrap(), "2025-02-19");
+        assert_eq!(exact("+1y").unwrap(), "2026-03-19");
+        assert_eq!(exact("3d"), None);
+    }
+
+    #[test]
+    fn test_weekdays() {
+        assert_eq!(exact("last friday").unwrap(), "2025-03-14");
+        assert_eq!(exact("last wednesday").unwrap(), "2025-03-12");
+        assert_eq!(exact("next wed").unwrap(), "2025-03-26");
+        assert_eq!(exact("wednesday").unwrap(), "2025-03-19");
+        assert_eq!(exact("monday").unwrap(), "2025-03-17");
+    }
+
+    #[test]
+    fn test_partial_dates() {
+        assert_eq!(exact("15").unwrap(), "2025-03-15");
+        assert_eq!(exact("5").unwrap(), "2025-03-05");
+        assert_eq!(exact("03-15").unwrap(), "2025-03-15");
+        assert_eq!(exact("3-5").unwrap(), "2025-03-05");
+        assert_eq!(exact("2024-03-15"), None);
+    }
+
+    #[test]
+    fn test_other_date_types() {
+        assert_eq!(
+            resolve_date_shorthand("-1m", today(), DateType::Monthly).unwrap(),
+            "2025-02"
+        );
+        assert_eq!(
+            resolve_date_shorthand("7", today(), DateType::Monthly).unwrap(),
+            "2025-07"
+        );
+        assert_eq!(
+            resolve_date_shorthand("-1y", today(), DateType::Yearly).unwrap(),
+            "2024"
+        );
+        assert_eq!(
+            resolve_date_shorthand("2024", today(), DateType::Yearly),
+            None
+        );
+    }
+}
@@ -1,10 +1,14 @@
+mod amount_parser;
 mod autofiller;
+mod classifier;
+mod date_parser;
 mod outputs;
 mod shared;
 mod stepper;
 mod verifier;
 
 pub use autofiller::*;
+pub use classifier::*;
 pub use outputs::*;
 pub use shared::*;
 pub use stepper::*;
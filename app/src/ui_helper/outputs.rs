@@ -19,6 +19,8 @@ impl fmt::Display for Output {
 pub enum Field {
     #[error("Date")]
     Date,
+    #[error("Time")]
+    Time,
     #[error("Tx Method")]
     TxMethod,
     #[error("Amount")]
@@ -45,8 +47,14 @@ pub enum VerifierError {
     DayTooBig,
     #[error("Date: Date not acceptable and possibly non-existing")]
     NonExistingDate,
+    #[error("Time: Time not acceptable. Example Time: 14:30")]
+    InvalidTime,
     #[error("Amount: Value must be bigger than zero")]
     AmountBelowZero,
+    #[error("Amount: Value is too big")]
+    AmountTooBig,
+    #[error("Amount: {reason} at position {position}")]
+    InvalidAmount { position: usize, reason: String },
     #[error("TX Method: Transaction Method not found")]
     InvalidTxMethod,
     #[error(
@@ -63,6 +71,24 @@ pub enum VerifierError {
     Others(String),
 }
 
+#[derive(PartialEq, Debug, Error)]
+pub enum TxBuildError {
+    #[error("Date: Year must have 4 digits. Example Date: 2022-05-01")]
+    InvalidYear,
+    #[error("Amount: Value must be bigger than zero")]
+    AmountBelowZero,
+    #[error("Amount: Value cannot have more than 10 digits before the decimal point")]
+    AmountTooBig,
+    #[error("TX Method: TX Method cannot be empty")]
+    EmptyMethod,
+    #[error("TX Method: Transaction Method with id {0} not found")]
+    InvalidTxMethod(i32),
+    #[error("TX Method: To method can only be set for Transfer")]
+    UnexpectedToMethod,
+    #[error("TX Method: From and To methods cannot be the same for Transfer")]
+    SameTxMethod,
+}
+
 #[derive(Debug, Error)]
 pub enum SteppingError {
     #[error("Date: Failed to step due to invalid date format")]
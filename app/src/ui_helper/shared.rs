@@ -23,6 +23,15 @@ pub enum StepType {
     StepDown,
 }
 
+/// How far an exact date moves in a single step
+#[derive(Copy, Clone, Default)]
+pub enum DateStep {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
 #[derive(Copy, Clone)]
 pub enum DateType {
     Exact,
@@ -1,11 +1,12 @@
-use chrono::{Duration, Months, NaiveDate};
+use chrono::{Duration, Local, Months, NaiveDate};
 use rex_db::ConnCache;
 use rex_db::models::TxType;
+use rex_shared::models::Cent;
 use strum::IntoEnumIterator;
 
 use crate::conn::MutDbConn;
 use crate::ui_helper::{
-    DateType, Field, Output, StepType, SteppingError, VerifierError, get_best_match,
+    DateStep, DateType, Field, Output, StepType, SteppingError, VerifierError, get_best_match,
 };
 
 pub struct Stepper<'a> {
@@ -18,30 +19,54 @@ impl<'a> Stepper<'a> {
     }
 
     pub fn date(
+        self,
+        user_date: &mut String,
+        step_type: StepType,
+        date_type: DateType,
+    ) -> Result<(), SteppingError> {
+        self.date_by(user_date, step_type, date_type, DateStep::Day)
+    }
+
+    /// Same as [`Stepper::date`] but exact dates move by the given step size
+    pub fn date_by(
         mut self,
         user_date: &mut String,
         step_type: StepType,
         date_type: DateType,
+        date_step: DateStep,
     ) -> Result<(), SteppingError> {
         let verify_status = self.conn.verify().date(user_date, date_type);
 
         match verify_status {
             Ok(data) => match data {
-                Output::Nothing(_) => match date_type {
-                    DateType::Exact => *user_date = String::from("2022-01-01"),
-                    DateType::Monthly => *user_date = String::from("2022-01"),
-                    DateType::Yearly => *user_date = String::from("2022"),
-                },
+                Output::Nothing(_) => {
+                    let today = Local::now().date_naive();
+
+                    *user_date = match date_type {
+                        DateType::Exact => today.format("%Y-%m-%d").to_string(),
+                        DateType::Monthly => today.format("%Y-%m").to_string(),
+                        DateType::Yearly => today.format("%Y").to_string(),
+                    };
+                }
                 Output::Accepted(_) => match date_type {
                     DateType::Exact => {
                         let mut current_date =
                             NaiveDate::parse_from_str(user_date, "%Y-%m-%d").unwrap();
-                        match step_type {
-                            StepType::StepUp => {
-                                current_date += Duration::days(1);
+
+                        let days = match date_step {
+                            DateStep::Day => Some(Duration::days(1)),
+                            DateStep::Week => Some(Duration::weeks(1)),
+                            DateStep::Month => None,
+                        };
+
+                        match (step_type, days) {
+                            (StepType::StepUp, Some(days)) => current_date += days,
+                            (StepType::StepDown, Some(days)) => current_date -= days,
+                            (StepType::StepUp, None) => {
+                                current_date = current_date + Months::new(1)
                             }
-                            StepType::StepDown => {
-                                current_date -= Duration::days(1);
+                            (StepType::StepDown, None) => {
+                                current_date = current_date - Months::new(1)
                             }
                         }
                         *user_date = current_date.to_string();
@@ -69,7 +94,7 @@ impl<'a> Stepper<'a> {
                         *user_date = current_date.format("%Y-%m").to_string();
                     }
                     DateType::Yearly => {
-                        let mut int_year: u16 = user_date.parse().unwrap();
+                        let mut int_year: i32 = user_date.parse().unwrap();
                         match step_type {
                             StepType::StepUp => {
                                 int_year += 1;
@@ -133,11 +158,21 @@ impl<'a> Stepper<'a> {
     }
 
     pub fn amount(
+        self,
+        user_amount: &mut String,
+        step_type: StepType,
+    ) -> Result<(), SteppingError> {
+        self.amount_with_balance(user_amount, step_type, None)
+    }
+
+    /// Same as [`Stepper::amount`] but `b` in the amount is replaced with the given balance
+    pub fn amount_with_balance(
         mut self,
         user_amount: &mut String,
         step_type: StepType,
+        balance: Option<Cent>,
     ) -> Result<(), SteppingError> {
-        let verify_status = self.conn.verify().amount(user_amount);
+        let verify_status = self.conn.verify().amount_with_balance(user_amount, balance);
 
         match verify_status {
             Ok(data) => match data {
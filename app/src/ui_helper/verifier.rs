@@ -1,12 +1,15 @@
 use std::cmp::Ordering;
 use std::collections::HashSet;
 
-use chrono::NaiveDate;
+use chrono::{Datelike, Local, NaiveDate};
 use rex_db::ConnCache;
 use rex_db::models::TxType;
+use rex_shared::models::Cent;
 use strum::IntoEnumIterator;
 
 use crate::conn::MutDbConn;
+use crate::ui_helper::amount_parser::evaluate_amount;
+use crate::ui_helper::date_parser::resolve_date_shorthand;
 use crate::ui_helper::{DateType, Field, Output, VerifierError, get_best_match};
 
 pub struct Verifier<'a> {
@@ -18,9 +21,10 @@ impl<'a> Verifier<'a> {
         Self { conn }
     }
 
-    /// Checks if:
+    /// Resolves shorthand dates like `today`, `-3d` or `last friday` first,
+    /// then checks if:
     ///
-    /// - The inputted year is between 2022 to 2037.
+    /// - The inputted year has 4 digits.
     /// - The inputted month is between 01 to 12.
     /// - The inputted date is between 01 to 31.
     /// - The inputted date is empty.
@@ -42,6 +46,12 @@ impl<'a> Verifier<'a> {
             return Ok(Output::Nothing(Field::Date));
         }
 
+        if let Some(resolved) =
+            resolve_date_shorthand(user_date, Local::now().date_naive(), date_type)
+        {
+            *user_date = resolved;
+        }
+
         *user_date = user_date
             .chars()
             .filter(|c| c.is_numeric() || *c == '-')
@@ -52,23 +62,26 @@ impl<'a> Verifier<'a> {
             .map(ToString::to_string)
             .collect::<Vec<String>>();
 
+        // Invalid parts of the date get replaced with the current year
+        let current_year = Local::now().year();
+
         // If one part of the date is missing/extra, return unknown date
         match date_type {
             DateType::Exact => {
                 if split_date.len() != 3 {
-                    *user_date = "2022-01-01".to_string();
+                    *user_date = format!("{current_year}-01-01");
                     return Err(VerifierError::InvalidDate);
                 }
             }
             DateType::Monthly => {
                 if split_date.len() != 2 {
-                    *user_date = "2022-01".to_string();
+                    *user_date = format!("{current_year}-01");
                     return Err(VerifierError::InvalidDate);
                 }
             }
             DateType::Yearly => {
                 if split_date.len() != 1 {
-                    *user_date = "2022".to_string();
+                    *user_date = current_year.to_string();
                     return Err(VerifierError::InvalidDate);
                 }
             }
@@ -96,16 +109,16 @@ impl<'a> Verifier<'a> {
             DateType::Yearly => (None, None),
         };
 
-        // Checks if the year part length is 4. If not 4, turn the year to 2022 + the other character entered by the user
+        // Checks if the year part length is 4. If not 4, turn the year to the current year + the other character entered by the user
         // and return the new date
         if split_date[0].len() != 4 {
             match split_date[0].len().cmp(&4) {
                 Ordering::Less => match date_type {
                     DateType::Exact => {
-                        *user_date = format!("2022-{}-{}", split_date[1], split_date[2]);
+                        *user_date = format!("{current_year}-{}-{}", split_date[1], split_date[2]);
                     }
-                    DateType::Monthly => *user_date = format!("2022-{}", split_date[1]),
-                    DateType::Yearly => *user_date = "2022".to_string(),
+                    DateType::Monthly => *user_date = format!("{current_year}-{}", split_date[1]),
+                    DateType::Yearly => *user_date = current_year.to_string(),
                 },
                 Ordering::Greater => match date_type {
                     DateType::Exact => {
@@ -227,168 +240,127 @@ impl<'a> Verifier<'a> {
 
     /// Checks if:
     ///
-    /// - Amount is empty
-    /// - Amount is zero or below
-    /// - Amount text contains a calculation symbol
-    /// - contains any extra spaces
-    /// - removes any extra spaces and non-numeric characters
+    /// - Time is empty
+    /// - Time is in `HH:MM`, `HHMM`, `H:MM` or `H` format with an optional am/pm suffix
+    /// - Hour is between 00 to 23 and minute between 00 to 59
     ///
-    /// If the value is not float, tries to make it float ending with double zero
-    pub fn amount(&self, user_amount: &mut String) -> Result<Output, VerifierError> {
-        // Cancel all verification if the amount is empty
-        if user_amount.is_empty() {
-            return Ok(Output::Nothing(Field::Amount));
+    /// Accepted times are turned into the `HH:MM` 24 hour format.
+    pub fn time(&self, user_time: &mut String) -> Result<Output, VerifierError> {
+        let mut time = user_time.trim().to_lowercase().replace(' ', "");
+
+        if time.is_empty() {
+            user_time.clear();
+            return Ok(Output::Nothing(Field::Time));
         }
 
-        let calc_symbols = vec!['*', '/', '+', '-'];
+        // Everything below slices by byte
+        if !time.is_ascii() {
+            return Err(VerifierError::InvalidTime);
+        }
 
-        *user_amount = user_amount
-            .chars()
-            .filter(|c| c.is_numeric() || *c == '.' || calc_symbols.contains(c))
-            .collect();
+        let meridiem = if let Some(stripped) = time.strip_suffix("am") {
+            time = stripped.to_string();
+            Some(false)
+        } else if let Some(stripped) = time.strip_suffix("pm") {
+            time = stripped.to_string();
+            Some(true)
+        } else {
+            None
+        };
+
+        let (hour, minute) = match time.split_once(':') {
+            Some((hour, minute)) => (hour.to_string(), minute.to_string()),
+            None if time.len() <= 2 => (time.clone(), "00".to_string()),
+            None if time.len() <= 4 => {
+                let (hour, minute) = time.split_at(time.len() - 2);
+                (hour.to_string(), minute.to_string())
+            }
+            None => return Err(VerifierError::InvalidTime),
+        };
 
-        // Already checked if the initial amount is empty.
-        // If it becomes empty after the filtering was done, there no number inside so return error
-        if user_amount.is_empty() {
-            return Err(VerifierError::ParsingError(Field::Amount));
+        let is_valid_part = |part: &str| {
+            !part.is_empty() && part.len() <= 2 && part.chars().all(|c| c.is_ascii_digit())
+        };
+
+        if !is_valid_part(&hour) || minute.len() != 2 || !is_valid_part(&minute) {
+            return Err(VerifierError::InvalidTime);
         }
 
-        // Check if any of the symbols are present
-        if calc_symbols.iter().any(|s| user_amount.contains(*s)) {
-            // How it works:
-            // The calc_symbol intentionally starts with * and / so these calculations are done first.
-            // Start a main loop which will only run for the amount of times anyone of them from calc_symbols is present.
-            // Loop over the symbols and check if the symbol is present in the string
-            // find the index of where the symbol is then take the number values from both side of the symbol.
-            // Example: 1+5*10. We start with *, we initially, we will work with 5*10.
-            // Isolate the numbers => do the calculation => replace the part of the string we are working with, with the result which is 50
-            // result: 1+50 => break the symbol checking loop and continue the main loop again so we start working with 1+50.
-
-            // Get the amount of time the symbols were found in the amount string
-            let count = user_amount
-                .chars()
-                .filter(|c| calc_symbols.contains(c))
-                .count();
-
-            // Remove all spaces for easier indexing
-            let mut working_value = user_amount.to_owned();
-
-            for _i in 0..count {
-                for symbol in &calc_symbols {
-                    if let Some(location) = working_value.find(*symbol) {
-                        // If a symbol is found, we want to store the values to its side to these variables.
-                        // Example: 1+5 first_value = 1 last_value = 5
-                        let mut first_value = String::new();
-                        let mut last_value = String::new();
-
-                        // Skip to symbol location + 1 index value and start taking chars from here until the end
-                        // of the string or until another cal symbol is encountered
-                        for char in working_value.chars().skip(location + 1) {
-                            if calc_symbols.contains(&char) {
-                                break;
-                            }
-                            last_value.push(char);
-                        }
-
-                        // Do the same thing as before but this time, reverse the string
-                        for char in working_value
-                            .chars()
-                            .rev()
-                            .skip(working_value.len() - location)
-                        {
-                            if calc_symbols.contains(&char) {
-                                break;
-                            }
-                            first_value.push(char);
-                        }
-                        // Un-reverse the string
-                        first_value = first_value.chars().rev().collect();
-
-                        // If either of them is empty, the one that is not empty is the value we want to use for using in replacement
-                        let final_value = if first_value.is_empty() || last_value.is_empty() {
-                            if first_value.is_empty() {
-                                last_value.clone()
-                            } else {
-                                first_value.clone()
-                            }
-                        } else {
-                            // If both value is intact, do the calculation and the result is for replacement
-                            let first_num: f64 = match first_value.parse() {
-                                Ok(v) => v,
-                                Err(_) => {
-                                    return Err(VerifierError::ParsingError(Field::Amount));
-                                }
-                            };
-
-                            let last_num: f64 = match last_value.parse() {
-                                Ok(v) => v,
-                                Err(_) => {
-                                    return Err(VerifierError::ParsingError(Field::Amount));
-                                }
-                            };
-
-                            match *symbol {
-                                '*' => format!("{:.2}", (first_num * last_num)),
-                                '/' => format!("{:.2}", (first_num / last_num)),
-                                '+' => format!("{:.2}", (first_num + last_num)),
-                                '-' => format!("{:.2}", (first_num - last_num)),
-                                _ => String::new(),
-                            }
-                        };
-
-                        // Example: 1+5*10
-                        // if everything goes alright, first_value is 5, last_value is 10 and the symbol is *
-                        // replace 5*10 with the earlier result we got which is 50. Continue with 1+50 in the next loop
-                        working_value = working_value
-                            .replace(&format!("{first_value}{symbol}{last_value}"), &final_value);
-
-                        break;
-                    }
-                }
+        let mut hour = hour
+            .parse::<u32>()
+            .map_err(|_| VerifierError::InvalidTime)?;
+        let minute = minute
+            .parse::<u32>()
+            .map_err(|_| VerifierError::InvalidTime)?;
+
+        if let Some(is_pm) = meridiem {
+            if hour == 0 || hour > 12 {
+                return Err(VerifierError::InvalidTime);
             }
-            *user_amount = working_value;
-        }
 
-        // If dot is present but nothing after that, add 2 zero
-        // if no dot, add dot + 2 zero
-        if user_amount.contains('.') {
-            let state = user_amount.split('.').collect::<Vec<&str>>();
-            if state[1].is_empty() {
-                *user_amount += "00";
+            hour %= 12;
+
+            if is_pm {
+                hour += 12;
             }
-        } else {
-            *user_amount = format!("{user_amount}.00");
         }
 
-        let float_amount: f64 = user_amount
-            .parse()
-            .map_err(|_| VerifierError::ParsingError(Field::Amount))?;
+        if hour > 23 || minute > 59 {
+            return Err(VerifierError::InvalidTime);
+        }
 
-        if float_amount <= 0.0 {
-            *user_amount = format!("{:.2}", (float_amount - (float_amount * 2.0)));
-            return Err(VerifierError::AmountBelowZero);
+        *user_time = format!("{hour:02}:{minute:02}");
+
+        Ok(Output::Accepted(Field::Time))
+    }
+
+    /// Checks if:
+    ///
+    /// - Amount is empty
+    /// - Amount is zero or below
+    /// - Amount is a valid expression
+    ///
+    /// The amount is evaluated as an expression supporting `+ - * /`, parentheses,
+    /// unary minus, percentages (`200-15%`) and the `k`/`m` suffixes. The result
+    /// is turned into a float ending with two decimals.
+    pub fn amount(&self, user_amount: &mut String) -> Result<Output, VerifierError> {
+        self.amount_with_balance(user_amount, None)
+    }
+
+    /// Same as [`Verifier::amount`] but `b` in the amount is replaced with the given balance
+    pub fn amount_with_balance(
+        &self,
+        user_amount: &mut String,
+        balance: Option<Cent>,
+    ) -> Result<Output, VerifierError> {
+        // Cancel all verification if the amount is empty
+        if user_amount.trim().is_empty() {
+            return Ok(Output::Nothing(Field::Amount));
         }
 
-        // Checks if there is 2 number after the dot else add zero/s
-        if user_amount.contains('.') {
-            let split_amount = user_amount.split('.').collect::<Vec<&str>>();
+        let float_amount = evaluate_amount(user_amount, balance)?;
 
-            match split_amount[1].len().cmp(&2) {
-                Ordering::Less => *user_amount = format!("{user_amount}0"),
-                Ordering::Greater => {
-                    *user_amount = format!("{}.{}", split_amount[0], &split_amount[1][..2]);
-                }
-                Ordering::Equal => (),
-            }
+        if !float_amount.is_finite() {
+            return Err(VerifierError::AmountTooBig);
         }
 
-        // We can safely split now as previously we just added a dot + 2 numbers with the amount
-        // and create the final value for the amount
-        let split_amount = user_amount.split('.').collect::<Vec<&str>>();
+        // Cut to 2 numbers after the dot. Formatting to 6 decimals first clears float noise
+        // such as 1.15 * 3 ending in 0.449999 but rounds anything past the 6th decimal
+        let formatted = format!("{:.6}", float_amount.abs());
+        let (whole, fraction) = formatted.split_once('.').unwrap();
+        let fraction = &fraction[..2];
 
         // limit max character to 10
-        if split_amount[0].len() > 10 {
-            *user_amount = format!("{}.{}", &split_amount[0][..10], split_amount[1]);
+        let whole = if whole.len() > 10 {
+            &whole[..10]
+        } else {
+            whole
+        };
+
+        *user_amount = format!("{whole}.{fraction}");
+
+        if float_amount <= 0.0 || *user_amount == "0.00" {
+            return Err(VerifierError::AmountBelowZero);
         }
 
         Ok(Output::Accepted(Field::Amount))
@@ -2,8 +2,8 @@ use anyhow::Result;
 use rex_db::models::AmountNature;
 use rex_shared::models::{Cent, Dollar};
 
-pub fn month_name_to_num(name: &str) -> u32 {
-    match name {
+pub fn month_name_to_num(name: &str) -> Option<u32> {
+    let month = match name {
         "January" => 1,
         "February" => 2,
         "March" => 3,
@@ -16,8 +16,10 @@ pub fn month_name_to_num(name: &str) -> u32 {
         "October" => 10,
         "November" => 11,
         "December" => 12,
-        _ => panic!("Invalid month name {name}"),
-    }
+        _ => return None,
+    };
+
+    Some(month)
 }
 
 pub fn month_year_to_unique(month: i32, year: i32) -> i32 {
@@ -57,22 +59,30 @@ pub fn parse_amount_nature_cent(amount: &str) -> Result<Option<AmountNature>> {
     Ok(Some(res))
 }
 
-pub fn compare_change(current: Dollar, previous: Dollar) -> String {
-    match current.cent().percent_change(previous.cent()) {
-        None => "∞".to_string(),
-        Some(diff) if diff < 0.0 => format!("↓{:.2}", diff.abs()),
-        Some(diff) => format!("↑{diff:.2}"),
+/// Escapes a CSV field if it contains a separator, a quote or a new line
+pub(crate) fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
     }
 }
 
+pub fn compare_change(current: Dollar, previous: Dollar) -> String {
+    format_change(current.cent().percent_change(previous.cent()))
+}
+
 pub fn compare_change_opt(current: Dollar, previous: Option<Dollar>) -> String {
-    match previous {
+    format_change(previous.and_then(|prev| current.cent().percent_change(prev.cent())))
+}
+
+/// Formats a % change with an arrow for the direction. No change value means there was nothing
+/// to compare with
+pub(crate) fn format_change(change: Option<f64>) -> String {
+    match change {
         None => "∞".to_string(),
-        Some(prev) => match current.cent().percent_change(prev.cent()) {
-            None => "∞".to_string(),
-            Some(diff) if diff < 0.0 => format!("↓{:.2}", diff.abs()),
-            Some(diff) => format!("↑{diff:.2}"),
-        },
+        Some(diff) if diff < 0.0 => format!("↓{:.2}", diff.abs()),
+        Some(diff) => format!("↑{diff:.2}"),
     }
 }
 
@@ -84,24 +94,23 @@ mod tests {
 
     #[test]
     fn test_month_name_to_num_all_months() {
-        assert_eq!(month_name_to_num("January"), 1);
-        assert_eq!(month_name_to_num("February"), 2);
-        assert_eq!(month_name_to_num("March"), 3);
-        assert_eq!(month_name_to_num("April"), 4);
-        assert_eq!(month_name_to_num("May"), 5);
-        assert_eq!(month_name_to_num("June"), 6);
-        assert_eq!(month_name_to_num("July"), 7);
-        assert_eq!(month_name_to_num("August"), 8);
-        assert_eq!(month_name_to_num("September"), 9);
-        assert_eq!(month_name_to_num("October"), 10);
-        assert_eq!(month_name_to_num("November"), 11);
-        assert_eq!(month_name_to_num("December"), 12);
-    }
-
-    #[test]
-    #[should_panic(expected = "Invalid month name")]
-    fn test_month_name_to_num_invalid_panics() {
-        month_name_to_num("NotAMonth");
+        assert_eq!(month_name_to_num("January"), Some(1));
+        assert_eq!(month_name_to_num("February"), Some(2));
+        assert_eq!(month_name_to_num("March"), Some(3));
+        assert_eq!(month_name_to_num("April"), Some(4));
+        assert_eq!(month_name_to_num("May"), Some(5));
+        assert_eq!(month_name_to_num("June"), Some(6));
+        assert_eq!(month_name_to_num("July"), Some(7));
+        assert_eq!(month_name_to_num("August"), Some(8));
+        assert_eq!(month_name_to_num("September"), Some(9));
+        assert_eq!(month_name_to_num("October"), Some(10));
+        assert_eq!(month_name_to_num("November"), Some(11));
+        assert_eq!(month_name_to_num("December"), Some(12));
+    }
+
+    #[test]
+    fn test_month_name_to_num_invalid() {
+        assert_eq!(month_name_to_num("NotAMonth"), None);
     }
 
     #[test]
@@ -4,7 +4,13 @@ use rex_db::ConnCache;
 pub use rex_db::models::FullActivityTx;
 use rex_db::models::{Activity, ActivityNature, ActivityWithTxs};
 
-pub struct ActivityView(Vec<ActivityWithTxs>);
+use crate::views::ActivityJson;
+
+pub struct ActivityView {
+    activities: Vec<ActivityWithTxs>,
+    /// Tx rows of each activity, built on load so corrupted rows fail here instead of while drawing
+    tx_tables: Vec<Vec<Vec<String>>>,
+}
 
 pub(crate) fn get_activity_view(
     date: NaiveDate,
@@ -12,23 +18,39 @@ pub(crate) fn get_activity_view(
 ) -> Result<ActivityView> {
     let activities = Activity::get_activities(date, conn)?;
 
-    Ok(ActivityView(activities))
+    let tx_tables = activities
+        .iter()
+        .map(ActivityWithTxs::to_array)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ActivityView {
+        activities,
+        tx_tables,
+    })
 }
 
 impl ActivityView {
     #[must_use]
     pub fn total_activity(&self) -> usize {
-        self.0.len()
+        self.activities.len()
     }
 
     #[must_use]
     pub fn get_activity_table(&self) -> Vec<Vec<String>> {
-        self.0.iter().map(|a| a.activity.to_array()).collect()
+        self.activities
+            .iter()
+            .map(|a| a.activity.to_array())
+            .collect()
+    }
+
+    #[must_use]
+    pub fn json(&self) -> Vec<ActivityJson> {
+        self.activities.iter().map(ActivityJson::from).collect()
     }
 
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.activities.is_empty()
     }
 
     #[must_use]
@@ -37,23 +59,21 @@ impl ActivityView {
             return Vec::new();
         };
 
-        let target_activity = self.0.get(index).unwrap();
-
-        target_activity.to_array()
+        self.tx_tables.get(index).unwrap().clone()
     }
 
     #[must_use]
     pub fn add_extra_field(&self, index: usize) -> bool {
-        let target_activity = self.0.get(index).unwrap();
+        let target_activity = self.activities.get(index).unwrap();
 
         matches!(
-            target_activity.activity.activity_type.as_str().into(),
-            ActivityNature::EditTx | ActivityNature::PositionSwap
+            ActivityNature::try_from(target_activity.activity.activity_type.as_str()),
+            Ok(ActivityNature::EditTx | ActivityNature::PositionSwap)
         )
     }
 
     #[must_use]
     pub fn get_activity_txs(&self, index: usize) -> Vec<&FullActivityTx> {
-        self.0.get(index).unwrap().txs.iter().collect()
+        self.activities.get(index).unwrap().txs.iter().collect()
     }
 }
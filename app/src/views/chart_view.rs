@@ -1,18 +1,105 @@
-use chrono::NaiveDate;
-use rex_db::models::FullTx;
+use chrono::{Datelike, Days, Months, NaiveDate};
+use rex_db::models::{FetchNature, FullTx, TxType};
 use rex_shared::models::Cent;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use crate::views::TxViewGroup;
 
 pub struct ChartView {
     txs: TxViewGroup,
+    nature: FetchNature,
     dates: HashSet<NaiveDate>,
     first_date: NaiveDate,
     last_date: NaiveDate,
 }
 
-pub(crate) fn get_chart_view(txs: TxViewGroup) -> ChartView {
+/// The length of time each cash flow group covers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CashFlowGrouping {
+    Day,
+    Month,
+    Year,
+}
+
+impl CashFlowGrouping {
+    /// Days for the monthly and weekly modes, months for yearly and quarterly and years for
+    /// all time. Custom ranges are grouped based on their length
+    #[must_use]
+    pub fn from_nature(nature: FetchNature) -> Self {
+        match nature {
+            FetchNature::Monthly | FetchNature::Weekly => CashFlowGrouping::Day,
+            FetchNature::Yearly | FetchNature::Quarterly => CashFlowGrouping::Month,
+            FetchNature::All => CashFlowGrouping::Year,
+            FetchNature::Custom(start_date, end_date) => {
+                let days = end_date.signed_duration_since(start_date).num_days().abs();
+
+                if days <= 62 {
+                    CashFlowGrouping::Day
+                } else if days <= 366 * 3 {
+                    CashFlowGrouping::Month
+                } else {
+                    CashFlowGrouping::Year
+                }
+            }
+        }
+    }
+
+    /// The first date of the group the date falls in
+    fn group_start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            CashFlowGrouping::Day => date,
+            CashFlowGrouping::Month => date.with_day(1).unwrap(),
+            CashFlowGrouping::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap(),
+        }
+    }
+
+    fn next_group(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            CashFlowGrouping::Day => date + Days::new(1),
+            CashFlowGrouping::Month => date + Months::new(1),
+            CashFlowGrouping::Year => date + Months::new(12),
+        }
+    }
+
+    fn label(self, date: NaiveDate) -> String {
+        match self {
+            CashFlowGrouping::Day => date.format("%Y-%m-%d").to_string(),
+            CashFlowGrouping::Month => date.format("%Y-%m").to_string(),
+            CashFlowGrouping::Year => date.format("%Y").to_string(),
+        }
+    }
+}
+
+/// Total income and expense of a day, month or year
+pub struct CashFlow {
+    label: String,
+    income: Cent,
+    expense: Cent,
+}
+
+impl CashFlow {
+    #[must_use]
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    #[must_use]
+    pub fn income(&self) -> Cent {
+        self.income
+    }
+
+    #[must_use]
+    pub fn expense(&self) -> Cent {
+        self.expense
+    }
+
+    #[must_use]
+    pub fn net(&self) -> Cent {
+        self.income - self.expense.value()
+    }
+}
+
+pub(crate) fn get_chart_view(txs: TxViewGroup, nature: FetchNature) -> ChartView {
     let mut first_date = NaiveDate::default();
     let mut last_date = NaiveDate::default();
 
@@ -46,6 +133,7 @@ pub(crate) fn get_chart_view(txs: TxViewGroup) -> ChartView {
 
     ChartView {
         txs,
+        nature,
         dates: unique_dates,
         first_date,
         last_date,
@@ -87,4 +175,108 @@ impl ChartView {
     pub fn get_tx(&self, index: usize) -> &FullTx {
         &self.txs.0[index].tx
     }
+
+    #[must_use]
+    pub fn cash_flow_grouping(&self) -> CashFlowGrouping {
+        CashFlowGrouping::from_nature(self.nature)
+    }
+
+    /// Income and expense of every group from the first to the last tx date.
+    /// Groups without any income or expense are kept with 0
+    #[must_use]
+    pub fn cash_flow(&self) -> Vec<CashFlow> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+
+        let grouping = self.cash_flow_grouping();
+
+        let mut groups = BTreeMap::new();
+
+        let last_group = grouping.group_start(self.last_date);
+        let mut ongoing_group = grouping.group_start(self.first_date);
+
+        while ongoing_group <= last_group {
+            groups.insert(ongoing_group, (Cent::new(0), Cent::new(0)));
+            ongoing_group = grouping.next_group(ongoing_group);
+        }
+
+        for tx in &self.txs.0 {
+            let group = grouping.group_start(tx.tx.date.date());
+            let (income, expense) = groups.entry(group).or_default();
+
+            match tx.tx.tx_type {
+                TxType::Income => *income += tx.tx.amount,
+                TxType::Expense => *expense += tx.tx.amount,
+                _ => {}
+            }
+        }
+
+        groups
+            .into_iter()
+            .map(|(group, (income, expense))| CashFlow {
+                label: grouping.label(group),
+                income,
+                expense,
+            })
+            .collect()
+    }
+
+    /// Expense of each tag by month from the month of the first tx to the month of the last tx.
+    /// Months without any expense are kept with 0. Txs only count towards their first tag,
+    /// same as the summary and the reports
+    #[must_use]
+    pub fn tag_monthly_expense(&self) -> BTreeMap<String, BTreeMap<NaiveDate, Cent>> {
+        let mut tag_months = BTreeMap::new();
+
+        if self.is_empty() {
+            return tag_months;
+        }
+
+        let grouping = CashFlowGrouping::Month;
+
+        let mut all_months = BTreeMap::new();
+
+        let last_month = grouping.group_start(self.last_date);
+        let mut ongoing_month = grouping.group_start(self.first_date);
+
+        while ongoing_month <= last_month {
+            all_months.insert(ongoing_month, Cent::new(0));
+            ongoing_month = grouping.next_group(ongoing_month);
+        }
+
+        for tx in &self.txs.0 {
+            let TxType::Expense = tx.tx.tx_type else {
+                continue;
+            };
+
+            let Some(tag) = tx.tx.tags.first() else {
+                continue;
+            };
+
+            let month = grouping.group_start(tx.tx.date.date());
+
+            let months = tag_months
+                .entry(tag.name.clone())
+                .or_insert_with(|| all_months.clone());
+
+            *months.entry(month).or_default() += tx.tx.amount;
+        }
+
+        tag_months
+    }
+
+    /// Tags with any expense sorted by the total expense, highest first
+    #[must_use]
+    pub fn expense_tags(&self) -> Vec<String> {
+        let mut tag_totals: Vec<(String, i64)> = self
+            .tag_monthly_expense()
+            .into_iter()
+            .map(|(tag, months)| (tag, months.values().map(Cent::value).sum()))
+            .collect();
+
+        tag_totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        tag_totals.into_iter().map(|(tag, _)| tag).collect()
+    }
 }
@@ -0,0 +1,223 @@
+use anyhow::Result;
+use chrono::{Datelike, Months, NaiveDate};
+use rex_db::ConnCache;
+use rex_db::models::{Balance, FetchNature, FullTx, Tx, TxType};
+use rex_shared::models::Cent;
+use std::collections::HashMap;
+
+/// Number of whole months before the forecast date that are used to get the monthly averages
+pub const LOOKBACK_MONTHS: u32 = 6;
+
+/// The months ahead the forecast is usually checked for
+pub const FORECAST_MONTHS: [u32; 3] = [1, 3, 6];
+
+/// Projects the balance of each method forward from a date using the average monthly
+/// movement of the months before it
+pub struct ForecastView {
+    date: NaiveDate,
+    /// Number of months the averages were taken from
+    months: u32,
+    balances: HashMap<i32, Cent>,
+    monthly_changes: HashMap<i32, Cent>,
+    /// Average monthly income and expense of each tag
+    tags: HashMap<String, (Cent, Cent)>,
+}
+
+/// Returns the balance of every method after all txs of the given date
+fn balance_at(date: NaiveDate, db_conn: &mut impl ConnCache) -> Result<HashMap<i32, Cent>> {
+    let next_date = date.succ_opt().unwrap();
+
+    Ok(Balance::get_last_balance(
+        next_date,
+        FetchNature::Custom(next_date, next_date),
+        db_conn,
+    )?)
+}
+
+fn average(total: Cent, months: u32) -> Cent {
+    Cent::new((total.value() as f64 / f64::from(months)).round() as i64)
+}
+
+pub(crate) fn get_forecast_view(
+    date: NaiveDate,
+    db_conn: &mut impl ConnCache,
+) -> Result<ForecastView> {
+    let balances = balance_at(date, db_conn)?;
+
+    let month_start = date.with_day(1).unwrap();
+    let lookback_start = month_start - Months::new(LOOKBACK_MONTHS);
+
+    // Don't average over the months that came before the first tx
+    let (mut window_start, mut months) = match Tx::get_date_range(db_conn)? {
+        Some((first_date, _)) if first_date > lookback_start => {
+            let first_month = first_date.with_day(1).unwrap();
+            let months = (month_start.year() - first_month.year()) * 12
+                + month_start.month() as i32
+                - first_month.month() as i32;
+
+            (first_month, months.max(0) as u32)
+        }
+        _ => (lookback_start, LOOKBACK_MONTHS),
+    };
+
+    let mut window_end = month_start.pred_opt().unwrap();
+
+    // No whole month to take the average from. Use the current month up to the date
+    if months == 0 {
+        window_start = month_start;
+        window_end = date;
+        months = 1;
+    }
+
+    let start_balances = balance_at(window_start.pred_opt().unwrap(), db_conn)?;
+    let end_balances = balance_at(window_end, db_conn)?;
+
+    let monthly_changes = end_balances
+        .iter()
+        .map(|(method_id, end_balance)| {
+            let start_balance = start_balances.get(method_id).copied().unwrap_or_default();
+
+            (
+                *method_id,
+                average(
+                    Cent::new(end_balance.value() - start_balance.value()),
+                    months,
+                ),
+            )
+        })
+        .collect();
+
+    let txs = FullTx::get_txs(
+        window_start,
+        FetchNature::Custom(window_start, window_end),
+        db_conn,
+    )?;
+
+    let mut tag_totals: HashMap<String, (Cent, Cent)> = HashMap::new();
+
+    for tx in txs {
+        let Some(tag) = tx.tags.first() else {
+            continue;
+        };
+
+        match tx.tx_type {
+            TxType::Income => tag_totals.entry(tag.name.clone()).or_default().0 += tx.amount,
+            TxType::Expense => tag_totals.entry(tag.name.clone()).or_default().1 += tx.amount,
+            _ => {}
+        }
+    }
+
+    let tags = tag_totals
+        .into_iter()
+        .map(|(name, (income, expense))| {
+            (name, (average(income, months), average(expense, months)))
+        })
+        .collect();
+
+    Ok(ForecastView {
+        date,
+        months,
+        balances,
+        monthly_changes,
+        tags,
+    })
+}
+
+/// Months until the balance goes below 0 if it keeps moving by the monthly change.
+/// None if the balance never goes negative
+fn runway_from(balance: Cent, monthly_change: Cent) -> Option<u32> {
+    if balance < 0 {
+        return Some(0);
+    }
+
+    if monthly_change >= 0 {
+        return None;
+    }
+
+    Some((balance.value() / -monthly_change.value()) as u32 + 1)
+}
+
+impl ForecastView {
+    #[must_use]
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    #[must_use]
+    pub fn lookback_months(&self) -> u32 {
+        self.months
+    }
+
+    /// The balance of the method on the forecast date
+    #[must_use]
+    pub fn balance(&self, method_id: i32) -> Cent {
+        self.balances.get(&method_id).copied().unwrap_or_default()
+    }
+
+    /// The average amount the balance of the method moves by each month
+    #[must_use]
+    pub fn monthly_change(&self, method_id: i32) -> Cent {
+        self.monthly_changes
+            .get(&method_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn projected_balance(&self, method_id: i32, months: u32) -> Cent {
+        self.balance(method_id) + self.monthly_change(method_id).value() * i64::from(months)
+    }
+
+    /// Monthly projected points of the method, starting from the month after the forecast date
+    #[must_use]
+    pub fn projected_series(&self, method_id: i32, months: u32) -> Vec<(NaiveDate, Cent)> {
+        (1..=months)
+            .map(|month| {
+                (
+                    self.date + Months::new(month),
+                    self.projected_balance(method_id, month),
+                )
+            })
+            .collect()
+    }
+
+    /// Months until the method balance goes negative. None if it never does
+    #[must_use]
+    pub fn runway(&self, method_id: i32) -> Option<u32> {
+        runway_from(self.balance(method_id), self.monthly_change(method_id))
+    }
+
+    #[must_use]
+    pub fn total_balance(&self) -> Cent {
+        Cent::new(self.balances.values().map(Cent::value).sum())
+    }
+
+    #[must_use]
+    pub fn total_monthly_change(&self) -> Cent {
+        Cent::new(self.monthly_changes.values().map(Cent::value).sum())
+    }
+
+    /// Months until the total balance of all methods goes negative. None if it never does
+    #[must_use]
+    pub fn total_runway(&self) -> Option<u32> {
+        runway_from(self.total_balance(), self.total_monthly_change())
+    }
+
+    /// Rows of tag name, average monthly income, average monthly expense and average monthly net
+    #[must_use]
+    pub fn tags_array(&self) -> Vec<Vec<String>> {
+        let mut tags: Vec<(&String, &(Cent, Cent))> = self.tags.iter().collect();
+        tags.sort_by_key(|(name, _)| *name);
+
+        tags.into_iter()
+            .map(|(name, (income, expense))| {
+                vec![
+                    name.clone(),
+                    format!("{:.2}", income.dollar().value()),
+                    format!("{:.2}", expense.dollar().value()),
+                    format!("{:.2}", (*income - expense.value()).dollar().value()),
+                ]
+            })
+            .collect()
+    }
+}
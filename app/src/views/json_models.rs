@@ -0,0 +1,230 @@
+use rex_db::models::{Activity, ActivityWithTxs, Balance, FullActivityTx, FullTx, TxMethod};
+use rex_shared::models::{Cent, Dollar};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Format of every date with a time in the JSON output
+const JSON_DATE_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// Rounds a dollar amount to the cent so the JSON does not carry float noise
+pub(crate) fn json_amount(amount: Dollar) -> f64 {
+    amount.cent().dollar().value()
+}
+
+/// Rounds a percentage to 2 decimal places, the same precision the tables show
+pub(crate) fn json_percentage(percentage: f64) -> f64 {
+    (percentage * 100.0).round() / 100.0
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct TxJson {
+    pub id: i32,
+    /// YYYY-MM-DDTHH:MM:SS
+    pub date: String,
+    pub details: Option<String>,
+    pub from_method: String,
+    /// Only exists for Transfer txs
+    pub to_method: Option<String>,
+    pub amount: f64,
+    pub tx_type: String,
+    pub tags: Vec<String>,
+}
+
+impl From<&FullTx> for TxJson {
+    fn from(tx: &FullTx) -> Self {
+        Self {
+            id: tx.id,
+            date: tx.date.format(JSON_DATE_TIME_FORMAT).to_string(),
+            details: tx.details.clone(),
+            from_method: tx.from_method.name.clone(),
+            to_method: tx.to_method.as_ref().map(|method| method.name.clone()),
+            amount: json_amount(tx.amount.dollar()),
+            tx_type: tx.tx_type.to_string(),
+            tags: tx.tags.iter().map(|tag| tag.name.clone()).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct MethodBalanceJson {
+    pub method: String,
+    pub balance: f64,
+}
+
+impl MethodBalanceJson {
+    /// Balance of every method in the order of their position
+    pub(crate) fn sorted(balances: &HashMap<i32, Cent>, methods: &[&TxMethod]) -> Vec<Self> {
+        methods
+            .iter()
+            .map(|method| MethodBalanceJson {
+                method: method.name.clone(),
+                balance: json_amount(
+                    balances
+                        .get(&method.id)
+                        .copied()
+                        .unwrap_or_default()
+                        .dollar(),
+                ),
+            })
+            .collect()
+    }
+}
+
+/// A tx with the balance of every method right after it was committed
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct TxViewJson {
+    #[serde(flatten)]
+    pub tx: TxJson,
+    pub balances: Vec<MethodBalanceJson>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct BalancesJson {
+    pub methods: Vec<MethodBalanceJson>,
+    pub total: f64,
+}
+
+impl BalancesJson {
+    pub(crate) fn new(balances: &HashMap<i32, Balance>, methods: &[&TxMethod]) -> Self {
+        let balances: HashMap<i32, Cent> = balances
+            .iter()
+            .map(|(method_id, balance)| (*method_id, Cent::new(balance.balance)))
+            .collect();
+
+        let total = balances
+            .values()
+            .fold(Cent::new(0), |total, balance| total + balance.value());
+
+        Self {
+            methods: MethodBalanceJson::sorted(&balances, methods),
+            total: json_amount(total.dollar()),
+        }
+    }
+}
+
+/// Income and expense movement of the whole period or a single method
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct MovementJson {
+    pub total_income: f64,
+    pub total_expense: f64,
+    /// Monthly average. Does not exist for monthly and weekly summaries
+    pub average_income: Option<f64>,
+    pub average_expense: Option<f64>,
+    pub income_percentage: f64,
+    pub expense_percentage: f64,
+    /// % change compared to the previous period. Null if there was nothing to compare with
+    pub income_change: Option<f64>,
+    pub expense_change: Option<f64>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct MethodSummaryJson {
+    pub method: String,
+    #[serde(flatten)]
+    pub movement: MovementJson,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct LargestTxJson {
+    /// YYYY-MM-DD. Null if the period has no tx of this type
+    pub date: Option<String>,
+    pub method: Option<String>,
+    pub amount: f64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PeakMonthJson {
+    /// YYYY-MM. Null if the period has no tx of this type
+    pub month: Option<String>,
+    pub amount: f64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct LendBorrowJson {
+    pub borrows: f64,
+    pub lends: f64,
+    pub borrows_change: Option<f64>,
+    pub lends_change: Option<f64>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SummaryJson {
+    pub net: MovementJson,
+    pub methods: Vec<MethodSummaryJson>,
+    pub largest_income: LargestTxJson,
+    pub largest_expense: LargestTxJson,
+    pub peak_income: PeakMonthJson,
+    pub peak_expense: PeakMonthJson,
+    pub lend_borrows: LendBorrowJson,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct TagSummaryJson {
+    pub tag: String,
+    pub income: f64,
+    pub expense: f64,
+    pub income_percentage: f64,
+    pub expense_percentage: f64,
+    /// % change compared to the previous period. Null if there was nothing to compare with
+    pub income_change: Option<f64>,
+    pub expense_change: Option<f64>,
+    pub borrows: f64,
+    pub lends: f64,
+}
+
+/// The tx of an activity. Every field is optional as search activities only save the fields
+/// that were searched with
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ActivityTxJson {
+    pub date: Option<String>,
+    pub details: Option<String>,
+    pub from_method: Option<String>,
+    pub to_method: Option<String>,
+    /// Exact amount or a comparison such as >100
+    pub amount: Option<String>,
+    pub tx_type: Option<String>,
+    pub tags: Vec<String>,
+    pub display_order: Option<i32>,
+}
+
+impl From<&FullActivityTx> for ActivityTxJson {
+    fn from(tx: &FullActivityTx) -> Self {
+        Self {
+            date: tx.date.clone(),
+            details: tx.details.clone(),
+            from_method: tx.from_method.as_ref().map(|method| method.name.clone()),
+            to_method: tx.to_method.as_ref().map(|method| method.name.clone()),
+            amount: tx.amount.as_ref().map(ToString::to_string),
+            tx_type: tx.tx_type.as_ref().map(ToString::to_string),
+            tags: tx.tags.iter().map(|tag| tag.name.clone()).collect(),
+            display_order: tx.display_order,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ActivityJson {
+    pub id: i32,
+    /// YYYY-MM-DDTHH:MM:SS
+    pub date: String,
+    /// One of add_tx, edit_tx, delete_tx, search_tx or position_swap
+    pub activity_type: String,
+    pub txs: Vec<ActivityTxJson>,
+}
+
+impl From<&ActivityWithTxs> for ActivityJson {
+    fn from(activity_with_txs: &ActivityWithTxs) -> Self {
+        let activity: &Activity = &activity_with_txs.activity;
+
+        Self {
+            id: activity.id,
+            date: activity.date.format(JSON_DATE_TIME_FORMAT).to_string(),
+            activity_type: activity.activity_type.clone(),
+            txs: activity_with_txs
+                .txs
+                .iter()
+                .map(ActivityTxJson::from)
+                .collect(),
+        }
+    }
+}
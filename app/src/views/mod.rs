@@ -1,13 +1,23 @@
 mod activity_view;
 mod chart_view;
+mod forecast_view;
+mod json_models;
 mod search_view;
+mod summary_compare_view;
 mod summary_models;
+mod summary_report_view;
 mod summary_view;
 mod tx_view;
+mod yearly_report_view;
 
 pub use activity_view::*;
 pub use chart_view::*;
+pub use forecast_view::*;
+pub use json_models::*;
 pub use search_view::*;
+pub use summary_compare_view::*;
 pub(crate) use summary_models::*;
+pub use summary_report_view::*;
 pub use summary_view::*;
 pub use tx_view::*;
+pub use yearly_report_view::*;
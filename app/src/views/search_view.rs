@@ -2,6 +2,8 @@ use anyhow::Result;
 use rex_db::ConnCache;
 use rex_db::models::{FullTx, NewSearch};
 
+use crate::views::TxJson;
+
 pub struct SearchView(Vec<FullTx>);
 
 pub(crate) fn get_search_txs(
@@ -21,6 +23,11 @@ impl SearchView {
         self.0.iter().map(|tx| tx.to_array(true)).collect()
     }
 
+    #[must_use]
+    pub fn json(&self) -> Vec<TxJson> {
+        self.0.iter().map(TxJson::from).collect()
+    }
+
     #[must_use]
     pub fn get_tx(&self, index: usize) -> &FullTx {
         &self.0[index]
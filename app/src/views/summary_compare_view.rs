@@ -0,0 +1,180 @@
+use rex_db::ConnCache;
+use rex_db::models::{FullTx, TxType};
+use rex_shared::models::Cent;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::utils::compare_change;
+
+/// Income and expense of a tag or a method within a period
+#[derive(Default, Clone, Copy)]
+struct Movement {
+    income: Cent,
+    expense: Cent,
+}
+
+/// Tag and method movement of two periods side by side
+pub struct SummaryComparison {
+    /// Method name with the movement of the first and the second period, in method position order
+    methods: Vec<(String, Movement, Movement)>,
+    /// Tag name with the movement of the first and the second period
+    tags: BTreeMap<String, (Movement, Movement)>,
+    first_total: Movement,
+    second_total: Movement,
+}
+
+/// Returns the movement of every method and every tag in the txs. Same as the summary,
+/// only the first tag of a tx is considered
+fn get_movements(txs: &[FullTx]) -> (HashMap<String, Movement>, HashMap<String, Movement>) {
+    let mut methods: HashMap<String, Movement> = HashMap::new();
+    let mut tags: HashMap<String, Movement> = HashMap::new();
+
+    for tx in txs {
+        let tag = tx.tags.first().map(|tag| tag.name.clone());
+
+        match tx.tx_type {
+            TxType::Income => {
+                methods
+                    .entry(tx.from_method.name.clone())
+                    .or_default()
+                    .income += tx.amount;
+
+                if let Some(tag) = tag {
+                    tags.entry(tag).or_default().income += tx.amount;
+                }
+            }
+            TxType::Expense => {
+                methods
+                    .entry(tx.from_method.name.clone())
+                    .or_default()
+                    .expense += tx.amount;
+
+                if let Some(tag) = tag {
+                    tags.entry(tag).or_default().expense += tx.amount;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    (methods, tags)
+}
+
+pub(crate) fn get_summary_comparison(
+    first_txs: &[FullTx],
+    second_txs: &[FullTx],
+    conn: &impl ConnCache,
+) -> SummaryComparison {
+    let (first_methods, first_tags) = get_movements(first_txs);
+    let (second_methods, second_tags) = get_movements(second_txs);
+
+    let mut comparison = SummaryComparison {
+        methods: Vec::new(),
+        tags: BTreeMap::new(),
+        first_total: Movement::default(),
+        second_total: Movement::default(),
+    };
+
+    for method in conn.cache().get_methods() {
+        let first = first_methods.get(&method.name).copied().unwrap_or_default();
+        let second = second_methods
+            .get(&method.name)
+            .copied()
+            .unwrap_or_default();
+
+        comparison.first_total.income += first.income;
+        comparison.first_total.expense += first.expense;
+        comparison.second_total.income += second.income;
+        comparison.second_total.expense += second.expense;
+
+        comparison
+            .methods
+            .push((method.name.clone(), first, second));
+    }
+
+    for (tag, first) in &first_tags {
+        comparison.tags.entry(tag.clone()).or_default().0 = *first;
+    }
+
+    for (tag, second) in &second_tags {
+        comparison.tags.entry(tag.clone()).or_default().1 = *second;
+    }
+
+    comparison
+}
+
+fn format_cent(value: Cent) -> String {
+    format!("{:.2}", value.dollar().value())
+}
+
+/// Amount, change and % change from the first to the second value
+fn change_fields(first: Cent, second: Cent) -> [String; 4] {
+    let change = second - first.value();
+
+    // Nothing to compare if neither period had any movement
+    let percent_change = if first == 0 && second == 0 {
+        "-".to_string()
+    } else {
+        compare_change(second.dollar(), first.dollar())
+    };
+
+    [
+        format_cent(first),
+        format_cent(second),
+        format!("{:+.2}", change.dollar().value()),
+        percent_change,
+    ]
+}
+
+fn comparison_row(name: &str, first: Movement, second: Movement) -> Vec<String> {
+    let mut row = vec![name.to_string()];
+
+    row.extend(change_fields(first.income, second.income));
+    row.extend(change_fields(first.expense, second.expense));
+
+    row
+}
+
+impl SummaryComparison {
+    /// Names of each column. Row name, then the first period, second period, change
+    /// and % change of the income followed by the same for the expense
+    #[must_use]
+    pub fn headers(name: &str) -> Vec<String> {
+        [
+            name,
+            "Income 1",
+            "Income 2",
+            "Change",
+            "Change %",
+            "Expense 1",
+            "Expense 2",
+            "Change",
+            "Change %",
+        ]
+        .into_iter()
+        .map(ToString::to_string)
+        .collect()
+    }
+
+    /// One row for each tag that had any income or expense in either period
+    #[must_use]
+    pub fn tags_array(&self) -> Vec<Vec<String>> {
+        self.tags
+            .iter()
+            .map(|(tag, (first, second))| comparison_row(tag, *first, *second))
+            .collect()
+    }
+
+    /// One row for each method followed by the total of all methods
+    #[must_use]
+    pub fn methods_array(&self) -> Vec<Vec<String>> {
+        let mut rows: Vec<Vec<String>> = self
+            .methods
+            .iter()
+            .map(|(method, first, second)| comparison_row(method, *first, *second))
+            .collect();
+
+        rows.push(comparison_row("Total", self.first_total, self.second_total));
+
+        rows
+    }
+}
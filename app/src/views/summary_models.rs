@@ -2,6 +2,15 @@ use chrono::NaiveDate;
 use rex_shared::models::{Cent, Dollar};
 use std::fmt;
 
+use crate::utils::{compare_change_opt, format_change};
+use crate::views::{
+    LargestTxJson, LendBorrowJson, MethodSummaryJson, MovementJson, PeakMonthJson, TagSummaryJson,
+    json_amount, json_percentage,
+};
+
+/// % change compared to the previous period. None if there was nothing to compare with
+pub(crate) type Change = Option<f64>;
+
 #[derive(Default, Clone)]
 pub(crate) struct LargestMomvement {
     pub(crate) date: NaiveDate,
@@ -32,8 +41,8 @@ pub(crate) struct SummaryNet {
     average_expense: Option<Dollar>,
     income_percentage: f64,
     expense_percentage: f64,
-    mom_yoy_earning: Option<String>,
-    mom_yoy_expense: Option<String>,
+    mom_yoy_earning: Option<Change>,
+    mom_yoy_expense: Option<Change>,
 }
 
 impl SummaryNet {
@@ -45,8 +54,8 @@ impl SummaryNet {
         average_expense: Option<Dollar>,
         income_percentage: f64,
         expense_percentage: f64,
-        mom_yoy_earning: Option<String>,
-        mom_yoy_expense: Option<String>,
+        mom_yoy_earning: Option<Change>,
+        mom_yoy_expense: Option<Change>,
     ) -> Self {
         Self {
             total_income,
@@ -84,16 +93,30 @@ impl SummaryNet {
             ]]
         };
 
-        if let Some(mom_yoy_earning) = &self.mom_yoy_earning {
-            to_return[0].push(mom_yoy_earning.clone());
+        if let Some(mom_yoy_earning) = self.mom_yoy_earning {
+            to_return[0].push(format_change(mom_yoy_earning));
         }
 
-        if let Some(mom_yoy_expense) = &self.mom_yoy_expense {
-            to_return[0].push(mom_yoy_expense.clone());
+        if let Some(mom_yoy_expense) = self.mom_yoy_expense {
+            to_return[0].push(format_change(mom_yoy_expense));
         }
 
         to_return
     }
+
+    #[must_use]
+    pub(crate) fn json(&self) -> MovementJson {
+        MovementJson {
+            total_income: json_amount(self.total_income),
+            total_expense: json_amount(self.total_expense),
+            average_income: self.average_income.map(json_amount),
+            average_expense: self.average_expense.map(json_amount),
+            income_percentage: json_percentage(self.income_percentage),
+            expense_percentage: json_percentage(self.expense_percentage),
+            income_change: self.mom_yoy_earning.flatten().map(json_percentage),
+            expense_change: self.mom_yoy_expense.flatten().map(json_percentage),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -169,6 +192,17 @@ impl SummaryLargest {
             ]
         }
     }
+
+    #[must_use]
+    pub(crate) fn json(&self) -> LargestTxJson {
+        let found = self.date != NaiveDate::default();
+
+        LargestTxJson {
+            date: found.then(|| self.date.format("%Y-%m-%d").to_string()),
+            method: found.then(|| self.method.clone()),
+            amount: json_amount(self.amount),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -205,6 +239,16 @@ impl SummaryPeak {
             ]
         }
     }
+
+    #[must_use]
+    pub(crate) fn json(&self) -> PeakMonthJson {
+        let found = self.date != NaiveDate::default();
+
+        PeakMonthJson {
+            month: found.then(|| self.date.format("%Y-%m").to_string()),
+            amount: json_amount(self.amount),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -216,8 +260,8 @@ pub(crate) struct SummaryMethods {
     percentage_expense: f64,
     average_earning: Option<Dollar>,
     average_expense: Option<Dollar>,
-    mom_yoy_earning: Option<String>,
-    mom_yoy_expense: Option<String>,
+    mom_yoy_earning: Option<Change>,
+    mom_yoy_expense: Option<Change>,
 }
 
 impl SummaryMethods {
@@ -230,8 +274,8 @@ impl SummaryMethods {
         percentage_expense: f64,
         average_earning: Option<Dollar>,
         average_expense: Option<Dollar>,
-        mom_yoy_earning: Option<String>,
-        mom_yoy_expense: Option<String>,
+        mom_yoy_earning: Option<Change>,
+        mom_yoy_expense: Option<Change>,
     ) -> Self {
         Self {
             method,
@@ -270,31 +314,48 @@ impl SummaryMethods {
             ]
         };
 
-        if let Some(mom_yoy_earning) = &self.mom_yoy_earning {
-            to_return.push(mom_yoy_earning.clone());
+        if let Some(mom_yoy_earning) = self.mom_yoy_earning {
+            to_return.push(format_change(mom_yoy_earning));
         }
-        if let Some(mom_yoy_expense) = &self.mom_yoy_expense {
-            to_return.push(mom_yoy_expense.clone());
+        if let Some(mom_yoy_expense) = self.mom_yoy_expense {
+            to_return.push(format_change(mom_yoy_expense));
         }
 
         to_return
     }
+
+    #[must_use]
+    pub(crate) fn json(&self) -> MethodSummaryJson {
+        MethodSummaryJson {
+            method: self.method.clone(),
+            movement: MovementJson {
+                total_income: json_amount(self.total_earning),
+                total_expense: json_amount(self.total_expense),
+                average_income: self.average_earning.map(json_amount),
+                average_expense: self.average_expense.map(json_amount),
+                income_percentage: json_percentage(self.percentage_earning),
+                expense_percentage: json_percentage(self.percentage_expense),
+                income_change: self.mom_yoy_earning.flatten().map(json_percentage),
+                expense_change: self.mom_yoy_expense.flatten().map(json_percentage),
+            },
+        }
+    }
 }
 
 #[derive(Debug)]
 pub(crate) struct SummaryLendBorrows {
     pub(crate) borrows: Dollar,
     pub(crate) lends: Dollar,
-    mom_yoy_borrows: Option<String>,
-    mom_yoy_lends: Option<String>,
+    mom_yoy_borrows: Option<Change>,
+    mom_yoy_lends: Option<Change>,
 }
 
 impl SummaryLendBorrows {
     pub(crate) fn new(
         borrows: Dollar,
         lends: Dollar,
-        mom_yoy_borrows: Option<String>,
-        mom_yoy_lends: Option<String>,
+        mom_yoy_borrows: Option<Change>,
+        mom_yoy_lends: Option<Change>,
     ) -> Self {
         Self {
             borrows,
@@ -310,13 +371,79 @@ impl SummaryLendBorrows {
             format!("{:.2}", self.lends.value()),
         ];
 
-        if let Some(mom_yoy_borrows) = &self.mom_yoy_borrows {
-            to_return.push(mom_yoy_borrows.clone());
+        if let Some(mom_yoy_borrows) = self.mom_yoy_borrows {
+            to_return.push(format_change(mom_yoy_borrows));
         }
-        if let Some(mom_yoy_lends) = &self.mom_yoy_lends {
-            to_return.push(mom_yoy_lends.clone());
+        if let Some(mom_yoy_lends) = self.mom_yoy_lends {
+            to_return.push(format_change(mom_yoy_lends));
         }
 
         to_return
     }
+
+    pub(crate) fn json(&self) -> LendBorrowJson {
+        LendBorrowJson {
+            borrows: json_amount(self.borrows),
+            lends: json_amount(self.lends),
+            borrows_change: self.mom_yoy_borrows.flatten().map(json_percentage),
+            lends_change: self.mom_yoy_lends.flatten().map(json_percentage),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SummaryTag {
+    pub(crate) tag: String,
+    pub(crate) income: Dollar,
+    pub(crate) expense: Dollar,
+    pub(crate) income_percentage: f64,
+    pub(crate) expense_percentage: f64,
+    /// Income and expense of the period this summary is compared with
+    pub(crate) previous_income: Option<Dollar>,
+    pub(crate) previous_expense: Option<Dollar>,
+    pub(crate) borrows: Dollar,
+    pub(crate) lends: Dollar,
+}
+
+impl SummaryTag {
+    /// The comparison columns are only added if the summary was compared with another period
+    pub(crate) fn array(&self, with_change: bool) -> Vec<String> {
+        let mut to_return = vec![
+            self.tag.clone(),
+            format!("{:.2}", self.income),
+            format!("{:.2}", self.expense),
+            format!("{:.2}", self.income_percentage),
+            format!("{:.2}", self.expense_percentage),
+        ];
+
+        if with_change {
+            to_return.push(compare_change_opt(self.income, self.previous_income));
+            to_return.push(compare_change_opt(self.expense, self.previous_expense));
+        }
+
+        to_return.push(format!("{:.2}", self.borrows));
+        to_return.push(format!("{:.2}", self.lends));
+
+        to_return
+    }
+
+    pub(crate) fn json(&self) -> TagSummaryJson {
+        let change = |current: Dollar, previous: Option<Dollar>| {
+            previous
+                .and_then(|previous| current.cent().percent_change(previous.cent()))
+                .map(json_percentage)
+        };
+
+        TagSummaryJson {
+            tag: self.tag.clone(),
+            income: json_amount(self.income),
+            expense: json_amount(self.expense),
+            income_percentage: json_percentage(self.income_percentage),
+            expense_percentage: json_percentage(self.expense_percentage),
+            income_change: change(self.income, self.previous_income),
+            expense_change: change(self.expense, self.previous_expense),
+            borrows: json_amount(self.borrows),
+            lends: json_amount(self.lends),
+        }
+    }
 }
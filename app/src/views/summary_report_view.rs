@@ -0,0 +1,332 @@
+use rex_db::models::FetchNature;
+use std::fmt::Write;
+
+use crate::views::FullSummary;
+
+/// Width of the longest tag bar in the tag distribution SVG
+const BAR_WIDTH: f64 = 300.0;
+/// Height taken by each tag in the tag distribution SVG
+const BAR_ROW_HEIGHT: usize = 24;
+/// Space on the left side of the bars for the tag names
+const BAR_LABEL_WIDTH: usize = 150;
+
+const INCOME_COLOR: &str = "#43a047";
+const EXPENSE_COLOR: &str = "#e53935";
+
+/// File formats a summary report can be rendered to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    /// Picks the format from a file extension. Anything other than html or htm is Markdown
+    #[must_use]
+    pub fn from_extension(extension: &str) -> Self {
+        if extension.eq_ignore_ascii_case("html") || extension.eq_ignore_ascii_case("htm") {
+            ReportFormat::Html
+        } else {
+            ReportFormat::Markdown
+        }
+    }
+
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "md",
+            ReportFormat::Html => "html",
+        }
+    }
+}
+
+/// A titled table of the report
+struct ReportTable {
+    name: &'static str,
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+/// Self contained Markdown or HTML document of a summary and its tags table
+pub struct SummaryReport<'a> {
+    title: String,
+    nature: FetchNature,
+    summary: &'a FullSummary,
+    /// Rows in the same format as `SummaryView::tags_array`
+    tags: &'a [Vec<String>],
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn to_strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(ToString::to_string).collect()
+}
+
+impl<'a> SummaryReport<'a> {
+    #[must_use]
+    pub fn new(
+        title: &str,
+        nature: FetchNature,
+        summary: &'a FullSummary,
+        tags: &'a [Vec<String>],
+    ) -> Self {
+        SummaryReport {
+            title: title.to_string(),
+            nature,
+            summary,
+            tags,
+        }
+    }
+
+    /// Same as the summary page, there is nothing to compare with in All Time
+    fn comparison_label(&self) -> Option<&'static str> {
+        match self.nature {
+            FetchNature::Monthly => Some("MoM"),
+            FetchNature::Yearly => Some("YoY"),
+            FetchNature::Weekly => Some("WoW"),
+            FetchNature::Quarterly => Some("QoQ"),
+            FetchNature::Custom(_, _) => Some("PoP"),
+            FetchNature::All => None,
+        }
+    }
+
+    /// Headers of the net and method rows
+    fn movement_headers(&self, name: &str) -> Vec<String> {
+        let mut headers = to_strings(&[name, "Total Income", "Total Expense"]);
+
+        // Monthly and Weekly summaries have no monthly averages
+        if !matches!(self.nature, FetchNature::Monthly | FetchNature::Weekly) {
+            headers.extend(to_strings(&["Average Income", "Average Expense"]));
+        }
+
+        headers.extend(to_strings(&["Income %", "Expense %"]));
+
+        if let Some(label) = self.comparison_label() {
+            headers.push(format!("{label} Income %"));
+            headers.push(format!("{label} Expense %"));
+        }
+
+        headers
+    }
+
+    fn tag_headers(&self) -> Vec<String> {
+        let mut headers = to_strings(&[
+            "Tag",
+            "Total Income",
+            "Total Expense",
+            "Income %",
+            "Expense %",
+        ]);
+
+        // Tags only get compared if there was an earlier period to compare with
+        let has_comparison = self.tags.first().is_some_and(|row| row.len() > 7);
+
+        if has_comparison && let Some(label) = self.comparison_label() {
+            headers.push(format!("{label} Income %"));
+            headers.push(format!("{label} Expense %"));
+        }
+
+        headers.extend(to_strings(&["Borrow", "Lend"]));
+
+        headers
+    }
+
+    fn tables(&self) -> Vec<ReportTable> {
+        let mut lend_borrow_headers = to_strings(&["Borrow", "Lend"]);
+
+        if let Some(label) = self.comparison_label() {
+            lend_borrow_headers.push(format!("{label} Borrow %"));
+            lend_borrow_headers.push(format!("{label} Lend %"));
+        }
+
+        vec![
+            ReportTable {
+                name: "Overview",
+                headers: self.movement_headers(""),
+                rows: self.summary.net_array(),
+            },
+            ReportTable {
+                name: "Methods",
+                headers: self.movement_headers("Method"),
+                rows: self.summary.method_array(),
+            },
+            ReportTable {
+                name: "Largest Transactions",
+                headers: to_strings(&["", "Date", "Amount", "Method"]),
+                rows: self.summary.largest_array(),
+            },
+            ReportTable {
+                name: "Peak Months",
+                headers: to_strings(&["", "Month", "Amount"]),
+                rows: self.summary.peak_array(),
+            },
+            ReportTable {
+                name: "Outstanding Lends and Borrows",
+                headers: lend_borrow_headers,
+                rows: self.summary.lend_borrows_array(),
+            },
+            ReportTable {
+                name: "Tags",
+                headers: self.tag_headers(),
+                rows: self.tags.to_vec(),
+            },
+        ]
+    }
+
+    /// Horizontal income and expense bars of each tag scaled by the largest amount
+    fn tag_bars_svg(&self) -> String {
+        let amount = |value: &String| value.parse::<f64>().unwrap_or(0.0);
+
+        let bars: Vec<(&str, f64, f64)> = self
+            .tags
+            .iter()
+            .filter(|row| row.len() > 2)
+            .map(|row| (row[0].as_str(), amount(&row[1]), amount(&row[2])))
+            .filter(|(_, income, expense)| *income > 0.0 || *expense > 0.0)
+            .collect();
+
+        let highest = bars
+            .iter()
+            .map(|(_, income, expense)| income.max(*expense))
+            .fold(0.0, f64::max);
+
+        let width = BAR_LABEL_WIDTH + BAR_WIDTH as usize + 150;
+        let height = (bars.len() + 1) * BAR_ROW_HEIGHT;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             font-family=\"sans-serif\" font-size=\"12\">\n"
+        );
+
+        // Legend on the first row
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{BAR_LABEL_WIDTH}\" y=\"6\" width=\"10\" height=\"10\" fill=\"{INCOME_COLOR}\"/>\
+             <text x=\"{}\" y=\"15\">Income</text>\
+             <rect x=\"{}\" y=\"6\" width=\"10\" height=\"10\" fill=\"{EXPENSE_COLOR}\"/>\
+             <text x=\"{}\" y=\"15\">Expense</text>",
+            BAR_LABEL_WIDTH + 14,
+            BAR_LABEL_WIDTH + 80,
+            BAR_LABEL_WIDTH + 94,
+        );
+
+        for (index, (tag, income, expense)) in bars.iter().enumerate() {
+            let y = (index + 1) * BAR_ROW_HEIGHT;
+
+            let scale = |value: f64| {
+                if highest == 0.0 {
+                    0.0
+                } else {
+                    value / highest * BAR_WIDTH
+                }
+            };
+
+            let income_width = scale(*income);
+            let expense_width = scale(*expense);
+
+            let _ = writeln!(
+                svg,
+                "<text x=\"0\" y=\"{}\">{}</text>\
+                 <rect x=\"{BAR_LABEL_WIDTH}\" y=\"{}\" width=\"{income_width:.1}\" height=\"8\" fill=\"{INCOME_COLOR}\"/>\
+                 <rect x=\"{BAR_LABEL_WIDTH}\" y=\"{}\" width=\"{expense_width:.1}\" height=\"8\" fill=\"{EXPENSE_COLOR}\"/>\
+                 <text x=\"{:.1}\" y=\"{}\">{income:.2} / {expense:.2}</text>",
+                y + 15,
+                escape_html(tag),
+                y + 3,
+                y + 12,
+                BAR_LABEL_WIDTH as f64 + income_width.max(expense_width) + 6.0,
+                y + 15,
+            );
+        }
+
+        svg.push_str("</svg>\n");
+
+        svg
+    }
+
+    #[must_use]
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Markdown => self.to_markdown(),
+            ReportFormat::Html => self.to_html(),
+        }
+    }
+
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("# {}\n", escape_markdown(&self.title));
+
+        let markdown_row = |row: &[String]| {
+            let fields: Vec<String> = row.iter().map(|f| escape_markdown(f)).collect();
+            format!("| {} |\n", fields.join(" | "))
+        };
+
+        for table in self.tables() {
+            let _ = write!(markdown, "\n## {}\n\n", table.name);
+
+            markdown.push_str(&markdown_row(&table.headers));
+            let _ = writeln!(markdown, "|{}", "---|".repeat(table.headers.len()));
+
+            for row in &table.rows {
+                markdown.push_str(&markdown_row(row));
+            }
+        }
+
+        markdown.push_str("\n## Tag Distribution\n\n");
+        markdown.push_str(&self.tag_bars_svg());
+
+        markdown
+    }
+
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        let title = escape_html(&self.title);
+
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>\n\
+             body {{ font-family: sans-serif; margin: 2em; }}\n\
+             table {{ border-collapse: collapse; margin-bottom: 1em; }}\n\
+             th, td {{ border: 1px solid #ccc; padding: 4px 8px; text-align: right; }}\n\
+             th:first-child, td:first-child {{ text-align: left; }}\n\
+             th {{ background: #eee; }}\n\
+             </style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+        );
+
+        for table in self.tables() {
+            let _ = writeln!(html, "<h2>{}</h2>\n<table>", table.name);
+
+            let header_cells: String = table
+                .headers
+                .iter()
+                .map(|h| format!("<th>{}</th>", escape_html(h)))
+                .collect();
+            let _ = writeln!(html, "<tr>{header_cells}</tr>");
+
+            for row in &table.rows {
+                let cells: String = row
+                    .iter()
+                    .map(|c| format!("<td>{}</td>", escape_html(c)))
+                    .collect();
+                let _ = writeln!(html, "<tr>{cells}</tr>");
+            }
+
+            html.push_str("</table>\n");
+        }
+
+        html.push_str("<h2>Tag Distribution</h2>\n");
+        html.push_str(&self.tag_bars_svg());
+        html.push_str("</body>\n</html>\n");
+
+        html
+    }
+}
@@ -5,10 +5,11 @@ use rex_db::models::{FetchNature, FullTx, TxType};
 use rex_shared::models::{Cent, Dollar};
 use std::collections::HashMap;
 
-use crate::utils::{compare_change, compare_change_opt, get_percentages, month_year_to_unique};
+use crate::utils::{get_percentages, month_year_to_unique};
 use crate::views::{
-    LargestMomvement, LargestType, PeakMonthlyMovement, PeakType, SummaryLargest,
-    SummaryLendBorrows, SummaryMethods, SummaryNet, SummaryPeak,
+    LargestMomvement, LargestType, PeakMonthlyMovement, PeakType, SummaryComparison, SummaryJson,
+    SummaryLargest, SummaryLendBorrows, SummaryMethods, SummaryNet, SummaryPeak, SummaryTag,
+    TagSummaryJson, get_summary_comparison,
 };
 
 /// Contains `FullTx` to generate summary data. Will always contain the exact number of txs from
@@ -48,6 +49,19 @@ impl FullSummary {
     pub fn lend_borrows_array(&self) -> Vec<Vec<String>> {
         vec![self.lend_borrows.array()]
     }
+
+    #[must_use]
+    pub fn json(&self) -> SummaryJson {
+        SummaryJson {
+            net: self.net.json(),
+            methods: self.methods.iter().map(SummaryMethods::json).collect(),
+            largest_income: self.largest[0].json(),
+            largest_expense: self.largest[1].json(),
+            peak_income: self.peak[0].json(),
+            peak_expense: self.peak[1].json(),
+            lend_borrows: self.lend_borrows.json(),
+        }
+    }
 }
 
 type CacheTxs = HashMap<i32, Vec<FullTx>>;
@@ -86,11 +100,42 @@ pub(crate) fn get_summary(
 }
 
 impl SummaryView {
+    /// Compares the tag and method movement of this summary with a summary of another period
+    #[must_use]
+    pub fn compare(&self, second: &SummaryView, conn: &impl ConnCache) -> SummaryComparison {
+        get_summary_comparison(&self.txs, &second.txs, conn)
+    }
+
     pub fn tags_array(
         &self,
         compare: Option<&SummaryView>,
         conn: &impl ConnCache,
     ) -> Vec<Vec<String>> {
+        let with_change = compare.is_some() && self.nature != FetchNature::All;
+
+        self.tag_summaries(compare, conn)
+            .iter()
+            .map(|tag| tag.array(with_change))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn tags_json(
+        &self,
+        compare: Option<&SummaryView>,
+        conn: &impl ConnCache,
+    ) -> Vec<TagSummaryJson> {
+        self.tag_summaries(compare, conn)
+            .iter()
+            .map(SummaryTag::json)
+            .collect()
+    }
+
+    fn tag_summaries(
+        &self,
+        compare: Option<&SummaryView>,
+        conn: &impl ConnCache,
+    ) -> Vec<SummaryTag> {
         let mut income_tags = HashMap::new();
         let mut expense_tags = HashMap::new();
         let mut borrow_tags = HashMap::new();
@@ -155,8 +200,6 @@ impl SummaryView {
         for tag in conn.cache().tags.values() {
             let mut no_push = true;
 
-            let mut to_push = vec![tag.name.clone()];
-
             let mut income_percentage = 0.0;
             let mut expense_percentage = 0.0;
 
@@ -197,31 +240,25 @@ impl SummaryView {
                 continue;
             }
 
-            to_push.push(format!("{income_amount:.2}"));
-            to_push.push(format!("{expense_amount:.2}"));
-
-            to_push.push(format!("{income_percentage:.2}"));
-            to_push.push(format!("{expense_percentage:.2}"));
+            let mut previous_income = None;
+            let mut previous_expense = None;
 
             if !no_mom_yoy && compare.is_some() {
-                let compare_income = compare_income_tags.get(&tag.name);
-
-                let compare_expense = compare_expense_tags.get(&tag.name);
-
-                to_push.push(compare_change_opt(
-                    income_amount,
-                    compare_income.map(Cent::dollar),
-                ));
-                to_push.push(compare_change_opt(
-                    expense_amount,
-                    compare_expense.map(Cent::dollar),
-                ));
+                previous_income = compare_income_tags.get(&tag.name).map(Cent::dollar);
+                previous_expense = compare_expense_tags.get(&tag.name).map(Cent::dollar);
             }
 
-            to_push.push(format!("{borrow_amount:.2}"));
-            to_push.push(format!("{lend_amount:.2}"));
-
-            to_return.push(to_push);
+            to_return.push(SummaryTag {
+                tag: tag.name.clone(),
+                income: income_amount,
+                expense: expense_amount,
+                income_percentage,
+                expense_percentage,
+                previous_income,
+                previous_expense,
+                borrows: borrow_amount,
+                lends: lend_amount,
+            });
         }
 
         to_return
@@ -426,7 +463,8 @@ impl SummaryView {
             let mut mom_yoy_earning = None;
             let mut mom_yoy_expense = None;
 
-            if let FetchNature::Monthly = self.nature {
+            // Monthly averages don't mean much on periods that are a month or shorter
+            if let FetchNature::Monthly | FetchNature::Weekly = self.nature {
                 average_earning = None;
                 average_expense = None;
             }
@@ -439,16 +477,16 @@ impl SummaryView {
                 let last_earning = comparison[index].total_earning;
                 let last_expense = comparison[index].total_expense;
 
-                let current_earning = method_earning[&method.name].dollar();
-                let current_expense = method_expense[&method.name].dollar();
+                let current_earning = method_earning[&method.name];
+                let current_expense = method_expense[&method.name];
 
-                mom_yoy_earning = Some(compare_change(current_earning, last_earning));
-                mom_yoy_expense = Some(compare_change(current_expense, last_expense));
+                mom_yoy_earning = Some(current_earning.percent_change(last_earning.cent()));
+                mom_yoy_expense = Some(current_expense.percent_change(last_expense.cent()));
             }
 
             if !no_mom_yoy && mom_yoy_expense.is_none() && mom_yoy_earning.is_none() {
-                mom_yoy_earning = Some("∞".to_string());
-                mom_yoy_expense = Some("∞".to_string());
+                mom_yoy_earning = Some(None);
+                mom_yoy_expense = Some(None);
             }
 
             let method_summary = SummaryMethods::new(
@@ -466,7 +504,7 @@ impl SummaryView {
             method_data.push(method_summary);
         }
 
-        if let FetchNature::Monthly = self.nature {
+        if let FetchNature::Monthly | FetchNature::Weekly = self.nature {
             average_income = None;
             average_expense = None;
         }
@@ -484,32 +522,24 @@ impl SummaryView {
 
             let lend_borrows_comparison = &last_summary.lend_borrows;
 
-            net_mom_yoy_earning = Some(compare_change(
-                total_income.dollar(),
-                net_comparison.total_income,
-            ));
-            net_mom_yoy_expense = Some(compare_change(
-                total_expense.dollar(),
-                net_comparison.total_expense,
-            ));
-            mom_yoy_borrows = Some(compare_change(
-                outstanding_borrows.dollar(),
-                lend_borrows_comparison.borrows,
-            ));
-            mom_yoy_lends = Some(compare_change(
-                outstanding_lends.dollar(),
-                lend_borrows_comparison.lends,
-            ));
+            net_mom_yoy_earning =
+                Some(total_income.percent_change(net_comparison.total_income.cent()));
+            net_mom_yoy_expense =
+                Some(total_expense.percent_change(net_comparison.total_expense.cent()));
+            mom_yoy_borrows =
+                Some(outstanding_borrows.percent_change(lend_borrows_comparison.borrows.cent()));
+            mom_yoy_lends =
+                Some(outstanding_lends.percent_change(lend_borrows_comparison.lends.cent()));
         }
 
         if !no_mom_yoy && net_mom_yoy_expense.is_none() && net_mom_yoy_earning.is_none() {
-            net_mom_yoy_earning = Some("∞".to_string());
-            net_mom_yoy_expense = Some("∞".to_string());
+            net_mom_yoy_earning = Some(None);
+            net_mom_yoy_expense = Some(None);
         }
 
         if !no_mom_yoy && mom_yoy_borrows.is_none() && mom_yoy_lends.is_none() {
-            mom_yoy_borrows = Some("∞".to_string());
-            mom_yoy_lends = Some("∞".to_string());
+            mom_yoy_borrows = Some(None);
+            mom_yoy_lends = Some(None);
         }
 
         let summary_net = SummaryNet::new(
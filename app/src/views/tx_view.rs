@@ -7,6 +7,8 @@ use rex_shared::models::{Cent, Dollar};
 use std::collections::HashMap;
 
 use crate::conn::DbConn;
+use crate::utils::csv_field;
+use crate::views::{MethodBalanceJson, TxJson, TxViewJson};
 
 pub struct PartialTx<'a> {
     pub from_method: &'a str,
@@ -223,10 +225,10 @@ impl TxViewGroup {
         };
 
         let target_tx = &self.0[index];
-        let ongoing_date = target_tx.tx.date;
+        let ongoing_date = target_tx.tx.date.date();
 
         for tx in self.0.iter().take(index + 1).rev() {
-            if tx.tx.date != ongoing_date {
+            if tx.tx.date.date() != ongoing_date {
                 break;
             }
 
@@ -251,10 +253,10 @@ impl TxViewGroup {
         };
 
         let target_tx = &self.0[index];
-        let ongoing_date = target_tx.tx.date;
+        let ongoing_date = target_tx.tx.date.date();
 
         for tx in self.0.iter().take(index + 1).rev() {
-            if tx.tx.date != ongoing_date {
+            if tx.tx.date.date() != ongoing_date {
                 break;
             }
 
@@ -362,7 +364,7 @@ impl TxViewGroup {
         to_return[0].push(String::from("Total"));
 
         let changes = if let Some(partial_tx) = &partial_tx {
-            let tx_type = partial_tx.tx_type.into();
+            let tx_type = partial_tx.tx_type.try_into()?;
             let amount = Dollar::new(partial_tx.amount.parse()?).cent();
 
             let from_method = db_conn.cache().get_method_id(partial_tx.from_method)?;
@@ -432,7 +434,7 @@ impl TxViewGroup {
             };
 
             if let Some(partial_tx) = &partial_tx {
-                let tx_type = partial_tx.tx_type.into();
+                let tx_type = partial_tx.tx_type.try_into()?;
                 let amount = Dollar::new(partial_tx.amount.parse()?).cent();
 
                 let from_method = db_conn.cache().get_method_id(partial_tx.from_method)?;
@@ -497,6 +499,12 @@ impl TxViewGroup {
                 return Ok(false);
             }
 
+            // The time the user set decides the order of same day txs. Only txs with the same
+            // time, such as untimed ones, can be reordered
+            if tx_1.tx.order_time() != tx_2.tx.order_time() {
+                return Ok(false);
+            }
+
             tx_1.tx.date.date()
         };
 
@@ -544,4 +552,55 @@ impl TxViewGroup {
     pub fn get_tx_balance(&self, index: usize) -> &HashMap<i32, Cent> {
         &self.0[index].balance
     }
+
+    /// All txs with the balance of every method after each of them
+    #[must_use]
+    pub fn json(&self, db_conn: &DbConn) -> Vec<TxViewJson> {
+        let sorted_methods = db_conn.get_tx_methods_sorted();
+
+        self.0
+            .iter()
+            .map(|tx_view| TxViewJson {
+                tx: TxJson::from(&tx_view.tx),
+                balances: MethodBalanceJson::sorted(&tx_view.balance, &sorted_methods),
+            })
+            .collect()
+    }
+
+    /// All txs as CSV with one column for each tx field
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("Date,Details,From Method,To Method,Amount,Type,Tags\n");
+
+        for tx_view in &self.0 {
+            let tx = &tx_view.tx;
+
+            let tags = tx
+                .tags
+                .iter()
+                .map(|tag| tag.name.as_str())
+                .collect::<Vec<&str>>()
+                .join(", ");
+
+            let fields = [
+                tx.date.format("%Y-%m-%d %H:%M").to_string(),
+                tx.details.clone().unwrap_or_default(),
+                tx.from_method.name.clone(),
+                tx.to_method
+                    .as_ref()
+                    .map(|method| method.name.clone())
+                    .unwrap_or_default(),
+                format!("{:.2}", tx.amount.dollar()),
+                tx.tx_type.to_string(),
+                tags,
+            ];
+
+            let fields: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+
+        csv
+    }
 }
@@ -0,0 +1,187 @@
+use anyhow::Result;
+use chrono::{Datelike, NaiveDate};
+use rex_db::ConnCache;
+use rex_db::models::{FetchNature, FullTx, TxType};
+use rex_shared::models::Cent;
+use std::collections::BTreeMap;
+
+use crate::utils::csv_field;
+
+/// Number of rows before the tag rows start. Income, Expense, Net and Savings Rate
+pub const REPORT_TOTAL_ROWS: usize = 4;
+
+const MONTH_HEADERS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Month by month income, expense and tag movement of a year
+pub struct YearlyReport {
+    year: i32,
+    income: [Cent; 12],
+    expense: [Cent; 12],
+    /// Income minus expense of each tag by month
+    tags: BTreeMap<String, [Cent; 12]>,
+    /// Months that have at least one tx
+    active_months: [bool; 12],
+}
+
+pub(crate) fn get_yearly_report(year: i32, db_conn: &mut impl ConnCache) -> Result<YearlyReport> {
+    let date = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+
+    let txs = FullTx::get_txs(date, FetchNature::Yearly, db_conn)?;
+
+    let mut report = YearlyReport {
+        year,
+        income: [Cent::new(0); 12],
+        expense: [Cent::new(0); 12],
+        tags: BTreeMap::new(),
+        active_months: [false; 12],
+    };
+
+    for tx in txs {
+        let month = tx.date.month0() as usize;
+
+        report.active_months[month] = true;
+
+        let tag_movement = match tx.tx_type {
+            TxType::Income => {
+                report.income[month] += tx.amount;
+                tx.amount.value()
+            }
+            TxType::Expense => {
+                report.expense[month] += tx.amount;
+                -tx.amount.value()
+            }
+            _ => continue,
+        };
+
+        // Same as the summary, only the first tag is considered
+        if let Some(tag) = tx.tags.first() {
+            let tag_months = report
+                .tags
+                .entry(tag.name.clone())
+                .or_insert([Cent::new(0); 12]);
+
+            tag_months[month] += tag_movement;
+        }
+    }
+
+    Ok(report)
+}
+
+fn savings_rate(income: Cent, expense: Cent) -> Option<f64> {
+    if income == 0 {
+        return None;
+    }
+
+    let net = (income.value() - expense.value()) as f64;
+
+    Some(net / income.value() as f64 * 100.0)
+}
+
+fn format_cent(value: Cent) -> String {
+    format!("{:.2}", value.dollar().value())
+}
+
+fn format_rate(rate: Option<f64>) -> String {
+    rate.map_or_else(|| "-".to_string(), |rate| format!("{rate:.2}"))
+}
+
+impl YearlyReport {
+    #[must_use]
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// Number of months with any tx. Used for the average column. Minimum 1
+    fn month_count(&self) -> i64 {
+        self.active_months.iter().filter(|m| **m).count().max(1) as i64
+    }
+
+    fn amount_row(&self, name: &str, months: [Cent; 12]) -> Vec<String> {
+        let total: i64 = months.iter().map(Cent::value).sum();
+
+        let mut row = vec![name.to_string()];
+
+        row.extend(months.into_iter().map(format_cent));
+        row.push(format_cent(Cent::new(total)));
+        row.push(format_cent(Cent::new(
+            (total as f64 / self.month_count() as f64).round() as i64,
+        )));
+
+        row
+    }
+
+    fn net(&self) -> [Cent; 12] {
+        std::array::from_fn(|i| self.income[i] - self.expense[i].value())
+    }
+
+    fn savings_rate_row(&self) -> Vec<String> {
+        let rates: Vec<Option<f64>> = (0..12)
+            .map(|i| savings_rate(self.income[i], self.expense[i]))
+            .collect();
+
+        let total_income = Cent::new(self.income.iter().map(Cent::value).sum());
+        let total_expense = Cent::new(self.expense.iter().map(Cent::value).sum());
+
+        // Average of the months that had any income
+        let valid_rates: Vec<f64> = rates.iter().flatten().copied().collect();
+        let average_rate = if valid_rates.is_empty() {
+            None
+        } else {
+            Some(valid_rates.iter().sum::<f64>() / valid_rates.len() as f64)
+        };
+
+        let mut row = vec!["Savings Rate %".to_string()];
+
+        row.extend(rates.into_iter().map(format_rate));
+        row.push(format_rate(savings_rate(total_income, total_expense)));
+        row.push(format_rate(average_rate));
+
+        row
+    }
+
+    /// Names of each column. Row name, 12 months, Total and Average
+    #[must_use]
+    pub fn headers(&self) -> Vec<String> {
+        let mut headers = vec![self.year.to_string()];
+
+        headers.extend(MONTH_HEADERS.iter().map(ToString::to_string));
+        headers.push("Total".to_string());
+        headers.push("Average".to_string());
+
+        headers
+    }
+
+    /// Income, Expense, Net and Savings Rate rows followed by one net movement row for each tag
+    #[must_use]
+    pub fn array(&self) -> Vec<Vec<String>> {
+        let mut rows = vec![
+            self.amount_row("Income", self.income),
+            self.amount_row("Expense", self.expense),
+            self.amount_row("Net", self.net()),
+            self.savings_rate_row(),
+        ];
+
+        for (tag, months) in &self.tags {
+            rows.push(self.amount_row(tag, *months));
+        }
+
+        rows
+    }
+
+    /// The headers and all rows as CSV
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+
+        for row in std::iter::once(self.headers()).chain(self.array()) {
+            let fields: Vec<String> = row.iter().map(|f| csv_field(f)).collect();
+
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+
+        csv
+    }
+}
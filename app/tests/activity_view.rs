@@ -1,5 +1,7 @@
 use chrono::{Datelike, Local};
-use std::fs;
+use diesel::RunQueryDsl;
+use rex_app::error::{IntegrityError, RexError};
+use rex_db::ConnCache;
 
 use crate::common::{add_tx, create_test_db};
 
@@ -7,8 +9,7 @@ mod common;
 
 #[test]
 fn activity_view_empty() {
-    let file_name = "test_activity_empty.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     // Query a month with no activity
     let view = db_conn
@@ -19,15 +20,11 @@ fn activity_view_empty() {
     assert_eq!(view.total_activity(), 0);
     assert!(view.get_activity_table().is_empty());
     assert_eq!(view.get_activity_txs_table(None).len(), 0);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn activity_view_after_add_tx() {
-    let file_name = "test_activity_add.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -70,15 +67,11 @@ fn activity_view_after_add_tx() {
 
     // add_extra_field is false for AddTx
     assert!(!view.add_extra_field(0));
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn activity_view_edit_has_extra_field() {
-    let file_name = "test_activity_edit.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     let old_tx = add_tx(
         &mut db_conn,
@@ -135,15 +128,11 @@ fn activity_view_edit_has_extra_field() {
         }
     }
     assert!(found, "Expected an EditTx activity with extra field");
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn activity_view_get_table_returns_formatted() {
-    let file_name = "test_activity_table.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -186,15 +175,11 @@ fn activity_view_get_table_returns_formatted() {
 
     let txs_table = view.get_activity_txs_table(Some(0));
     assert!(!txs_table.is_empty());
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn activity_view_position_swap_has_extra_field() {
-    let file_name = "test_activity_swap.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     use chrono::NaiveDate;
     use rex_app::conn::FetchNature;
@@ -254,7 +239,55 @@ fn activity_view_position_swap_has_extra_field() {
         }
     }
     assert!(found, "Expected a PositionSwap or EditTx activity");
+}
+
+#[test]
+fn activity_view_corrupted_edit_activity() {
+    let mut db_conn = create_test_db();
+
+    let old_tx = add_tx(
+        &mut db_conn,
+        "2024-09-01",
+        "Original",
+        "Cash",
+        "",
+        "10.00",
+        "Expense",
+        "A",
+    );
+
+    let new_tx = rex_app::modifier::parse_tx_fields(
+        "2024-09-01",
+        "Changed",
+        "Cash",
+        "",
+        "20.00",
+        "Expense",
+        &db_conn,
+    )
+    .unwrap();
+    db_conn.edit_tx(&old_tx, new_tx, "A").unwrap();
+
+    // Drop one of the two txs the edit activity saved, as if the row was removed outside of Rex
+    diesel::sql_query(
+        "DELETE FROM activity_tx_tags WHERE tx_id = (SELECT MAX(id) FROM activity_txs)",
+    )
+    .execute(db_conn.conn())
+    .unwrap();
+    diesel::sql_query("DELETE FROM activity_txs WHERE id = (SELECT MAX(id) FROM activity_txs)")
+        .execute(db_conn.conn())
+        .unwrap();
+
+    let now = Local::now().naive_local();
+    let month = now.format("%B").to_string();
+    let year = now.year().to_string();
 
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
+    let error = db_conn
+        .get_activity_view_with_str(&month, &year)
+        .map(|_| ())
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        RexError::Integrity(IntegrityError::InvalidValue(ref e)) if e.kind == "activity id"
+    ));
 }
@@ -1,8 +1,7 @@
-use chrono::NaiveDate;
+use chrono::{Local, NaiveDate, NaiveTime};
 use rex_app::conn::{DbConn, FetchNature};
 use rex_app::modifier::parse_tx_fields;
 use rex_db::ConnCache;
-use std::fs;
 
 use crate::common::create_test_db;
 
@@ -10,9 +9,7 @@ mod common;
 
 #[test]
 fn add_tx_test() {
-    let file_name = "test_add_tx.sqlite";
-
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_method = db_conn.cache().get_method_id("Cash").unwrap();
     let bank_method = db_conn.cache().get_method_id("Bank").unwrap();
 
@@ -185,15 +182,11 @@ fn add_tx_test() {
     // Bank method should still be at zero — no txs touched it
     let jul_balance = jul_txs.get_tx_balance(0);
     assert_eq!(jul_balance[&bank_method].value(), 0);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn add_tx_yearly_fetch() {
-    let file_name = "test_add_tx_yearly.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx_helper(
         &mut db_conn,
@@ -231,15 +224,11 @@ fn add_tx_yearly_fetch() {
         .fetch_txs_with_date(date, FetchNature::Yearly)
         .unwrap();
     assert_eq!(yearly.len(), 3);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn add_tx_all_fetch() {
-    let file_name = "test_add_tx_all.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx_helper(
         &mut db_conn,
@@ -275,15 +264,11 @@ fn add_tx_all_fetch() {
     let date = NaiveDate::from_ymd_opt(2023, 6, 1).unwrap();
     let all = db_conn.fetch_txs_with_date(date, FetchNature::All).unwrap();
     assert_eq!(all.len(), 3);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn add_tx_multiple_methods_balance_independently() {
-    let file_name = "test_add_tx_multi_method.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
     let bank_id = db_conn.cache().get_method_id("Bank").unwrap();
 
@@ -327,15 +312,11 @@ fn add_tx_multiple_methods_balance_independently() {
     let balance = tx_view.get_tx_balance(2);
     assert_eq!(balance[&cash_id].value(), 45000);
     assert_eq!(balance[&bank_id].value(), 20000);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn add_tx_transfer_sets_to_method() {
-    let file_name = "test_add_tx_transfer.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx_helper(
         &mut db_conn,
@@ -372,15 +353,11 @@ fn add_tx_transfer_sets_to_method() {
     let array = transfer.to_array(false);
     let method_str = &array[2];
     assert!(method_str.contains("→"));
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn add_tx_to_array_search_format_differs() {
-    let file_name = "test_add_tx_to_array.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx_helper(
         &mut db_conn,
@@ -411,9 +388,44 @@ fn add_tx_to_array_search_format_differs() {
     let display_arr = tx.to_array(false);
     assert!(display_arr[0].contains("Wed")); // 2024-05-15 is a Wednesday
     assert!(!display_arr[0].contains("2024"));
+}
+
+#[test]
+fn untimed_tx_saved_at_midnight() {
+    let db_conn = create_test_db();
+    let today = Local::now().date_naive();
+    let untimed_date = today.format("%Y-%m-%d").to_string();
+    let timed_date = today.format("%Y-%m-%d 13:45").to_string();
+
+    // Today's txs do not get the current time invented for them
+    let untimed = parse_tx_fields(
+        &untimed_date,
+        "Lunch",
+        "Cash",
+        "",
+        "10.00",
+        "Expense",
+        &db_conn,
+    )
+    .unwrap();
+    assert_eq!(untimed.date, today.and_time(NaiveTime::MIN));
+    assert!(!untimed.has_time);
 
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
+    let timed = parse_tx_fields(
+        &timed_date,
+        "Lunch",
+        "Cash",
+        "",
+        "10.00",
+        "Expense",
+        &db_conn,
+    )
+    .unwrap();
+    assert_eq!(
+        timed.date.time(),
+        NaiveTime::from_hms_opt(13, 45, 0).unwrap()
+    );
+    assert!(timed.has_time);
 }
 
 // Local helper to reduce boilerplate
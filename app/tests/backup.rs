@@ -0,0 +1,351 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use rex_app::backup::{
+    AutoBackup, BackupFile, Retention, copy_db, create_backup, list_backups, prune_backups,
+    save_safety_copy,
+};
+use rex_app::conn::{DbConn, FetchNature};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::common::{add_tx, create_file_db, create_test_db};
+
+mod common;
+
+fn time(date: &str, time: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(&format!("{date} {time}"), "%Y-%m-%d %H:%M:%S").unwrap()
+}
+
+#[test]
+fn backup_file_names() {
+    let created = time("2025-03-01", "18:45:12");
+
+    assert_eq!(
+        BackupFile::file_name(created, false),
+        "rex-20250301-184512.sqlite"
+    );
+    assert_eq!(
+        BackupFile::file_name(created, true),
+        "rex-20250301-184512.sqlite.enc"
+    );
+
+    let backup = BackupFile::from_path(Path::new("backups/rex-20250301-184512.sqlite.enc"));
+    assert_eq!(
+        backup,
+        Some(BackupFile {
+            path: PathBuf::from("backups/rex-20250301-184512.sqlite.enc"),
+            created,
+            sequence: 0,
+            encrypted: true,
+            safety: false,
+        })
+    );
+
+    let backup = BackupFile::from_path(Path::new("rex-safety-20250301-184512-2.sqlite")).unwrap();
+    assert_eq!(
+        (
+            backup.created,
+            backup.sequence,
+            backup.encrypted,
+            backup.safety
+        ),
+        (created, 2, false, true)
+    );
+
+    for name in [
+        "rex.sqlite",
+        "rex-20250301.sqlite",
+        "rex-20251301-184512.sqlite",
+        "rex-20250301-184512.sqlite.tmp",
+        "other-20250301-184512.sqlite",
+        "rex-20250301-184512-.sqlite",
+        "rex-20250301-184512-0.sqlite",
+        "rex-20250301-184512-x.sqlite",
+        "rex-safety-20250301.sqlite",
+    ] {
+        assert_eq!(BackupFile::from_path(Path::new(name)), None);
+    }
+}
+
+#[test]
+fn backup_retention() {
+    // Two backups a day for 100 days, newest first
+    let newest = time("2025-06-30", "20:00:00");
+    let backups = (0..200)
+        .map(|index| {
+            let created = newest - Duration::hours(12 * index);
+
+            BackupFile {
+                path: PathBuf::from(BackupFile::file_name(created, false)),
+                created,
+                sequence: 0,
+                encrypted: false,
+                safety: false,
+            }
+        })
+        .collect::<Vec<BackupFile>>();
+
+    let kept = |retention: Retention| {
+        let removed = retention.to_remove(&backups);
+
+        backups
+            .iter()
+            .filter(|backup| !removed.contains(backup))
+            .map(|backup| backup.created.format("%Y-%m-%d %H").to_string())
+            .collect::<Vec<String>>()
+    };
+
+    let retention = Retention {
+        keep_last: 3,
+        daily: 0,
+        weekly: 0,
+        monthly: 0,
+    };
+    assert_eq!(
+        kept(retention),
+        ["2025-06-30 20", "2025-06-30 08", "2025-06-29 20"]
+    );
+
+    let retention = Retention {
+        keep_last: 1,
+        daily: 3,
+        weekly: 0,
+        monthly: 0,
+    };
+    assert_eq!(
+        kept(retention),
+        ["2025-06-30 20", "2025-06-29 20", "2025-06-28 20"]
+    );
+
+    // 2025-06-30 is a Monday so the week before ends on Sunday 2025-06-29
+    let retention = Retention {
+        keep_last: 1,
+        daily: 0,
+        weekly: 3,
+        monthly: 0,
+    };
+    assert_eq!(
+        kept(retention),
+        ["2025-06-30 20", "2025-06-29 20", "2025-06-22 20"]
+    );
+
+    let retention = Retention {
+        keep_last: 1,
+        daily: 0,
+        weekly: 0,
+        monthly: 12,
+    };
+    assert_eq!(
+        kept(retention),
+        [
+            "2025-06-30 20",
+            "2025-05-31 20",
+            "2025-04-30 20",
+            "2025-03-31 20"
+        ]
+    );
+
+    // Rules overlap instead of adding up
+    let retention = Retention {
+        keep_last: 2,
+        daily: 2,
+        weekly: 1,
+        monthly: 1,
+    };
+    assert_eq!(
+        kept(retention),
+        ["2025-06-30 20", "2025-06-30 08", "2025-06-29 20"]
+    );
+
+    // The newest backup is never removed
+    let retention = Retention {
+        keep_last: 0,
+        daily: 0,
+        weekly: 0,
+        monthly: 0,
+    };
+    assert_eq!(kept(retention), ["2025-06-30 20"]);
+
+    assert_eq!(Retention::default().to_remove(&backups).len(), 200 - 17);
+}
+
+#[test]
+fn backup_files() {
+    let file_name = "test_backup_files.sqlite";
+    let backup_dir = Path::new("test_backup_files");
+    let mut db_conn = create_file_db(file_name);
+
+    if backup_dir.exists() {
+        fs::remove_dir_all(backup_dir).unwrap();
+    }
+    fs::create_dir(backup_dir).unwrap();
+
+    add_tx(
+        &mut db_conn,
+        "2024-06-15",
+        "Salary",
+        "Cash",
+        "",
+        "100.00",
+        "Income",
+        "Salary",
+    );
+
+    // Backups work while the DB is open
+    let first = time("2025-01-01", "10:00:00");
+    let backup_path = create_backup(Path::new(file_name), backup_dir, first).unwrap();
+    assert_eq!(backup_path, backup_dir.join("rex-20250101-100000.sqlite"));
+
+    add_tx(
+        &mut db_conn,
+        "2024-06-15",
+        "",
+        "Cash",
+        "",
+        "20.00",
+        "Expense",
+        "Food",
+    );
+
+    let second = time("2025-01-02", "10:00:00");
+    create_backup(Path::new(file_name), backup_dir, second).unwrap();
+
+    // Not a backup, left alone
+    fs::write(backup_dir.join("rex.sqlite"), "").unwrap();
+
+    let backups = list_backups(backup_dir).unwrap();
+    assert_eq!(
+        backups
+            .iter()
+            .map(|backup| backup.created)
+            .collect::<Vec<NaiveDateTime>>(),
+        [second, first]
+    );
+
+    let date = NaiveDate::from_ymd_opt(2024, 6, 15).unwrap();
+
+    for (backup, tx_count) in backups.iter().zip([2, 1]) {
+        let mut backup_conn = DbConn::new(backup.path.to_str().unwrap());
+        let txs = backup_conn
+            .fetch_txs_with_date(date, FetchNature::Monthly)
+            .unwrap();
+
+        assert_eq!(txs.len(), tx_count);
+    }
+
+    let retention = Retention {
+        keep_last: 1,
+        daily: 0,
+        weekly: 0,
+        monthly: 0,
+    };
+    let removed = prune_backups(backup_dir, &retention).unwrap();
+    assert_eq!(removed, [backups[1].path.clone()]);
+    assert_eq!(list_backups(backup_dir).unwrap(), [backups[0].clone()]);
+    assert!(backup_dir.join("rex.sqlite").exists());
+
+    // Safety copies are never pruned, however old they are
+    let safety_path = save_safety_copy(Path::new(file_name), backup_dir, None, first).unwrap();
+    assert_eq!(
+        safety_path,
+        backup_dir.join("rex-safety-20250101-100000.sqlite")
+    );
+
+    // Backups made in the same second get the next number instead of replacing each other
+    let third = time("2025-01-03", "10:00:00");
+    let paths = (0..3)
+        .map(|_| create_backup(Path::new(file_name), backup_dir, third).unwrap())
+        .collect::<Vec<PathBuf>>();
+    assert_eq!(
+        paths,
+        [
+            backup_dir.join("rex-20250103-100000.sqlite"),
+            backup_dir.join("rex-20250103-100000-1.sqlite"),
+            backup_dir.join("rex-20250103-100000-2.sqlite"),
+        ]
+    );
+
+    // Newest first, the highest number being the newest of the same second
+    let listed = list_backups(backup_dir).unwrap();
+    assert_eq!(
+        listed
+            .iter()
+            .map(|backup| backup.path.clone())
+            .collect::<Vec<PathBuf>>()[..3],
+        [paths[2].clone(), paths[1].clone(), paths[0].clone()]
+    );
+
+    let removed = prune_backups(backup_dir, &retention).unwrap();
+    assert_eq!(removed.len(), 3);
+    assert_eq!(
+        list_backups(backup_dir).unwrap(),
+        [listed[0].clone(), listed[4].clone()]
+    );
+    assert!(listed[4].safety);
+
+    // Copies replace the target
+    let copy_path = backup_dir.join("copy.sqlite");
+    fs::write(&copy_path, "not a database").unwrap();
+    copy_db(Path::new(file_name), &copy_path).unwrap();
+
+    let mut copy_conn = DbConn::new(copy_path.to_str().unwrap());
+    assert_eq!(copy_conn.get_final_balances_json().unwrap().total, 80.0);
+
+    drop(copy_conn);
+    drop(db_conn);
+    fs::remove_dir_all(backup_dir).unwrap();
+    fs::remove_file(file_name).unwrap();
+}
+
+#[test]
+fn auto_backup_due() {
+    let minutes = |count: u64| std::time::Duration::from_secs(count * 60);
+
+    let auto_backup = AutoBackup {
+        interval_minutes: 10,
+        changes: 5,
+    };
+
+    // Nothing changed so there is nothing to back up
+    assert!(!auto_backup.is_due(minutes(60), 0));
+
+    assert!(!auto_backup.is_due(minutes(9), 4));
+    assert!(auto_backup.is_due(minutes(10), 1));
+    assert!(auto_backup.is_due(minutes(0), 5));
+
+    let interval_only = AutoBackup {
+        interval_minutes: 10,
+        changes: 0,
+    };
+    assert!(!interval_only.is_due(minutes(9), 100));
+    assert!(interval_only.is_due(minutes(10), 1));
+
+    let turned_off = AutoBackup {
+        interval_minutes: 0,
+        changes: 0,
+    };
+    assert!(!turned_off.is_enabled());
+    assert!(!turned_off.is_due(minutes(1000), 1000));
+    assert!(AutoBackup::default().is_enabled());
+
+    // Every change made through the connection is counted
+    let mut db_conn = create_test_db();
+    let start = db_conn.changes();
+
+    let tx = add_tx(
+        &mut db_conn,
+        "2024-06-15",
+        "Salary",
+        "Cash",
+        "",
+        "100.00",
+        "Income",
+        "Salary",
+    );
+    db_conn.rename_tx_method("Other", "Wallet").unwrap();
+    db_conn.delete_tx(&tx).unwrap();
+    assert_eq!(db_conn.changes() - start, 3);
+
+    // Reading does not count
+    db_conn.get_final_balances_json().unwrap();
+    assert_eq!(db_conn.changes() - start, 3);
+}
@@ -4,7 +4,6 @@ use rex_db::ConnCache;
 use rex_db::models::Balance;
 use rex_shared::models::Cent;
 use std::collections::HashMap;
-use std::fs;
 
 use crate::common::{add_tx, create_test_db};
 
@@ -54,8 +53,7 @@ fn get_final_balance_map(db_conn: &mut rex_app::conn::DbConn) -> HashMap<String,
 
 #[test]
 fn balance_table_add_tx_updates_current_month() {
-    let file_name = "test_balance_add.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     assert_eq!(get_final_balance_map(&mut db_conn)["Cash"], Cent::new(0));
 
@@ -78,15 +76,11 @@ fn balance_table_add_tx_updates_current_month() {
         get_final_balance_map(&mut db_conn)["Cash"],
         Cent::new(50000)
     );
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn balance_table_sequential_months_cascade() {
-    let file_name = "test_balance_months.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -125,15 +119,11 @@ fn balance_table_sequential_months_cascade() {
 
     let sep_last = get_last_balance(&mut db_conn, 2024, 9, FetchNature::Monthly);
     assert_eq!(sep_last["Cash"], Cent::new(80000));
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn balance_table_delete_cascades_forward() {
-    let file_name = "test_balance_delete.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -197,15 +187,11 @@ fn balance_table_delete_cascades_forward() {
         get_final_balance_map(&mut db_conn)["Cash"],
         Cent::new(70000)
     );
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn balance_table_edit_cascades_forward() {
-    let file_name = "test_balance_edit.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -266,15 +252,11 @@ fn balance_table_edit_cascades_forward() {
         get_final_balance_map(&mut db_conn)["Cash"],
         Cent::new(80000)
     );
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn balance_table_mid_insert_updates_forward() {
-    let file_name = "test_balance_mid.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -336,15 +318,11 @@ fn balance_table_mid_insert_updates_forward() {
 
     let may_last = get_last_balance(&mut db_conn, 2024, 5, FetchNature::Monthly);
     assert_eq!(may_last["Cash"], Cent::new(110000));
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn balance_table_multiple_methods() {
-    let file_name = "test_balance_methods.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -371,15 +349,11 @@ fn balance_table_multiple_methods() {
     assert_eq!(apr["Cash"], Cent::new(70000));
     assert_eq!(apr["Bank"], Cent::new(30000));
     assert_eq!(apr["Other"], Cent::new(0));
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn balance_table_year_boundary_cascades() {
-    let file_name = "test_balance_year.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -414,15 +388,11 @@ fn balance_table_year_boundary_cascades() {
         get_final_balance_map(&mut db_conn)["Cash"],
         Cent::new(80000)
     );
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn balance_table_out_of_order_insert_maintains_cascade() {
-    let file_name = "test_balance_ooo.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -485,15 +455,11 @@ fn balance_table_out_of_order_insert_maintains_cascade() {
         get_final_balance_map(&mut db_conn)["Cash"],
         Cent::new(100000)
     );
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn balance_table_transfer_across_months() {
-    let file_name = "test_balance_transfer.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -533,7 +499,4 @@ fn balance_table_transfer_across_months() {
     let jun = get_month_balance_map(&mut db_conn, 2024, 6);
     assert_eq!(jun["Cash"], Cent::new(60000));
     assert_eq!(jun["Bank"], Cent::new(40000));
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
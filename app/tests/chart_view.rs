@@ -1,7 +1,7 @@
 use chrono::NaiveDate;
 use rex_app::conn::FetchNature;
+use rex_app::views::{CashFlow, CashFlowGrouping};
 use rex_db::ConnCache;
-use std::fs;
 
 use crate::common::{add_tx, create_test_db};
 
@@ -9,8 +9,7 @@ mod common;
 
 #[test]
 fn chart_view_empty() {
-    let file_name = "test_chart_empty.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     let chart = db_conn
         .get_chart_view_with_str("January", "2024", FetchNature::Monthly)
@@ -18,15 +17,11 @@ fn chart_view_empty() {
 
     assert!(chart.is_empty());
     assert_eq!(chart.len(), 0);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn chart_view_date_bounds() {
-    let file_name = "test_chart_bounds.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -77,15 +72,11 @@ fn chart_view_date_bounds() {
 
     assert!(chart.contains_date(&NaiveDate::from_ymd_opt(2024, 3, 15).unwrap()));
     assert!(!chart.contains_date(&NaiveDate::from_ymd_opt(2024, 3, 10).unwrap()));
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn chart_view_get_tx_and_balance() {
-    let file_name = "test_chart_tx_balance.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
     let bank_id = db_conn.cache().get_method_id("Bank").unwrap();
 
@@ -143,15 +134,11 @@ fn chart_view_get_tx_and_balance() {
 
     assert_eq!(b2[&cash_id].value(), 60000);
     assert_eq!(b2[&bank_id].value(), 30000);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn chart_view_single_tx() {
-    let file_name = "test_chart_single.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -175,7 +162,135 @@ fn chart_view_single_tx() {
         NaiveDate::from_ymd_opt(2024, 9, 1).unwrap()
     );
     assert!(chart.contains_date(&NaiveDate::from_ymd_opt(2024, 9, 1).unwrap()));
+}
+
+#[test]
+fn chart_view_cash_flow() {
+    let mut db_conn = create_test_db();
+
+    let txs = [
+        ("2023-11-20", "", "40.00", "Expense"),
+        ("2024-01-03", "", "1000.00", "Income"),
+        ("2024-01-03", "", "100.00", "Expense"),
+        ("2024-01-05", "Bank", "300.00", "Transfer"),
+        ("2024-03-10", "", "50.00", "Expense"),
+    ];
+
+    for (date, to_method, amount, tx_type) in txs {
+        add_tx(
+            &mut db_conn,
+            date,
+            "",
+            "Cash",
+            to_method,
+            amount,
+            tx_type,
+            "Tag",
+        );
+    }
+
+    let as_values = |flows: Vec<CashFlow>| -> Vec<(String, i64, i64, i64)> {
+        flows
+            .into_iter()
+            .map(|f| {
+                (
+                    f.label().to_string(),
+                    f.income().value(),
+                    f.expense().value(),
+                    f.net().value(),
+                )
+            })
+            .collect()
+    };
+
+    // Monthly mode groups by day from the first to the last tx day
+    let chart = db_conn
+        .get_chart_view_with_str("January", "2024", FetchNature::Monthly)
+        .unwrap();
+
+    assert_eq!(chart.cash_flow_grouping(), CashFlowGrouping::Day);
+    assert_eq!(
+        as_values(chart.cash_flow()),
+        vec![
+            ("2024-01-03".to_string(), 100000, 10000, 90000),
+            ("2024-01-04".to_string(), 0, 0, 0),
+            ("2024-01-05".to_string(), 0, 0, 0),
+        ]
+    );
+
+    let chart = db_conn
+        .get_chart_view_with_str("January", "2024", FetchNature::Yearly)
+        .unwrap();
+
+    assert_eq!(chart.cash_flow_grouping(), CashFlowGrouping::Month);
+    assert_eq!(
+        as_values(chart.cash_flow()),
+        vec![
+            ("2024-01".to_string(), 100000, 10000, 90000),
+            ("2024-02".to_string(), 0, 0, 0),
+            ("2024-03".to_string(), 0, 5000, -5000),
+        ]
+    );
+
+    let chart = db_conn
+        .get_chart_view_with_str("January", "2024", FetchNature::All)
+        .unwrap();
+
+    assert_eq!(chart.cash_flow_grouping(), CashFlowGrouping::Year);
+    assert_eq!(
+        as_values(chart.cash_flow()),
+        vec![
+            ("2023".to_string(), 0, 4000, -4000),
+            ("2024".to_string(), 100000, 15000, 85000),
+        ]
+    );
+
+    let chart = db_conn
+        .get_chart_view_with_str("February", "2024", FetchNature::Monthly)
+        .unwrap();
+    assert!(chart.cash_flow().is_empty());
+}
+
+#[test]
+fn chart_view_tag_monthly_expense() {
+    let mut db_conn = create_test_db();
+
+    let txs = [
+        ("2024-01-03", "100.00", "Expense", "Food"),
+        ("2024-01-20", "30.00", "Expense", "Food, Eating Out"),
+        ("2024-03-10", "50.00", "Expense", "Food"),
+        ("2024-03-15", "500.00", "Expense", "Rent"),
+        ("2024-03-16", "999.00", "Income", "Food"),
+    ];
+
+    for (date, amount, tx_type, tags) in txs {
+        add_tx(&mut db_conn, date, "", "Cash", "", amount, tx_type, tags);
+    }
+
+    let chart = db_conn
+        .get_chart_view_with_str("January", "2024", FetchNature::Yearly)
+        .unwrap();
+
+    let tag_months = chart.tag_monthly_expense();
+
+    let month = |m: u32| NaiveDate::from_ymd_opt(2024, m, 1).unwrap();
+    let values = |tag: &str| -> Vec<(NaiveDate, i64)> {
+        tag_months[tag]
+            .iter()
+            .map(|(d, amount)| (*d, amount.value()))
+            .collect()
+    };
+
+    assert_eq!(
+        values("Food"),
+        vec![(month(1), 13000), (month(2), 0), (month(3), 5000)]
+    );
+    // A tx only counts towards its first tag
+    assert!(!tag_months.contains_key("Eating Out"));
+    assert_eq!(
+        values("Rent"),
+        vec![(month(1), 0), (month(2), 0), (month(3), 50000)]
+    );
 
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
+    assert_eq!(chart.expense_tags(), vec!["Rent", "Food"]);
 }
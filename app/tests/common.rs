@@ -4,8 +4,19 @@ use rex_app::modifier::parse_tx_fields;
 use rex_db::models::FullTx;
 use std::fs;
 
+#[allow(dead_code)]
+#[must_use]
+pub fn create_test_db() -> DbConn {
+    let mut conn = DbConn::in_memory().unwrap();
+    add_test_methods(&mut conn);
+
+    conn
+}
+
+/// Same as [`create_test_db`] but saved to `file_name`, for tests that need the DB on disk
+#[allow(dead_code)]
 #[must_use]
-pub fn create_test_db(file_name: &str) -> DbConn {
+pub fn create_file_db(file_name: &str) -> DbConn {
     if let Ok(metadata) = fs::metadata(file_name)
         && metadata.is_file()
     {
@@ -13,14 +24,18 @@ pub fn create_test_db(file_name: &str) -> DbConn {
     }
 
     let mut conn = get_conn(file_name);
+    add_test_methods(&mut conn);
+
+    conn
+}
+
+fn add_test_methods(conn: &mut DbConn) {
     let tx_methods = ["Cash", "Bank", "Other"]
         .iter()
         .map(|name| name.to_string())
         .collect::<Vec<String>>();
 
     conn.add_new_methods(&tx_methods).unwrap();
-
-    conn
 }
 
 #[allow(dead_code)]
@@ -47,6 +62,8 @@ pub fn add_tx(
 
     db_conn.add_new_tx(new_tx, tags).unwrap();
 
+    // The date may come with a time after it
+    let date = date.split(' ').next().unwrap();
     let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
     let tx_view = db_conn
         .fetch_txs_with_date(date, FetchNature::Monthly)
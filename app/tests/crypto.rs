@@ -0,0 +1,114 @@
+use chrono::NaiveDate;
+use rex_app::conn::{DbConn, FetchNature};
+use rex_app::crypto::{CryptoError, EncryptedDb, encrypted_path, is_encrypted};
+use rex_app::error::RexError;
+use std::fs;
+use std::path::Path;
+
+use crate::common::{add_tx, create_file_db};
+
+mod common;
+
+fn tx_count(db_conn: &mut DbConn) -> usize {
+    let date = NaiveDate::from_ymd_opt(2024, 6, 15).unwrap();
+
+    db_conn
+        .fetch_txs_with_date(date, FetchNature::Monthly)
+        .unwrap()
+        .len()
+}
+
+#[test]
+fn encrypted_db() {
+    let file_name = "test_encrypted_db.sqlite";
+    let mut db_conn = create_file_db(file_name);
+
+    add_tx(
+        &mut db_conn,
+        "2024-06-15",
+        "Secret Salary",
+        "Cash",
+        "",
+        "100.00",
+        "Income",
+        "Salary",
+    );
+    drop(db_conn);
+
+    let path = encrypted_path(Path::new(file_name));
+    assert_eq!(path, Path::new("test_encrypted_db.sqlite.enc"));
+
+    let db = EncryptedDb::create(Path::new(file_name), &path, "hunter2").unwrap();
+    assert!(is_encrypted(&path));
+    assert!(!is_encrypted(Path::new(file_name)));
+
+    // Nothing readable is left in the encrypted file
+    let encrypted = fs::read(&path).unwrap();
+    assert!(!encrypted.windows(6).any(|w| w == b"SQLite"));
+    assert!(!encrypted.windows(13).any(|w| w == b"Secret Salary"));
+
+    let decrypted_path = db.db_path();
+    drop(db);
+
+    // The decrypted copy is deleted once the DB is dropped
+    assert!(!decrypted_path.exists());
+
+    let error = EncryptedDb::open(&path, "hunter3").err().unwrap();
+    assert!(matches!(
+        error,
+        RexError::Crypto(CryptoError::WrongPassphrase)
+    ));
+
+    let error = EncryptedDb::open(&path, "").err().unwrap();
+    assert!(matches!(
+        error,
+        RexError::Crypto(CryptoError::EmptyPassphrase)
+    ));
+
+    let error = EncryptedDb::open(Path::new(file_name), "hunter2")
+        .err()
+        .unwrap();
+    assert!(matches!(
+        error,
+        RexError::Crypto(CryptoError::NotEncrypted(_))
+    ));
+
+    // Changes made on the decrypted copy are kept once it is saved
+    let db = EncryptedDb::open(&path, "hunter2").unwrap();
+    let mut db_conn = DbConn::new(db.db_path().to_str().unwrap());
+    assert_eq!(tx_count(&mut db_conn), 1);
+
+    add_tx(
+        &mut db_conn,
+        "2024-06-15",
+        "",
+        "Cash",
+        "",
+        "20.00",
+        "Expense",
+        "Food",
+    );
+    drop(db_conn);
+
+    let backup_path = Path::new("test_encrypted_db_backup.sqlite.enc");
+    db.save().unwrap();
+    db.save_to(backup_path).unwrap();
+    drop(db);
+
+    for path in [path.as_path(), backup_path] {
+        let db = EncryptedDb::open(path, "hunter2").unwrap();
+        let mut db_conn = DbConn::new(db.db_path().to_str().unwrap());
+
+        assert_eq!(tx_count(&mut db_conn), 2);
+        assert_eq!(db_conn.get_final_balances_json().unwrap().total, 80.0);
+    }
+
+    // The plain file was never touched
+    let mut db_conn = DbConn::new(file_name);
+    assert_eq!(tx_count(&mut db_conn), 1);
+
+    drop(db_conn);
+    fs::remove_file(file_name).unwrap();
+    fs::remove_file(path).unwrap();
+    fs::remove_file(backup_path).unwrap();
+}
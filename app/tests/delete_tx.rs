@@ -1,7 +1,6 @@
 use chrono::NaiveDate;
 use rex_app::conn::FetchNature;
 use rex_db::ConnCache;
-use std::fs;
 
 use crate::common::{add_tx, create_test_db};
 
@@ -9,9 +8,7 @@ mod common;
 
 #[test]
 fn delete_income_reverses_balance() {
-    let file_name = "test_delete_income.sqlite";
-
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
 
     let date = NaiveDate::from_ymd_opt(2024, 6, 15).unwrap();
@@ -39,16 +36,11 @@ fn delete_income_reverses_balance() {
         .fetch_txs_with_date(date, FetchNature::Monthly)
         .unwrap();
     assert_eq!(tx_view.len(), 0);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn delete_expense_reverses_balance() {
-    let file_name = "test_delete_expense.sqlite";
-
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
 
     // First add income so we have a balance to spend from
@@ -92,16 +84,11 @@ fn delete_expense_reverses_balance() {
     assert_eq!(tx_view.len(), 1, "Only the income should remain");
     // Balance should revert to 1000
     assert_eq!(tx_view.get_tx_balance(0)[&cash_id].value(), 100000);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn delete_transfer_reverses_both_methods() {
-    let file_name = "test_delete_transfer.sqlite";
-
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
     let bank_id = db_conn.cache().get_method_id("Bank").unwrap();
 
@@ -146,16 +133,11 @@ fn delete_transfer_reverses_both_methods() {
     // Cash back to 1000, Bank back to 0
     assert_eq!(tx_view.get_tx_balance(0)[&cash_id].value(), 100000);
     assert_eq!(tx_view.get_tx_balance(0)[&bank_id].value(), 0);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn delete_first_of_multiple_txs_maintains_other_balances() {
-    let file_name = "test_delete_first_tx.sqlite";
-
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
 
     let tx1 = add_tx(
@@ -205,16 +187,11 @@ fn delete_first_of_multiple_txs_maintains_other_balances() {
     assert_eq!(tx_view.len(), 2);
     // Without the income: 0 - 100 - 50 = -150
     assert_eq!(tx_view.get_tx_balance(1)[&cash_id].value(), -15000);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn delete_middle_tx_maintains_remaining_order() {
-    let file_name = "test_delete_middle_tx.sqlite";
-
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -262,7 +239,4 @@ fn delete_middle_tx_maintains_remaining_order() {
     // Details should be Salary and Food (Rent removed)
     assert_eq!(tx_view.get_tx(0).details, Some("Salary".to_string()));
     assert_eq!(tx_view.get_tx(1).details, Some("Food".to_string()));
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
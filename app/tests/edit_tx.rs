@@ -2,7 +2,6 @@ use chrono::NaiveDate;
 use rex_app::conn::FetchNature;
 use rex_app::modifier::parse_tx_fields;
 use rex_db::ConnCache;
-use std::fs;
 
 use crate::common::{add_tx, create_test_db};
 
@@ -10,9 +9,7 @@ mod common;
 
 #[test]
 fn edit_income_amount_updates_balance() {
-    let file_name = "test_edit_income_amount.sqlite";
-
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
     let date = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
 
@@ -45,16 +42,11 @@ fn edit_income_amount_updates_balance() {
         .unwrap();
     assert_eq!(tx_view.len(), 1);
     assert_eq!(tx_view.get_tx_balance(0)[&cash_id].value(), 70000);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn edit_expense_amount_updates_balance() {
-    let file_name = "test_edit_expense_amount.sqlite";
-
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
     let date = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
 
@@ -104,16 +96,11 @@ fn edit_expense_amount_updates_balance() {
     assert_eq!(tx_view.len(), 2);
     // 1000 - 300 = 700
     assert_eq!(tx_view.get_tx_balance(1)[&cash_id].value(), 70000);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn edit_income_to_expense_reverses_balance() {
-    let file_name = "test_edit_income_to_expense.sqlite";
-
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
     let date = NaiveDate::from_ymd_opt(2024, 8, 1).unwrap();
 
@@ -147,16 +134,11 @@ fn edit_income_to_expense_reverses_balance() {
     assert_eq!(tx_view.len(), 1);
     // Changed from +500 to -500
     assert_eq!(tx_view.get_tx_balance(0)[&cash_id].value(), -50000);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn edit_change_method_moves_balance() {
-    let file_name = "test_edit_change_method.sqlite";
-
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
     let bank_id = db_conn.cache().get_method_id("Bank").unwrap();
     let date = NaiveDate::from_ymd_opt(2024, 9, 1).unwrap();
@@ -208,16 +190,11 @@ fn edit_change_method_moves_balance() {
     // Cash: 1000 (unchanged), Bank: -50
     assert_eq!(tx_view.get_tx_balance(1)[&cash_id].value(), 100000);
     assert_eq!(tx_view.get_tx_balance(1)[&bank_id].value(), -5000);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn edit_details_preserves_balance() {
-    let file_name = "test_edit_details.sqlite";
-
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
     let date = NaiveDate::from_ymd_opt(2024, 10, 1).unwrap();
 
@@ -251,16 +228,11 @@ fn edit_details_preserves_balance() {
     assert_eq!(tx_view.len(), 1);
     assert_eq!(tx_view.get_tx(0).details, Some("New name".to_string()));
     assert_eq!(tx_view.get_tx_balance(0)[&cash_id].value(), 30000);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn edit_tags_changes_only_tags() {
-    let file_name = "test_edit_tags.sqlite";
-
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
     let date = NaiveDate::from_ymd_opt(2024, 11, 1).unwrap();
 
@@ -302,7 +274,4 @@ fn edit_tags_changes_only_tags() {
         .collect();
     assert_eq!(tags, vec!["NewTag", "ExtraTag"]);
     assert_eq!(tx_view.get_tx_balance(0)[&cash_id].value(), -20000);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
@@ -0,0 +1,52 @@
+use rex_app::error::{IntegrityError, RexError, ValidationError};
+use rex_app::modifier::parse_tx_fields;
+use rex_app::ui_helper::VerifierError;
+use rex_db::NotFoundError;
+
+use crate::common::create_test_db;
+
+mod common;
+
+#[test]
+fn structured_errors() {
+    let mut db_conn = create_test_db();
+
+    let error = db_conn.fetch_tx_with_id(999).unwrap_err();
+    assert!(matches!(error, RexError::NotFound(NotFoundError::Tx(999))));
+
+    let error = db_conn.get_tx_method_by_name("Wallet").unwrap_err();
+    assert!(matches!(
+        error,
+        RexError::NotFound(NotFoundError::TxMethod(ref name)) if name == "Wallet"
+    ));
+
+    let error = db_conn.rename_tx_method("Wallet", "Card").unwrap_err();
+    assert!(matches!(
+        error,
+        RexError::NotFound(NotFoundError::TxMethod(_))
+    ));
+
+    // Renaming to a name that is already taken breaks the unique constraint
+    let error = db_conn.rename_tx_method("Cash", "Bank").unwrap_err();
+    assert!(matches!(
+        error,
+        RexError::Integrity(IntegrityError::Unique(_))
+    ));
+    assert!(db_conn.get_tx_method_by_name("Cash").is_ok());
+
+    let error = parse_tx_fields("2024-13-01", "", "Cash", "", "10", "Expense", &db_conn)
+        .map(|_| ())
+        .unwrap_err();
+    assert!(matches!(
+        RexError::from(error),
+        RexError::Validation(ValidationError::Verifier(VerifierError::InvalidDate))
+    ));
+
+    let error = parse_tx_fields("2024-01-01", "", "Wallet", "", "10", "Expense", &db_conn)
+        .map(|_| ())
+        .unwrap_err();
+    assert!(matches!(
+        RexError::from(error),
+        RexError::NotFound(NotFoundError::TxMethod(_))
+    ));
+}
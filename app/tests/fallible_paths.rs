@@ -0,0 +1,88 @@
+use chrono::NaiveDate;
+use diesel::RunQueryDsl;
+use rex_app::conn::{DbConn, FetchNature};
+use rex_app::error::{IntegrityError, RexError, ValidationError};
+use rex_app::ui_helper::VerifierError;
+use rex_db::ConnCache;
+use rex_db::models::{ActivityNature, AmountType, TxType};
+
+use crate::common::{add_tx, create_test_db};
+
+mod common;
+
+#[test]
+fn invalid_values_do_not_panic() {
+    assert!(TxType::try_from("Borrow Repay").is_ok());
+    assert!(TxType::try_from("Gift").is_err());
+    assert!(AmountType::try_from("more_than").is_ok());
+    assert!(AmountType::try_from("around").is_err());
+    assert!(ActivityNature::try_from("edit_tx").is_ok());
+
+    let error = ActivityNature::try_from("undo_tx").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Unknown activity type 'undo_tx' found in the database"
+    );
+
+    let error = DbConn::try_new("missing_dir/nested/test.sqlite")
+        .map(|_| ())
+        .unwrap_err();
+    assert!(matches!(error, RexError::Open(_)));
+}
+
+#[test]
+fn fallible_db_paths() {
+    let mut db_conn = create_test_db();
+
+    add_tx(
+        &mut db_conn,
+        "2024-06-15",
+        "Lunch",
+        "Cash",
+        "",
+        "10.00",
+        "Expense",
+        "Food",
+    );
+
+    let error = db_conn
+        .fetch_txs_with_str("Junuary", "2024", FetchNature::Monthly)
+        .map(|_| ())
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        RexError::Validation(ValidationError::Verifier(VerifierError::InvalidMonth))
+    ));
+
+    let error = db_conn
+        .get_summary_with_str("June", "20x4", FetchNature::Monthly)
+        .map(|_| ())
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        RexError::Validation(ValidationError::Verifier(VerifierError::InvalidYear))
+    ));
+
+    let tx_view = db_conn
+        .fetch_txs_with_str("June", "2024", FetchNature::Monthly)
+        .unwrap();
+    assert_eq!(tx_view.len(), 1);
+
+    // A row that was changed outside of Rex, skipping the table checks
+    diesel::sql_query("PRAGMA ignore_check_constraints = ON")
+        .execute(db_conn.conn())
+        .unwrap();
+    diesel::sql_query("UPDATE txs SET tx_type = 'Gift'")
+        .execute(db_conn.conn())
+        .unwrap();
+
+    let date = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+    let error = db_conn
+        .fetch_txs_with_date(date, FetchNature::Monthly)
+        .map(|_| ())
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        RexError::Integrity(IntegrityError::InvalidValue(ref e)) if e.value == "Gift"
+    ));
+}
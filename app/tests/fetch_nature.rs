@@ -0,0 +1,175 @@
+use chrono::NaiveDate;
+use rex_app::conn::{DbConn, FetchNature};
+
+use crate::common::{add_tx, create_test_db};
+
+mod common;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn tx_details(db_conn: &mut DbConn, d: NaiveDate, nature: FetchNature) -> Vec<String> {
+    let tx_view = db_conn.fetch_txs_with_date(d, nature).unwrap();
+
+    (0..tx_view.len())
+        .map(|i| tx_view.get_tx(i).details.clone().unwrap_or_default())
+        .collect()
+}
+
+fn add_sample_txs(db_conn: &mut DbConn) {
+    let txs = [
+        ("2024-03-15", "Bonus", "500.00", "Income"),
+        ("2024-03-31", "Rent", "200.00", "Expense"),
+        ("2024-04-01", "Salary", "1000.00", "Income"),
+        ("2024-04-03", "Lunch", "10.00", "Expense"),
+        ("2024-04-07", "Dinner", "20.00", "Expense"),
+        ("2024-04-08", "Coffee", "5.00", "Expense"),
+    ];
+
+    for (tx_date, details, amount, tx_type) in txs {
+        add_tx(
+            db_conn, tx_date, details, "Cash", "", amount, tx_type, "Misc",
+        );
+    }
+}
+
+#[test]
+fn date_range_per_nature() {
+    let d = date(2024, 5, 15);
+
+    assert_eq!(
+        FetchNature::Weekly.date_range(d),
+        Some((date(2024, 5, 13), date(2024, 5, 19)))
+    );
+    assert_eq!(
+        FetchNature::Quarterly.date_range(d),
+        Some((date(2024, 4, 1), date(2024, 6, 30)))
+    );
+    assert_eq!(
+        FetchNature::Monthly.date_range(d),
+        Some((date(2024, 5, 1), date(2024, 5, 31)))
+    );
+    assert_eq!(
+        FetchNature::Custom(date(2024, 2, 10), date(2024, 1, 5)).date_range(d),
+        Some((date(2024, 1, 5), date(2024, 2, 10)))
+    );
+    assert_eq!(FetchNature::All.date_range(d), None);
+}
+
+#[test]
+fn previous_period_per_nature() {
+    let d = date(2024, 5, 15);
+
+    assert_eq!(
+        FetchNature::Weekly.previous_period(d),
+        Some((date(2024, 5, 12), FetchNature::Weekly))
+    );
+    assert_eq!(
+        FetchNature::Quarterly.previous_period(d),
+        Some((date(2024, 3, 31), FetchNature::Quarterly))
+    );
+
+    let custom = FetchNature::Custom(date(2024, 3, 10), date(2024, 3, 19));
+    assert_eq!(
+        custom.previous_period(d),
+        Some((
+            date(2024, 2, 29),
+            FetchNature::Custom(date(2024, 2, 29), date(2024, 3, 9))
+        ))
+    );
+
+    assert_eq!(FetchNature::All.previous_period(d), None);
+}
+
+#[test]
+fn next_period_per_nature() {
+    let d = date(2024, 5, 15);
+
+    assert_eq!(
+        FetchNature::Weekly.next_period(d),
+        Some((date(2024, 5, 20), FetchNature::Weekly))
+    );
+    assert_eq!(
+        FetchNature::Quarterly.next_period(d),
+        Some((date(2024, 7, 1), FetchNature::Quarterly))
+    );
+
+    let custom = FetchNature::Custom(date(2024, 2, 20), date(2024, 2, 29));
+    assert_eq!(
+        custom.next_period(d),
+        Some((
+            date(2024, 3, 1),
+            FetchNature::Custom(date(2024, 3, 1), date(2024, 3, 10))
+        ))
+    );
+
+    assert_eq!(FetchNature::All.next_period(d), None);
+}
+
+#[test]
+fn weekly_fetch_with_mid_month_opening_balance() {
+    let mut db_conn = create_test_db();
+    add_sample_txs(&mut db_conn);
+
+    let cash_id = db_conn.get_tx_method_by_name("Cash").unwrap().id;
+
+    // 2024-04-03 is a Wednesday. The week runs from Apr 1 to Apr 7
+    assert_eq!(
+        tx_details(&mut db_conn, date(2024, 4, 3), FetchNature::Weekly),
+        vec!["Salary", "Lunch", "Dinner"]
+    );
+
+    // The next week starts mid-month so the opening balance comes from the txs before it
+    let chart_view = db_conn
+        .get_chart_view_with_date(date(2024, 4, 8), FetchNature::Weekly)
+        .unwrap();
+
+    assert_eq!(chart_view.get_balance(0)[&cash_id].value(), 126_500);
+}
+
+#[test]
+fn quarterly_and_custom_fetch() {
+    let mut db_conn = create_test_db();
+    add_sample_txs(&mut db_conn);
+
+    let cash_id = db_conn.get_tx_method_by_name("Cash").unwrap().id;
+
+    assert_eq!(
+        tx_details(&mut db_conn, date(2024, 2, 1), FetchNature::Quarterly),
+        vec!["Bonus", "Rent"]
+    );
+
+    let chart_view = db_conn
+        .get_chart_view_with_date(date(2024, 5, 20), FetchNature::Quarterly)
+        .unwrap();
+
+    // Q1 ended with 300. The first Q2 tx adds 1000 to it
+    assert_eq!(chart_view.get_balance(0)[&cash_id].value(), 130_000);
+
+    let custom = FetchNature::Custom(date(2024, 3, 31), date(2024, 4, 3));
+    assert_eq!(
+        tx_details(&mut db_conn, date(2024, 1, 1), custom),
+        vec!["Rent", "Salary", "Lunch"]
+    );
+
+    let chart_view = db_conn
+        .get_chart_view_with_date(date(2024, 1, 1), custom)
+        .unwrap();
+    assert_eq!(chart_view.get_balance(0)[&cash_id].value(), 30_000);
+}
+
+#[test]
+fn weekly_summary_totals() {
+    let mut db_conn = create_test_db();
+    add_sample_txs(&mut db_conn);
+
+    let summary_view = db_conn
+        .get_summary_with_date(date(2024, 4, 5), FetchNature::Weekly)
+        .unwrap();
+    let full = summary_view.generate_summary(None, &db_conn);
+
+    let net = full.net_array();
+    assert_eq!(net[0][1], "1000.00");
+    assert_eq!(net[0][2], "30.00");
+}
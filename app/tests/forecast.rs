@@ -0,0 +1,129 @@
+use chrono::NaiveDate;
+
+use crate::common::{add_tx, create_test_db};
+
+mod common;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+#[test]
+fn forecast_and_runway() {
+    let mut db_conn = create_test_db();
+
+    add_tx(
+        &mut db_conn,
+        "2023-12-20",
+        "Salary",
+        "Cash",
+        "",
+        "2000",
+        "Income",
+        "Job",
+    );
+    add_tx(
+        &mut db_conn,
+        "2024-03-01",
+        "Interest",
+        "Bank",
+        "",
+        "600",
+        "Income",
+        "Interest",
+    );
+
+    for month in 1..=7 {
+        add_tx(
+            &mut db_conn,
+            &format!("2024-{month:02}-05"),
+            "Rent",
+            "Cash",
+            "",
+            "200",
+            "Expense",
+            "Rent",
+        );
+    }
+
+    let cash_id = db_conn.get_tx_method_by_name("Cash").unwrap().id;
+    let bank_id = db_conn.get_tx_method_by_name("Bank").unwrap().id;
+
+    let forecast = db_conn.get_forecast_view(date(2024, 7, 15)).unwrap();
+
+    assert_eq!(forecast.lookback_months(), 6);
+
+    assert_eq!(forecast.balance(cash_id).value(), 60_000);
+    assert_eq!(forecast.monthly_change(cash_id).value(), -20_000);
+    assert_eq!(forecast.monthly_change(bank_id).value(), 10_000);
+
+    assert_eq!(forecast.projected_balance(cash_id, 1).value(), 40_000);
+    assert_eq!(forecast.projected_balance(cash_id, 3).value(), 0);
+    assert_eq!(forecast.projected_balance(cash_id, 6).value(), -60_000);
+
+    let series: Vec<(NaiveDate, i64)> = forecast
+        .projected_series(cash_id, 2)
+        .into_iter()
+        .map(|(d, balance)| (d, balance.value()))
+        .collect();
+    assert_eq!(
+        series,
+        vec![(date(2024, 8, 15), 40_000), (date(2024, 9, 15), 20_000)]
+    );
+
+    assert_eq!(forecast.runway(cash_id), Some(4));
+    assert_eq!(forecast.runway(bank_id), None);
+    assert_eq!(forecast.total_runway(), Some(13));
+
+    assert_eq!(
+        forecast.tags_array(),
+        vec![
+            vec!["Interest", "100.00", "0.00", "100.00"],
+            vec!["Rent", "0.00", "200.00", "-200.00"],
+        ]
+    );
+}
+
+#[test]
+fn forecast_with_partial_month() {
+    let mut db_conn = create_test_db();
+
+    add_tx(
+        &mut db_conn,
+        "2024-05-02",
+        "Salary",
+        "Cash",
+        "",
+        "500",
+        "Income",
+        "Job",
+    );
+    add_tx(
+        &mut db_conn,
+        "2024-05-10",
+        "Lunch",
+        "Cash",
+        "",
+        "100",
+        "Expense",
+        "Food",
+    );
+
+    let cash_id = db_conn.get_tx_method_by_name("Cash").unwrap().id;
+
+    // No whole month before the date so the current month is used
+    let forecast = db_conn.get_forecast_view(date(2024, 5, 20)).unwrap();
+
+    assert_eq!(forecast.lookback_months(), 1);
+    assert_eq!(forecast.balance(cash_id).value(), 40_000);
+    assert_eq!(forecast.monthly_change(cash_id).value(), 40_000);
+    assert_eq!(forecast.runway(cash_id), None);
+
+    assert_eq!(
+        forecast.tags_array(),
+        vec![
+            vec!["Food", "0.00", "100.00", "-100.00"],
+            vec!["Job", "500.00", "0.00", "500.00"],
+        ]
+    );
+}
@@ -0,0 +1,163 @@
+use chrono::NaiveDate;
+use rex_app::conn::{DbConn, FetchNature};
+use rex_app::error::RexError;
+use std::fs;
+use std::path::Path;
+
+use crate::common::{add_tx, create_file_db, create_test_db};
+
+mod common;
+
+#[test]
+fn in_memory_db() {
+    let mut db_conn = create_test_db();
+    let mut other_conn = create_test_db();
+
+    add_tx(
+        &mut db_conn,
+        "2024-06-15",
+        "Salary",
+        "Cash",
+        "",
+        "100.00",
+        "Income",
+        "Salary",
+    );
+
+    let date = NaiveDate::from_ymd_opt(2024, 6, 15).unwrap();
+
+    assert_eq!(
+        db_conn
+            .fetch_txs_with_date(date, FetchNature::Monthly)
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // Every in-memory DB is separate
+    assert_eq!(
+        other_conn
+            .fetch_txs_with_date(date, FetchNature::Monthly)
+            .unwrap()
+            .len(),
+        0
+    );
+    assert_eq!(db_conn.get_final_balances_json().unwrap().total, 100.0);
+    assert_eq!(other_conn.get_final_balances_json().unwrap().total, 0.0);
+}
+
+#[test]
+fn in_memory_copy() {
+    let file_name = "test_in_memory_copy.sqlite";
+    let mut db_conn = create_file_db(file_name);
+
+    add_tx(
+        &mut db_conn,
+        "2024-06-15",
+        "Salary",
+        "Cash",
+        "",
+        "100.00",
+        "Income",
+        "Salary",
+    );
+    drop(db_conn);
+
+    let date = NaiveDate::from_ymd_opt(2024, 6, 15).unwrap();
+    let mut memory_conn = DbConn::in_memory_copy(file_name).unwrap();
+
+    let tx_view = memory_conn
+        .fetch_txs_with_date(date, FetchNature::Monthly)
+        .unwrap();
+    assert_eq!(tx_view.len(), 1);
+    assert_eq!(
+        tx_view.get_tx(0).to_array(true),
+        ["2024-06-15", "Salary", "Cash", "100.00", "Income", "Salary"]
+    );
+
+    // The copy works like the real DB
+    add_tx(
+        &mut memory_conn,
+        "2024-06-16",
+        "",
+        "Cash",
+        "Bank",
+        "40.00",
+        "Transfer",
+        "",
+    );
+    let old_tx = memory_conn
+        .fetch_txs_with_date(date, FetchNature::Monthly)
+        .unwrap()
+        .get_tx(0)
+        .clone();
+    memory_conn.delete_tx(&old_tx).unwrap();
+
+    let balances = memory_conn.get_final_balances_json().unwrap();
+    assert_eq!(balances.methods[0].balance, -40.0);
+    assert_eq!(balances.methods[1].balance, 40.0);
+    drop(memory_conn);
+
+    // None of the changes reached the file
+    let mut db_conn = DbConn::new(file_name);
+    let tx_view = db_conn
+        .fetch_txs_with_date(date, FetchNature::Monthly)
+        .unwrap();
+    assert_eq!(tx_view.len(), 1);
+    assert_eq!(db_conn.get_final_balances_json().unwrap().total, 100.0);
+
+    // A missing DB is not created
+    let missing = "test_in_memory_missing.sqlite";
+    let error = DbConn::in_memory_copy(missing).err().unwrap();
+    assert!(matches!(error, RexError::Open(_)));
+    assert!(!Path::new(missing).exists());
+
+    drop(db_conn);
+    fs::remove_file(file_name).unwrap();
+}
+
+#[test]
+fn in_memory_copy_keeps_deleted_ids() {
+    let file_name = "test_in_memory_copy_ids.sqlite";
+    let mut db_conn = create_file_db(file_name);
+
+    add_tx(
+        &mut db_conn,
+        "2024-06-15",
+        "Salary",
+        "Cash",
+        "",
+        "100.00",
+        "Income",
+        "Salary",
+    );
+    let deleted_tx = add_tx(
+        &mut db_conn,
+        "2024-06-16",
+        "Lunch",
+        "Cash",
+        "",
+        "10.00",
+        "Expense",
+        "Food",
+    );
+    db_conn.delete_tx(&deleted_tx).unwrap();
+    drop(db_conn);
+
+    // The copy must not give out the id of the deleted tx again
+    let mut memory_conn = DbConn::in_memory_copy(file_name).unwrap();
+    let new_tx = add_tx(
+        &mut memory_conn,
+        "2024-06-17",
+        "Dinner",
+        "Cash",
+        "",
+        "20.00",
+        "Expense",
+        "Food",
+    );
+    assert!(new_tx.id > deleted_tx.id);
+
+    drop(memory_conn);
+    fs::remove_file(file_name).unwrap();
+}
@@ -0,0 +1,157 @@
+use chrono::{Local, NaiveDate};
+use rex_app::conn::FetchNature;
+use rex_app::modifier::parse_search_fields;
+use serde_json::json;
+
+use crate::common::{add_tx, create_test_db};
+
+mod common;
+
+#[test]
+fn json_output_of_views() {
+    let mut db_conn = create_test_db();
+
+    add_tx(
+        &mut db_conn,
+        "2024-05-10",
+        "Old salary",
+        "Cash",
+        "",
+        "500.00",
+        "Income",
+        "Salary",
+    );
+    add_tx(
+        &mut db_conn,
+        "2024-06-01 09:30",
+        "Salary",
+        "Cash",
+        "",
+        "1000.00",
+        "Income",
+        "Salary",
+    );
+    add_tx(
+        &mut db_conn,
+        "2024-06-15",
+        "Lunch",
+        "Cash",
+        "",
+        "200.50",
+        "Expense",
+        "Food, Work",
+    );
+    add_tx(
+        &mut db_conn,
+        "2024-06-20",
+        "",
+        "Cash",
+        "Bank",
+        "100.00",
+        "Transfer",
+        "",
+    );
+
+    let date = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+    let tx_view = db_conn
+        .fetch_txs_with_date(date, FetchNature::Monthly)
+        .unwrap();
+
+    let txs = serde_json::to_value(tx_view.json(&db_conn)).unwrap();
+
+    assert_eq!(txs.as_array().unwrap().len(), 3);
+    assert_eq!(txs[0]["date"], "2024-06-01T09:30:00");
+    assert_eq!(txs[0]["details"], "Salary");
+    assert_eq!(txs[0]["amount"], json!(1000.0));
+    assert_eq!(txs[0]["tx_type"], "Income");
+    assert_eq!(txs[0]["to_method"], json!(null));
+    assert_eq!(
+        txs[0]["balances"],
+        json!([
+            {"method": "Cash", "balance": 1500.0},
+            {"method": "Bank", "balance": 0.0},
+            {"method": "Other", "balance": 0.0},
+        ])
+    );
+    assert_eq!(txs[1]["tags"], json!(["Food", "Work"]));
+    assert_eq!(txs[1]["balances"][0]["balance"], json!(1299.5));
+    assert_eq!(txs[2]["details"], json!(null));
+    assert_eq!(txs[2]["to_method"], "Bank");
+    assert_eq!(txs[2]["balances"][1]["balance"], json!(100.0));
+
+    let balances = serde_json::to_value(db_conn.get_final_balances_json().unwrap()).unwrap();
+
+    assert_eq!(
+        balances,
+        json!({
+            "methods": [
+                {"method": "Cash", "balance": 1199.5},
+                {"method": "Bank", "balance": 100.0},
+                {"method": "Other", "balance": 0.0},
+            ],
+            "total": 1299.5,
+        })
+    );
+
+    let search = parse_search_fields("", "", "", "", ">300", "", "", &db_conn).unwrap();
+    let search_view = db_conn.search_txs(search).unwrap();
+    let found = serde_json::to_value(search_view.json()).unwrap();
+
+    assert_eq!(found.as_array().unwrap().len(), 2);
+    assert_eq!(found[0]["details"], "Old salary");
+    assert!(found[0].get("balances").is_none());
+
+    let summary_view = db_conn
+        .get_summary_with_date(date, FetchNature::Monthly)
+        .unwrap();
+    let (last_date, last_nature) = FetchNature::Monthly.previous_period(date).unwrap();
+    let last_summary_view = db_conn
+        .get_summary_with_date(last_date, last_nature)
+        .unwrap();
+
+    let last_summary = last_summary_view.generate_summary(None, &db_conn);
+    let summary = summary_view.generate_summary(Some(&last_summary), &db_conn);
+    let summary = serde_json::to_value(summary.json()).unwrap();
+
+    assert_eq!(summary["net"]["total_income"], json!(1000.0));
+    assert_eq!(summary["net"]["total_expense"], json!(200.5));
+    assert_eq!(summary["net"]["average_income"], json!(null));
+    assert_eq!(summary["net"]["income_change"], json!(100.0));
+    // Nothing was spent in the previous month to compare with
+    assert_eq!(summary["net"]["expense_change"], json!(null));
+    assert_eq!(summary["methods"][0]["method"], "Cash");
+    assert_eq!(summary["methods"][0]["total_income"], json!(1000.0));
+    assert_eq!(summary["largest_expense"]["date"], "2024-06-15");
+    assert_eq!(summary["largest_expense"]["method"], "Cash");
+    assert_eq!(summary["peak_income"]["month"], "2024-06");
+    assert_eq!(summary["lend_borrows"]["borrows"], json!(0.0));
+
+    let mut tags = summary_view.tags_json(Some(&last_summary_view), &db_conn);
+    tags.sort_by(|a, b| a.tag.cmp(&b.tag));
+    let tags = serde_json::to_value(tags).unwrap();
+
+    assert_eq!(tags.as_array().unwrap().len(), 2);
+    assert_eq!(tags[0]["tag"], "Food");
+    assert_eq!(tags[0]["expense"], json!(200.5));
+    assert_eq!(tags[0]["expense_percentage"], json!(100.0));
+    assert_eq!(tags[0]["expense_change"], json!(null));
+    assert_eq!(tags[1]["tag"], "Salary");
+    assert_eq!(tags[1]["income_change"], json!(100.0));
+
+    // Activities are saved with the current date
+    let now = Local::now();
+    let activity_view = db_conn
+        .get_activity_view_with_str(&now.format("%B").to_string(), &now.format("%Y").to_string())
+        .unwrap();
+    let activities = serde_json::to_value(activity_view.json()).unwrap();
+
+    // 4 added txs and the search
+    assert_eq!(activities.as_array().unwrap().len(), 5);
+    assert_eq!(activities[0]["activity_type"], "add_tx");
+    assert_eq!(activities[0]["txs"][0]["details"], "Old salary");
+    assert_eq!(activities[0]["txs"][0]["amount"], "500.00");
+    assert_eq!(activities[3]["txs"][0]["to_method"], "Bank");
+    assert_eq!(activities[4]["activity_type"], "search_tx");
+    assert_eq!(activities[4]["txs"][0]["amount"], ">300.00");
+    assert_eq!(activities[4]["txs"][0]["from_method"], json!(null));
+}
@@ -1,7 +1,7 @@
 use chrono::NaiveDate;
 use rex_app::conn::FetchNature;
+use rex_app::modifier::parse_tx_fields;
 use rex_db::ConnCache;
-use std::fs;
 
 use crate::common::{add_tx, create_test_db};
 
@@ -18,8 +18,7 @@ fn tx_details_order(db_conn: &mut rex_app::conn::DbConn, date: NaiveDate) -> Vec
 
 #[test]
 fn swap_two_txs_same_day() {
-    let file_name = "test_pos_swap_two.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let date = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
 
     add_tx(
@@ -58,15 +57,11 @@ fn swap_two_txs_same_day() {
         tx_details_order(&mut db_conn, date),
         vec![Some("B".into()), Some("A".into())]
     );
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn swap_two_txs_twice_restores_original_order() {
-    let file_name = "test_pos_swap_two_twice.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let date = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
 
     add_tx(
@@ -114,15 +109,11 @@ fn swap_two_txs_twice_restores_original_order() {
         tx_details_order(&mut db_conn, date),
         vec![Some("First".into()), Some("Second".into())]
     );
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn swap_three_txs_first_and_last() {
-    let file_name = "test_pos_swap_three_first_last.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let date = NaiveDate::from_ymd_opt(2024, 8, 1).unwrap();
 
     add_tx(
@@ -181,15 +172,11 @@ fn swap_three_txs_first_and_last() {
             Some("First".into())
         ]
     );
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn swap_three_txs_adjacent_middle_pair() {
-    let file_name = "test_pos_swap_three_adjacent.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let date = NaiveDate::from_ymd_opt(2024, 9, 1).unwrap();
 
     add_tx(
@@ -248,15 +235,11 @@ fn swap_three_txs_adjacent_middle_pair() {
             Some("Middle".into())
         ]
     );
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn swap_different_dates_returns_false() {
-    let file_name = "test_pos_swap_diff_date.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -294,15 +277,11 @@ fn swap_different_dates_returns_false() {
         tx_details_order(&mut db_conn, date),
         vec![Some("Oct".into()), Some("Oct2".into())]
     );
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn swap_three_txs_first_and_middle() {
-    let file_name = "test_pos_swap_three_first_middle.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let date = NaiveDate::from_ymd_opt(2024, 11, 1).unwrap();
 
     add_tx(
@@ -354,15 +333,11 @@ fn swap_three_txs_first_and_middle() {
             Some("Last".into())
         ]
     );
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn swap_persists_after_multiple_swaps() {
-    let file_name = "test_pos_swap_persist.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let date = NaiveDate::from_ymd_opt(2024, 12, 1).unwrap();
 
     // Add 4 txs all on same day
@@ -466,15 +441,11 @@ fn swap_persists_after_multiple_swaps() {
             Some("A".into()),
         ]
     );
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn swap_four_txs_all_pairwise() {
-    let file_name = "test_pos_swap_four_all.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
 
     add_tx(
@@ -561,15 +532,11 @@ fn swap_four_txs_all_pairwise() {
     assert!(all_details.contains(&"B"));
     assert!(all_details.contains(&"C"));
     assert!(all_details.contains(&"D"));
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn swap_single_tx_noop() {
-    let file_name = "test_pos_swap_single.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let date = NaiveDate::from_ymd_opt(2025, 2, 1).unwrap();
 
     add_tx(
@@ -596,15 +563,11 @@ fn swap_single_tx_noop() {
         tx_details_order(&mut db_conn, date),
         vec![Some("Only".into())]
     );
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn swap_maintains_tx_integrity() {
-    let file_name = "test_pos_swap_integrity.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
 
     add_tx(
@@ -664,7 +627,153 @@ fn swap_maintains_tx_integrity() {
         .map(|i| tx_view.get_tx_balance(i)[&cash_id].value())
         .collect();
     assert_eq!(balances, vec![-3000, 7000, 5000]);
+}
+
+#[test]
+fn same_day_txs_ordered_by_time() {
+    let mut db_conn = create_test_db();
+    let date = NaiveDate::from_ymd_opt(2024, 8, 1).unwrap();
+
+    add_tx(
+        &mut db_conn,
+        "2024-08-01 18:45",
+        "Dinner",
+        "Cash",
+        "",
+        "30.00",
+        "Expense",
+        "Food",
+    );
+    add_tx(
+        &mut db_conn,
+        "2024-08-01 08:15",
+        "Breakfast",
+        "Cash",
+        "",
+        "10.00",
+        "Expense",
+        "Food",
+    );
+    add_tx(
+        &mut db_conn,
+        "2024-08-01",
+        "Untimed",
+        "Cash",
+        "",
+        "5.00",
+        "Expense",
+        "Food",
+    );
+
+    assert_eq!(
+        tx_details_order(&mut db_conn, date),
+        vec![
+            Some("Untimed".into()),
+            Some("Breakfast".into()),
+            Some("Dinner".into())
+        ]
+    );
+
+    let tx_view = db_conn
+        .fetch_txs_with_date(date, FetchNature::Monthly)
+        .unwrap();
+    let times = (0..tx_view.len())
+        .map(|i| tx_view.get_tx(i).date.format("%H:%M").to_string())
+        .collect::<Vec<String>>();
+    assert_eq!(times, vec!["00:00", "08:15", "18:45"]);
+}
+
+#[test]
+fn swap_txs_with_different_times() {
+    let mut db_conn = create_test_db();
+    let date = NaiveDate::from_ymd_opt(2024, 9, 1).unwrap();
 
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
+    add_tx(
+        &mut db_conn,
+        "2024-09-01 09:00",
+        "Morning",
+        "Cash",
+        "",
+        "10.00",
+        "Expense",
+        "A",
+    );
+    add_tx(
+        &mut db_conn,
+        "2024-09-01 21:00",
+        "Night",
+        "Cash",
+        "",
+        "20.00",
+        "Expense",
+        "B",
+    );
+
+    let mut tx_view = db_conn
+        .fetch_txs_with_date(date, FetchNature::Monthly)
+        .unwrap();
+    let changes = db_conn.changes();
+
+    // The time decides the order so the swap is refused and nothing is written
+    assert!(!db_conn.swap_tx_position(0, 1, &mut tx_view).unwrap());
+
+    assert_eq!(
+        tx_details_order(&mut db_conn, date),
+        vec![Some("Morning".into()), Some("Night".into())]
+    );
+    assert_eq!(db_conn.changes(), changes);
+
+    let tx_view = db_conn
+        .fetch_txs_with_date(date, FetchNature::Monthly)
+        .unwrap();
+    assert_eq!(tx_view.get_tx(0).date.format("%H:%M").to_string(), "09:00");
+    assert_eq!(tx_view.get_tx(1).date.format("%H:%M").to_string(), "21:00");
+}
+
+#[test]
+fn swap_legacy_txs_with_wall_clock_times() {
+    let mut db_conn = create_test_db();
+    let date = NaiveDate::from_ymd_opt(2024, 10, 1).unwrap();
+
+    // Older versions saved the current time of today's txs without the user setting one
+    for (details, time) in [("Later", (14, 5, 33)), ("Earlier", (9, 12, 10))] {
+        let mut tx = parse_tx_fields(
+            "2024-10-01",
+            details,
+            "Cash",
+            "",
+            "10.00",
+            "Expense",
+            &db_conn,
+        )
+        .unwrap();
+        tx.date = date.and_hms_opt(time.0, time.1, time.2).unwrap();
+        assert!(!tx.has_time);
+
+        db_conn.add_new_tx(tx, "A").unwrap();
+    }
+
+    // The saved time does not order untimed txs
+    assert_eq!(
+        tx_details_order(&mut db_conn, date),
+        vec![Some("Later".into()), Some("Earlier".into())]
+    );
+
+    let mut tx_view = db_conn
+        .fetch_txs_with_date(date, FetchNature::Monthly)
+        .unwrap();
+    assert!(db_conn.swap_tx_position(0, 1, &mut tx_view).unwrap());
+
+    assert_eq!(
+        tx_details_order(&mut db_conn, date),
+        vec![Some("Earlier".into()), Some("Later".into())]
+    );
+
+    // The times are left as they were
+    let tx_view = db_conn
+        .fetch_txs_with_date(date, FetchNature::Monthly)
+        .unwrap();
+    assert_eq!(tx_view.get_tx(0).date.format("%T").to_string(), "09:12:10");
+    assert_eq!(tx_view.get_tx(1).date.format("%T").to_string(), "14:05:33");
+    assert!(!tx_view.get_tx(0).has_time);
 }
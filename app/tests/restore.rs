@@ -0,0 +1,313 @@
+use chrono::NaiveDateTime;
+use diesel::RunQueryDsl;
+use rex_app::backup::{OpenedBackup, create_backup, list_backups, save_safety_copy};
+use rex_app::conn::try_get_conn_old;
+use rex_app::crypto::{CryptoError, EncryptedDb};
+use rex_app::error::RexError;
+use rex_app::modifier::parse_tx_fields;
+use std::fs;
+use std::path::Path;
+
+use crate::common::{add_tx, create_file_db};
+
+mod common;
+
+fn time(value: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").unwrap()
+}
+
+fn total_balance(backup: &OpenedBackup) -> f64 {
+    backup.summary().unwrap().balances.total
+}
+
+#[test]
+fn restore_backup() {
+    let file_name = "test_restore_backup.sqlite";
+    let backup_dir = Path::new("test_restore_backup");
+    let mut db_conn = create_file_db(file_name);
+
+    if backup_dir.exists() {
+        fs::remove_dir_all(backup_dir).unwrap();
+    }
+    fs::create_dir(backup_dir).unwrap();
+
+    let salary = add_tx(
+        &mut db_conn,
+        "2024-06-15",
+        "Salary",
+        "Cash",
+        "",
+        "100.00",
+        "Income",
+        "Salary",
+    );
+    add_tx(
+        &mut db_conn,
+        "2024-06-16",
+        "Lunch",
+        "Cash",
+        "",
+        "20.00",
+        "Expense",
+        "Food",
+    );
+
+    let backup_path = create_backup(
+        Path::new(file_name),
+        backup_dir,
+        time("2025-01-01 10:00:00"),
+    )
+    .unwrap();
+    let backup = list_backups(backup_dir).unwrap().remove(0);
+    assert_eq!(backup.path, backup_path);
+
+    // Lunch is deleted, Rent is added and Salary is edited after the backup
+    let lunch = db_conn.fetch_tx_with_id(salary.id + 1).unwrap();
+    db_conn.delete_tx(&lunch).unwrap();
+
+    add_tx(
+        &mut db_conn,
+        "2024-06-17",
+        "Rent",
+        "Cash",
+        "",
+        "50.00",
+        "Expense",
+        "Home",
+    );
+
+    let edited = parse_tx_fields(
+        "2024-06-15",
+        "Salary",
+        "Cash",
+        "",
+        "150.00",
+        "Income",
+        &db_conn,
+    )
+    .unwrap();
+    db_conn.edit_tx(&salary, edited, "Salary").unwrap();
+
+    let opened = OpenedBackup::open(&backup, None).unwrap();
+    let summary = opened.summary().unwrap();
+    assert_eq!(summary.tx_count, 2);
+    assert_eq!(summary.balances.total, 80.0);
+
+    let diff = opened.diff(&mut db_conn).unwrap();
+    assert!(!diff.is_empty());
+    assert_eq!(diff.added.len(), 1);
+    assert_eq!(diff.added[0].details.as_deref(), Some("Lunch"));
+    assert_eq!(diff.removed.len(), 1);
+    assert_eq!(diff.removed[0].details.as_deref(), Some("Rent"));
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].0.amount.value(), 15000);
+    assert_eq!(diff.changed[0].1.amount.value(), 10000);
+
+    let safety_path = save_safety_copy(
+        Path::new(file_name),
+        backup_dir,
+        None,
+        time("2025-01-02 10:00:00"),
+    )
+    .unwrap();
+    assert_eq!(list_backups(backup_dir).unwrap().len(), 2);
+
+    db_conn.restore_from(opened.db_path()).unwrap();
+
+    assert!(opened.diff(&mut db_conn).unwrap().is_empty());
+    assert_eq!(db_conn.get_final_balances_json().unwrap().total, 80.0);
+    assert!(db_conn.cache.get_tag_id("Food").is_ok());
+    assert!(db_conn.cache.get_tag_id("Home").is_err());
+
+    // The backup itself is left as it was
+    assert_eq!(total_balance(&opened), 80.0);
+
+    // Restoring the safety copy undoes the restore
+    let safety_copy = list_backups(backup_dir).unwrap().remove(0);
+    assert_eq!(safety_copy.path, safety_path);
+
+    db_conn.restore_from(&safety_path).unwrap();
+    assert_eq!(db_conn.get_final_balances_json().unwrap().total, 100.0);
+
+    let safety_copy = OpenedBackup::open(&safety_copy, None).unwrap();
+    assert!(safety_copy.diff(&mut db_conn).unwrap().is_empty());
+
+    drop(db_conn);
+    fs::remove_file(file_name).unwrap();
+    fs::remove_dir_all(backup_dir).unwrap();
+}
+
+#[test]
+fn restore_encrypted_backup() {
+    let file_name = "test_restore_encrypted_backup.sqlite";
+    let encrypted_name = "test_restore_encrypted_backup.sqlite.enc";
+    let backup_dir = Path::new("test_restore_encrypted_backup");
+    let mut db_conn = create_file_db(file_name);
+
+    if backup_dir.exists() {
+        fs::remove_dir_all(backup_dir).unwrap();
+    }
+    fs::create_dir(backup_dir).unwrap();
+
+    add_tx(
+        &mut db_conn,
+        "2024-06-15",
+        "Salary",
+        "Cash",
+        "",
+        "100.00",
+        "Income",
+        "Salary",
+    );
+
+    let encrypted_db =
+        EncryptedDb::create(Path::new(file_name), Path::new(encrypted_name), "secret").unwrap();
+
+    let backup_path = save_safety_copy(
+        Path::new(file_name),
+        backup_dir,
+        Some(&encrypted_db),
+        time("2025-01-01 10:00:00"),
+    )
+    .unwrap();
+    assert!(fs::read(&backup_path).unwrap().starts_with(b"REXCRYPT"));
+    assert_eq!(
+        backup_path,
+        backup_dir.join("rex-safety-20250101-100000.sqlite.enc")
+    );
+
+    let backup = list_backups(backup_dir).unwrap().remove(0);
+    assert!(backup.encrypted);
+    assert!(backup.safety);
+
+    // Encrypted backups cannot be read without the encrypted DB
+    assert!(matches!(
+        OpenedBackup::open(&backup, None),
+        Err(RexError::Crypto(CryptoError::NoKey(_)))
+    ));
+
+    add_tx(
+        &mut db_conn,
+        "2024-06-16",
+        "Lunch",
+        "Cash",
+        "",
+        "20.00",
+        "Expense",
+        "Food",
+    );
+
+    let opened = OpenedBackup::open(&backup, Some(&encrypted_db)).unwrap();
+    assert_eq!(total_balance(&opened), 100.0);
+
+    let diff = opened.diff(&mut db_conn).unwrap();
+    assert_eq!(diff.removed.len(), 1);
+    assert!(diff.added.is_empty() && diff.changed.is_empty());
+
+    db_conn.restore_from(opened.db_path()).unwrap();
+    assert_eq!(db_conn.get_final_balances_json().unwrap().total, 100.0);
+
+    // The decrypted backup is deleted once it is dropped
+    let decrypted_path = opened.db_path().to_path_buf();
+    drop(opened);
+    assert!(!decrypted_path.exists());
+
+    drop(encrypted_db);
+    drop(db_conn);
+    fs::remove_file(file_name).unwrap();
+    fs::remove_file(encrypted_name).unwrap();
+    fs::remove_dir_all(backup_dir).unwrap();
+}
+
+#[test]
+fn open_backup_from_older_schema() {
+    let file_name = "test_restore_old_schema.sqlite";
+    let backup_dir = Path::new("test_restore_old_schema");
+    let mut db_conn = create_file_db(file_name);
+
+    if backup_dir.exists() {
+        fs::remove_dir_all(backup_dir).unwrap();
+    }
+    fs::create_dir(backup_dir).unwrap();
+
+    add_tx(
+        &mut db_conn,
+        "2024-06-15",
+        "Salary",
+        "Cash",
+        "",
+        "100.00",
+        "Income",
+        "Salary",
+    );
+
+    let backup_path = create_backup(
+        Path::new(file_name),
+        backup_dir,
+        time("2025-01-01 10:00:00"),
+    )
+    .unwrap();
+
+    // Take the backup back to before the latest migration
+    let mut old_conn = try_get_conn_old(backup_path.to_str().unwrap()).unwrap();
+    diesel::sql_query("ALTER TABLE txs DROP COLUMN has_time")
+        .execute(&mut old_conn.conn)
+        .unwrap();
+    diesel::sql_query(
+        "DELETE FROM __diesel_schema_migrations
+        WHERE version = (SELECT MAX(version) FROM __diesel_schema_migrations)",
+    )
+    .execute(&mut old_conn.conn)
+    .unwrap();
+    drop(old_conn);
+
+    let backup = list_backups(backup_dir).unwrap().remove(0);
+    let opened = OpenedBackup::open(&backup, None).unwrap();
+    assert_eq!(opened.summary().unwrap().tx_count, 1);
+    assert!(opened.diff(&mut db_conn).unwrap().is_empty());
+
+    db_conn.restore_from(opened.db_path()).unwrap();
+    assert_eq!(db_conn.get_final_balances_json().unwrap().total, 100.0);
+
+    drop(opened);
+    drop(db_conn);
+    fs::remove_file(file_name).unwrap();
+    fs::remove_dir_all(backup_dir).unwrap();
+}
+
+#[test]
+fn restore_backup_from_newer_schema() {
+    let file_name = "test_restore_new_schema.sqlite";
+    let backup_dir = Path::new("test_restore_new_schema");
+    let mut db_conn = create_file_db(file_name);
+
+    if backup_dir.exists() {
+        fs::remove_dir_all(backup_dir).unwrap();
+    }
+    fs::create_dir(backup_dir).unwrap();
+
+    let backup_path = create_backup(
+        Path::new(file_name),
+        backup_dir,
+        time("2025-01-01 10:00:00"),
+    )
+    .unwrap();
+
+    // A backup made by a newer Rex with a migration this one does not know about
+    let mut new_conn = try_get_conn_old(backup_path.to_str().unwrap()).unwrap();
+    diesel::sql_query("INSERT INTO __diesel_schema_migrations (version) VALUES ('99991231000000')")
+        .execute(&mut new_conn.conn)
+        .unwrap();
+    drop(new_conn);
+
+    let error = db_conn.restore_from(&backup_path).unwrap_err();
+    assert!(matches!(error, RexError::SchemaMismatch(_)));
+    assert_eq!(
+        error.to_string(),
+        "The source database is on a different schema version"
+    );
+
+    drop(db_conn);
+    fs::remove_file(file_name).unwrap();
+    fs::remove_dir_all(backup_dir).unwrap();
+}
@@ -1,5 +1,4 @@
 use rex_app::modifier::parse_search_fields;
-use std::fs;
 
 use crate::common::{add_tx, create_test_db};
 
@@ -12,8 +11,7 @@ fn search_and_count(db_conn: &mut rex_app::conn::DbConn, amount: &str) -> usize
 
 #[test]
 fn search_amount_exact() {
-    let file_name = "test_search_amount_exact.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -60,15 +58,11 @@ fn search_amount_exact() {
     assert_eq!(search_and_count(&mut db_conn, "10.00"), 1);
     assert_eq!(search_and_count(&mut db_conn, "100.00"), 1);
     assert_eq!(search_and_count(&mut db_conn, "999.00"), 0);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn search_amount_more_than() {
-    let file_name = "test_search_amount_more.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -104,15 +98,11 @@ fn search_amount_more_than() {
     assert_eq!(search_and_count(&mut db_conn, ">50.00"), 1);
     assert_eq!(search_and_count(&mut db_conn, ">10.00"), 2);
     assert_eq!(search_and_count(&mut db_conn, ">100.00"), 0);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn search_amount_more_than_equal() {
-    let file_name = "test_search_amount_more_eq.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -138,15 +128,11 @@ fn search_amount_more_than_equal() {
     assert_eq!(search_and_count(&mut db_conn, ">=50.00"), 2);
     assert_eq!(search_and_count(&mut db_conn, ">=100.00"), 1);
     assert_eq!(search_and_count(&mut db_conn, ">=200.00"), 0);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn search_amount_less_than() {
-    let file_name = "test_search_amount_less.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -182,15 +168,11 @@ fn search_amount_less_than() {
     assert_eq!(search_and_count(&mut db_conn, "<50.00"), 1);
     assert_eq!(search_and_count(&mut db_conn, "<100.00"), 2);
     assert_eq!(search_and_count(&mut db_conn, "<10.00"), 0);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn search_amount_less_than_equal() {
-    let file_name = "test_search_amount_less_eq.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -216,15 +198,11 @@ fn search_amount_less_than_equal() {
     assert_eq!(search_and_count(&mut db_conn, "<=50.00"), 1);
     assert_eq!(search_and_count(&mut db_conn, "<=100.00"), 2);
     assert_eq!(search_and_count(&mut db_conn, "<=10.00"), 0);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn search_amount_combined_with_other_filters() {
-    let file_name = "test_search_amount_combined.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -282,7 +260,4 @@ fn search_amount_combined_with_other_filters() {
     let results = search.search_txs(&mut db_conn).unwrap();
     assert_eq!(results.len(), 1);
     assert_eq!(results[0].amount.value(), 8000);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
@@ -1,5 +1,4 @@
 use rex_app::modifier::{parse_search_fields, parse_tx_fields};
-use std::fs;
 
 use crate::common::create_test_db;
 
@@ -7,9 +6,7 @@ mod common;
 
 #[test]
 fn search_tx_test() {
-    let file_name = "test_search_tx.sqlite";
-
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     let tx_list = [
         [
@@ -129,7 +126,4 @@ fn search_tx_test() {
             expected_len
         );
     }
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
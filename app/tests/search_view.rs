@@ -1,5 +1,4 @@
 use rex_app::modifier::parse_search_fields;
-use std::fs;
 
 use crate::common::{add_tx, create_test_db};
 
@@ -14,8 +13,7 @@ fn search_view_empty_by_constructor() {
 
 #[test]
 fn search_view_with_results() {
-    let file_name = "test_search_view_results.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -53,15 +51,11 @@ fn search_view_with_results() {
     assert_eq!(arr[0][4], "Expense");
     assert_eq!(arr[1][1], "Salary");
     assert_eq!(arr[1][4], "Income");
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn search_view_no_results() {
-    let file_name = "test_search_view_empty.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -80,7 +74,4 @@ fn search_view_no_results() {
 
     assert!(view.is_empty());
     assert_eq!(view.tx_array().len(), 0);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
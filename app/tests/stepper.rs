@@ -1,5 +1,5 @@
-use rex_app::ui_helper::{DateType, StepType, SteppingError};
-use std::fs;
+use chrono::Local;
+use rex_app::ui_helper::{DateStep, DateType, StepType, SteppingError};
 
 use crate::common::{add_tx, create_test_db};
 
@@ -9,122 +9,146 @@ mod common;
 
 #[test]
 fn step_date_exact_up() {
-    let file_name = "test_step_date.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let mut s = "2024-06-15".to_string();
     db_conn
         .step()
         .date(&mut s, StepType::StepUp, DateType::Exact)
         .unwrap();
     assert_eq!(s, "2024-06-16");
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_date_exact_down() {
-    let file_name = "test_step_date_down.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let mut s = "2024-06-15".to_string();
     db_conn
         .step()
         .date(&mut s, StepType::StepDown, DateType::Exact)
         .unwrap();
     assert_eq!(s, "2024-06-14");
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_date_monthly_up() {
-    let file_name = "test_step_date_monthly.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let mut s = "2024-06".to_string();
     db_conn
         .step()
         .date(&mut s, StepType::StepUp, DateType::Monthly)
         .unwrap();
     assert_eq!(s, "2024-07");
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_date_monthly_down() {
-    let file_name = "test_step_date_monthly_down.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let mut s = "2024-06".to_string();
     db_conn
         .step()
         .date(&mut s, StepType::StepDown, DateType::Monthly)
         .unwrap();
     assert_eq!(s, "2024-05");
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_date_yearly_up() {
-    let file_name = "test_step_date_yearly.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let mut s = "2024".to_string();
     db_conn
         .step()
         .date(&mut s, StepType::StepUp, DateType::Yearly)
         .unwrap();
     assert_eq!(s, "2025");
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
+}
+
+#[test]
+fn step_date_before_2022() {
+    let mut db_conn = create_test_db();
+
+    let mut s = "2022".to_string();
+    db_conn
+        .step()
+        .date(&mut s, StepType::StepDown, DateType::Yearly)
+        .unwrap();
+    assert_eq!(s, "2021");
+
+    let mut s = "2022-01-01".to_string();
+    db_conn
+        .step()
+        .date(&mut s, StepType::StepDown, DateType::Exact)
+        .unwrap();
+    assert_eq!(s, "2021-12-31");
 }
 
 #[test]
 fn step_date_yearly_down() {
-    let file_name = "test_step_date_yearly_down.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let mut s = "2024".to_string();
     db_conn
         .step()
         .date(&mut s, StepType::StepDown, DateType::Yearly)
         .unwrap();
     assert_eq!(s, "2023");
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_date_from_empty_defaults() {
-    let file_name = "test_step_date_empty.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
+
+    let today = Local::now().date_naive();
 
     let mut s = String::new();
     db_conn
         .step()
         .date(&mut s, StepType::StepUp, DateType::Exact)
         .unwrap();
-    assert_eq!(s, "2022-01-01");
+    assert_eq!(s, today.format("%Y-%m-%d").to_string());
 
     let mut s = String::new();
     db_conn
         .step()
         .date(&mut s, StepType::StepUp, DateType::Monthly)
         .unwrap();
-    assert_eq!(s, "2022-01");
+    assert_eq!(s, today.format("%Y-%m").to_string());
 
     let mut s = String::new();
     db_conn
         .step()
         .date(&mut s, StepType::StepUp, DateType::Yearly)
         .unwrap();
-    assert_eq!(s, "2022");
+    assert_eq!(s, today.format("%Y").to_string());
+}
+
+#[test]
+fn step_date_by_week_and_month() {
+    let mut db_conn = create_test_db();
+
+    let mut s = "2024-02-26".to_string();
+    db_conn
+        .step()
+        .date_by(&mut s, StepType::StepUp, DateType::Exact, DateStep::Week)
+        .unwrap();
+    assert_eq!(s, "2024-03-04");
 
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
+    let mut s = "2024-03-31".to_string();
+    db_conn
+        .step()
+        .date_by(&mut s, StepType::StepDown, DateType::Exact, DateStep::Month)
+        .unwrap();
+    assert_eq!(s, "2024-02-29");
+
+    let mut s = "2024-03-01".to_string();
+    db_conn
+        .step()
+        .date_by(&mut s, StepType::StepDown, DateType::Exact, DateStep::Week)
+        .unwrap();
+    assert_eq!(s, "2024-02-23");
 }
 
 #[test]
 fn step_date_month_boundary_wraps_year() {
-    let file_name = "test_step_date_month_wrap.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     let mut s = "2024-12".to_string();
     db_conn
@@ -139,61 +163,45 @@ fn step_date_month_boundary_wraps_year() {
         .date(&mut s, StepType::StepDown, DateType::Monthly)
         .unwrap();
     assert_eq!(s, "2023-12");
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 // ---- Amount stepping ----
 
 #[test]
 fn step_amount_up() {
-    let file_name = "test_step_amount.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let mut s = "5.00".to_string();
     db_conn.step().amount(&mut s, StepType::StepUp).unwrap();
     assert_eq!(s, "6.00");
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_amount_down() {
-    let file_name = "test_step_amount_down.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let mut s = "5.00".to_string();
     db_conn.step().amount(&mut s, StepType::StepDown).unwrap();
     assert_eq!(s, "4.00");
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_amount_down_hits_floor() {
-    let file_name = "test_step_amount_floor.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let mut s = "0.00".to_string();
     db_conn.step().amount(&mut s, StepType::StepDown).unwrap();
     assert_eq!(s, "0.00");
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_amount_from_empty_defaults() {
-    let file_name = "test_step_amount_empty.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let mut s = String::new();
     db_conn.step().amount(&mut s, StepType::StepUp).unwrap();
     assert_eq!(s, "0.00");
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_amount_negative_restores_to_one() {
-    let file_name = "test_step_amount_negative.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     // After previous verification corrects negative to positive, stepping up
     // from a state where VerifierError::AmountBelowZero would be returned
     // should set the amount to 1.00. The easiest way to trigger this is
@@ -204,90 +212,69 @@ fn step_amount_negative_restores_to_one() {
     let mut s = "0.00".to_string();
     db_conn.step().amount(&mut s, StepType::StepUp).unwrap();
     assert_eq!(s, "1.00");
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 // ---- Tx Method stepping ----
 
 #[test]
 fn step_tx_method_up() {
-    let file_name = "test_step_method.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     // Methods: Cash(1), Bank(2), Other(3)
     let mut s = "Cash".to_string();
     db_conn.step().tx_method(&mut s, StepType::StepUp).unwrap();
     assert_eq!(s, "Bank");
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_tx_method_wraps_at_end() {
-    let file_name = "test_step_method_wrap.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let mut s = "Other".to_string();
     db_conn.step().tx_method(&mut s, StepType::StepUp).unwrap();
     assert_eq!(s, "Cash");
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_tx_method_down_wraps_at_start() {
-    let file_name = "test_step_method_wrap_down.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let mut s = "Cash".to_string();
     db_conn
         .step()
         .tx_method(&mut s, StepType::StepDown)
         .unwrap();
     assert_eq!(s, "Other");
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_tx_method_from_empty() {
-    let file_name = "test_step_method_empty.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let mut s = String::new();
     db_conn.step().tx_method(&mut s, StepType::StepUp).unwrap();
     assert_eq!(s, "Cash");
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 // ---- Tx Type stepping ----
 
 #[test]
 fn step_tx_type_up() {
-    let file_name = "test_step_type.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let mut s = "Income".to_string();
     db_conn.step().tx_type(&mut s, StepType::StepUp).unwrap();
     assert_eq!(s, "Expense");
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_tx_type_down_wraps() {
-    let file_name = "test_step_type_wrap.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let mut s = "Income".to_string();
     db_conn.step().tx_type(&mut s, StepType::StepDown).unwrap();
     // TxType order: Income, Expense, Transfer, Borrow, Lend, BorrowRepay, LendRepay
     // Wrapping down from first goes to last
     assert_eq!(s, "Lend Repay");
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_tx_type_cycles_all() {
-    let file_name = "test_step_type_cycle.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let expected = [
         "Income",
         "Expense",
@@ -307,28 +294,21 @@ fn step_tx_type_cycles_all() {
     // Wrap back to first
     db_conn.step().tx_type(&mut s, StepType::StepUp).unwrap();
     assert_eq!(s, "Income");
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_tx_type_from_empty() {
-    let file_name = "test_step_type_empty.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let mut s = String::new();
     db_conn.step().tx_type(&mut s, StepType::StepUp).unwrap();
     assert_eq!(s, "Income");
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 // ---- Tag stepping ----
 
 #[test]
 fn step_tag_up() {
-    let file_name = "test_step_tag.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     // Add tags to the DB
     add_tx(
@@ -365,15 +345,11 @@ fn step_tag_up() {
     let mut s = "Alpha".to_string();
     db_conn.step().tag(&mut s, StepType::StepUp).unwrap();
     assert_eq!(s, "Beta");
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_tag_wraps_at_end() {
-    let file_name = "test_step_tag_wrap.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -399,15 +375,11 @@ fn step_tag_wraps_at_end() {
     let mut s = "Zulu".to_string();
     db_conn.step().tag(&mut s, StepType::StepUp).unwrap();
     assert_eq!(s, "Alpha");
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_tag_down_wraps() {
-    let file_name = "test_step_tag_wrap_down.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -435,15 +407,11 @@ fn step_tag_down_wraps() {
     let mut s = "Alpha".to_string();
     db_conn.step().tag(&mut s, StepType::StepDown).unwrap();
     assert_eq!(s, "Unknown");
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_tag_from_empty() {
-    let file_name = "test_step_tag_empty.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -459,15 +427,11 @@ fn step_tag_from_empty() {
     let mut s = String::new();
     db_conn.step().tag(&mut s, StepType::StepUp).unwrap();
     assert_eq!(s, "Alpha");
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_tag_fuzzy_corrects_then_steps() {
-    let file_name = "test_step_tag_fuzzy.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -495,15 +459,11 @@ fn step_tag_fuzzy_corrects_then_steps() {
     let result = db_conn.step().tag(&mut s, StepType::StepUp);
     assert!(result.is_err()); // Invalid tag error
     assert_eq!(s, "Groceries");
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_tag_multiple_preserves_other_tags() {
-    let file_name = "test_step_tag_multi.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -540,63 +500,47 @@ fn step_tag_multiple_preserves_other_tags() {
     let mut s = "Alpha, Beta".to_string();
     db_conn.step().tag(&mut s, StepType::StepUp).unwrap();
     assert_eq!(s, "Alpha, Gamma");
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 // ---- Stepper error paths ----
 
 #[test]
 fn step_date_invalid_format_errors() {
-    let file_name = "test_step_date_err.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let mut s = "not-a-date".to_string();
     let result = db_conn
         .step()
         .date(&mut s, StepType::StepUp, DateType::Exact);
     assert!(result.is_err());
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_amount_non_numeric_errors() {
-    let file_name = "test_step_amount_err.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let mut s = "abc".to_string();
     let result = db_conn.step().amount(&mut s, StepType::StepUp);
     assert!(matches!(result, Err(SteppingError::InvalidAmount)));
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_tx_method_unknown_errors() {
-    let file_name = "test_step_method_err.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let mut s = "NonExistentMethod".to_string();
     let result = db_conn.step().tx_method(&mut s, StepType::StepUp);
     assert!(matches!(result, Err(SteppingError::InvalidTxMethod)));
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_tx_type_unknown_errors() {
-    let file_name = "test_step_type_err.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let mut s = "UnknownType".to_string();
     let result = db_conn.step().tx_type(&mut s, StepType::StepUp);
     assert!(matches!(result, Err(SteppingError::InvalidTxType)));
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn step_tag_empty_db_errors() {
-    let file_name = "test_step_tag_err.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     // No tags added — only pre-seeded "Unknown" exists, so empty+step finds it
     // Actually "Unknown" is always there from migrations. Test with no user tags.
     // Stepping from empty when no user tags exist still finds Unknown.
@@ -604,6 +548,4 @@ fn step_tag_empty_db_errors() {
     let result = db_conn.step().tag(&mut s, StepType::StepUp);
     // Fuzzy-corrects to best match and errors
     assert!(result.is_err());
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
@@ -1,5 +1,5 @@
 use rex_app::conn::FetchNature;
-use std::fs;
+use rex_app::views::{ReportFormat, SummaryReport};
 
 use crate::common::{add_tx, create_test_db};
 
@@ -7,8 +7,7 @@ mod common;
 
 #[test]
 fn summary_monthly_basic_income_expense() {
-    let file_name = "test_summary_basic.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -70,15 +69,11 @@ fn summary_monthly_basic_income_expense() {
     assert_eq!(peak[0][2], "3000.00");
     assert_eq!(peak[1][0], "Peak Expense");
     assert_eq!(peak[1][2], "1500.00");
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn summary_largest_single_tx_each() {
-    let file_name = "test_summary_largest.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -129,15 +124,11 @@ fn summary_largest_single_tx_each() {
     let largest = full.largest_array();
     assert_eq!(largest[0][2], "5000.00"); // largest earning
     assert_eq!(largest[1][2], "2000.00"); // largest expense
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn summary_peak_across_months() {
-    let file_name = "test_summary_peak.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     // Month 1: low earning
     add_tx(
@@ -182,15 +173,11 @@ fn summary_peak_across_months() {
     assert_eq!(peak[0][0], "Peak Earning");
     assert_eq!(peak[0][2], "5000.00");
     assert_eq!(peak[0][1], "02-2024"); // Feb date format
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn summary_lend_borrows() {
-    let file_name = "test_summary_lend_borrow.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -243,15 +230,11 @@ fn summary_lend_borrows() {
     let lb = full.lend_borrows_array();
     assert_eq!(lb[0][0], "250.00");
     assert_eq!(lb[0][1], "100.00");
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn summary_multiple_methods() {
-    let file_name = "test_summary_methods.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -300,15 +283,11 @@ fn summary_multiple_methods() {
     assert_eq!(bank_row[2], "0.00"); // Bank expense
     assert_eq!(other_row[1], "0.00"); // Other: no activity
     assert_eq!(other_row[2], "0.00");
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn summary_tags_array_groups_by_tag() {
-    let file_name = "test_summary_tags.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -354,15 +333,11 @@ fn summary_tags_array_groups_by_tag() {
     let food_row = tags.iter().find(|r| r[0] == "Food").unwrap();
     assert_eq!(food_row[1], "0.00");
     assert_eq!(food_row[2], "200.00"); // expense
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn summary_yearly_with_monthly_averages() {
-    let file_name = "test_summary_yearly.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     // Jan: income 100
     add_tx(
@@ -395,15 +370,11 @@ fn summary_yearly_with_monthly_averages() {
     // Total income: 400, over 2 months, avg = 200
     let net = full.net_array();
     assert_eq!(net[0][1], "400.00"); // total income
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn summary_tags_array_with_compare() {
-    let file_name = "test_summary_tags_compare.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     // August: income + expense
     add_tx(
@@ -463,15 +434,11 @@ fn summary_tags_array_with_compare() {
     assert_eq!(work_row[1], "2500.00");
     // Should have compare columns (positions 6-7 after income/expense %)
     assert!(work_row.len() > 6, "Should have compare columns");
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn summary_with_last_summary_mom_comparison() {
-    let file_name = "test_summary_mom.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     // August
     add_tx(
@@ -539,15 +506,11 @@ fn summary_with_last_summary_mom_comparison() {
     let methods = sep_full.method_array();
     let cash_row = methods.iter().find(|r| r[0] == "Cash").unwrap();
     assert!(cash_row.len() >= 7, "Method row should have MoM columns");
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn summary_lend_borrows_with_mom() {
-    let file_name = "test_summary_lb_mom.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     // August: borrow 500
     add_tx(
@@ -599,15 +562,11 @@ fn summary_lend_borrows_with_mom() {
         lb[0].len() >= 2,
         "Lend borrows array should have value columns"
     );
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn summary_all_fetch_nature() {
-    let file_name = "test_summary_all.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -638,7 +597,153 @@ fn summary_all_fetch_nature() {
     let net = full.net_array();
     assert_eq!(net[0][1], "1000.00"); // total income
     assert_eq!(net[0][2], "200.00"); // total expense
+}
+
+#[test]
+fn summary_compare_periods() {
+    let mut db_conn = create_test_db();
+
+    let txs = [
+        ("2024-03-05", "Cash", "1000.00", "Income", "Work"),
+        ("2024-03-10", "Cash", "200.00", "Expense", "Food"),
+        ("2024-03-12", "Bank", "100.00", "Expense", "Rent"),
+        ("2025-03-05", "Cash", "1500.00", "Income", "Work"),
+        ("2025-03-10", "Bank", "300.00", "Expense", "Food"),
+        ("2025-03-20", "Bank", "50.00", "Expense", "Travel"),
+    ];
+
+    for (date, method, amount, tx_type, tag) in txs {
+        add_tx(&mut db_conn, date, "", method, "", amount, tx_type, tag);
+    }
+
+    let first = db_conn
+        .get_summary_with_str("March", "2024", FetchNature::Monthly)
+        .unwrap();
+    let second = db_conn
+        .get_summary_with_str("March", "2025", FetchNature::Monthly)
+        .unwrap();
+
+    let comparison = first.compare(&second, &db_conn);
+
+    let row =
+        |values: [&str; 9]| -> Vec<String> { values.iter().map(ToString::to_string).collect() };
+
+    assert_eq!(
+        comparison.tags_array(),
+        vec![
+            row([
+                "Food", "0.00", "0.00", "+0.00", "-", "200.00", "300.00", "+100.00", "↑50.00"
+            ]),
+            row([
+                "Rent",
+                "0.00",
+                "0.00",
+                "+0.00",
+                "-",
+                "100.00",
+                "0.00",
+                "-100.00",
+                "↓100.00"
+            ]),
+            row([
+                "Travel", "0.00", "0.00", "+0.00", "-", "0.00", "50.00", "+50.00", "∞"
+            ]),
+            row([
+                "Work", "1000.00", "1500.00", "+500.00", "↑50.00", "0.00", "0.00", "+0.00", "-"
+            ]),
+        ]
+    );
+
+    assert_eq!(
+        comparison.methods_array(),
+        vec![
+            row([
+                "Cash",
+                "1000.00",
+                "1500.00",
+                "+500.00",
+                "↑50.00",
+                "200.00",
+                "0.00",
+                "-200.00",
+                "↓100.00"
+            ]),
+            row([
+                "Bank",
+                "0.00",
+                "0.00",
+                "+0.00",
+                "-",
+                "100.00",
+                "350.00",
+                "+250.00",
+                "↑250.00"
+            ]),
+            row([
+                "Other", "0.00", "0.00", "+0.00", "-", "0.00", "0.00", "+0.00", "-"
+            ]),
+            row([
+                "Total", "1000.00", "1500.00", "+500.00", "↑50.00", "300.00", "350.00", "+50.00",
+                "↑16.67"
+            ]),
+        ]
+    );
+}
+
+#[test]
+fn summary_report_markdown_and_html() {
+    let mut db_conn = create_test_db();
+
+    add_tx(
+        &mut db_conn,
+        "2024-03-05",
+        "Salary",
+        "Cash",
+        "",
+        "1000.00",
+        "Income",
+        "Work",
+    );
+    add_tx(
+        &mut db_conn,
+        "2024-03-10",
+        "Dinner",
+        "Cash",
+        "",
+        "250.00",
+        "Expense",
+        "Food & Drinks",
+    );
+
+    let summary_view = db_conn
+        .get_summary_with_str("March", "2024", FetchNature::Monthly)
+        .unwrap();
+    let full = summary_view.generate_summary(None, &db_conn);
+    let tags = summary_view.tags_array(None, &db_conn);
+
+    let report = SummaryReport::new("March 2024", FetchNature::Monthly, &full, &tags);
+
+    let markdown = report.render(ReportFormat::Markdown);
+
+    assert!(markdown.starts_with("# March 2024\n"));
+    assert!(markdown.contains("\n## Methods\n"));
+    assert!(
+        markdown.contains(
+            "| Method | Total Income | Total Expense | Income % | Expense % | MoM Income % | MoM Expense % |"
+        )
+    );
+    assert!(markdown.contains("| Work | 1000.00 | 0.00 |"));
+    assert!(markdown.contains("<svg"));
+    assert!(markdown.contains("1000.00 / 0.00"));
+
+    let html = report.render(ReportFormat::Html);
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<h1>March 2024</h1>"));
+    assert!(html.contains("<td>Food &amp; Drinks</td>"));
+    assert!(!html.contains("<th>Average Income</th>"));
+    assert!(html.trim_end().ends_with("</html>"));
 
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
+    assert_eq!(ReportFormat::from_extension("HTML"), ReportFormat::Html);
+    assert_eq!(ReportFormat::from_extension("md"), ReportFormat::Markdown);
 }
@@ -0,0 +1,109 @@
+use crate::common::{add_tx, create_test_db};
+
+mod common;
+
+#[test]
+fn suggest_tags_empty_db() {
+    let mut db_conn = create_test_db();
+
+    let suggestions = db_conn.suggest_tags("Lunch", "Cash", "10").unwrap();
+    assert!(suggestions.is_empty());
+}
+
+#[test]
+fn suggest_tags_ranks_by_details() {
+    let mut db_conn = create_test_db();
+
+    add_tx(
+        &mut db_conn,
+        "2024-01-01",
+        "Lunch at cafe",
+        "Cash",
+        "",
+        "12",
+        "Expense",
+        "Food",
+    );
+    add_tx(
+        &mut db_conn,
+        "2024-01-02",
+        "Dinner cafe",
+        "Cash",
+        "",
+        "20",
+        "Expense",
+        "Food",
+    );
+    add_tx(
+        &mut db_conn,
+        "2024-01-03",
+        "Bus ticket",
+        "Bank",
+        "",
+        "2",
+        "Expense",
+        "Transport",
+    );
+    add_tx(
+        &mut db_conn,
+        "2024-01-04",
+        "Train ticket",
+        "Bank",
+        "",
+        "30",
+        "Expense",
+        "Transport",
+    );
+
+    let suggestions = db_conn.suggest_tags("cafe", "Cash", "15").unwrap();
+    assert_eq!(suggestions.len(), 2);
+    assert_eq!(suggestions[0].name, "Food");
+    assert!(suggestions[0].confidence > 0.5);
+
+    let total: f64 = suggestions.iter().map(|s| s.confidence).sum();
+    assert!((total - 1.0).abs() < 1e-9);
+
+    let suggestions = db_conn.suggest_tags("ticket", "Bank", "").unwrap();
+    assert_eq!(suggestions[0].name, "Transport");
+}
+
+#[test]
+fn suggest_tags_learns_new_txs() {
+    let mut db_conn = create_test_db();
+
+    add_tx(
+        &mut db_conn,
+        "2024-01-01",
+        "Lunch",
+        "Cash",
+        "",
+        "12",
+        "Expense",
+        "Food",
+    );
+
+    // Trains the classifier before the next tx is added
+    let suggestions = db_conn.suggest_tags("Salary", "Bank", "1000").unwrap();
+    assert_eq!(suggestions.len(), 1);
+
+    let salary = add_tx(
+        &mut db_conn,
+        "2024-01-05",
+        "Salary",
+        "Bank",
+        "",
+        "1000",
+        "Income",
+        "Job",
+    );
+
+    let suggestions = db_conn.suggest_tags("Salary", "Bank", "1000").unwrap();
+    assert_eq!(suggestions.len(), 2);
+    assert_eq!(suggestions[0].name, "Job");
+
+    db_conn.delete_tx(&salary).unwrap();
+
+    let suggestions = db_conn.suggest_tags("Salary", "Bank", "1000").unwrap();
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(suggestions[0].name, "Food");
+}
@@ -0,0 +1,191 @@
+use chrono::{Local, NaiveDate, NaiveTime};
+use rex_app::conn::FetchNature;
+use rex_app::error::{RexError, ValidationError};
+use rex_app::modifier::TxBuilder;
+use rex_app::ui_helper::TxBuildError;
+use rex_db::ConnCache;
+use rex_shared::models::Cent;
+
+use crate::common::{add_tx, create_test_db};
+
+mod common;
+
+#[test]
+fn tx_builder_add_and_edit() {
+    let mut db_conn = create_test_db();
+
+    let cash = db_conn.cache().get_method_id("Cash").unwrap();
+    let bank = db_conn.cache().get_method_id("Bank").unwrap();
+    let date = NaiveDate::from_ymd_opt(2024, 6, 15).unwrap();
+
+    let income = TxBuilder::income(Cent::new(100_000))
+        .on(date)
+        .from(cash)
+        .details("Salary")
+        .tags(["Salary", " Work ", "Salary", ""]);
+    db_conn.add_tx(income).unwrap();
+
+    let expense = TxBuilder::expense(Cent::new(1250)).on(date).from(cash);
+    db_conn.add_tx(expense).unwrap();
+
+    let transfer = TxBuilder::transfer(Cent::new(20_000))
+        .on(date)
+        .from(cash)
+        .to(bank);
+    db_conn.add_tx(transfer).unwrap();
+
+    let tx_view = db_conn
+        .fetch_txs_with_date(date, FetchNature::Monthly)
+        .unwrap();
+
+    assert_eq!(tx_view.len(), 3);
+    assert_eq!(
+        tx_view.get_tx(0).to_array(true),
+        [
+            "2024-06-15",
+            "Salary",
+            "Cash",
+            "1000.00",
+            "Income",
+            "Salary, Work"
+        ]
+    );
+    assert_eq!(
+        tx_view.get_tx(1).to_array(true),
+        ["2024-06-15", "", "Cash", "12.50", "Expense", "Unknown"]
+    );
+    assert_eq!(
+        tx_view.get_tx(2).to_array(true),
+        [
+            "2024-06-15",
+            "",
+            "Cash → Bank",
+            "200.00",
+            "Transfer",
+            "Unknown"
+        ]
+    );
+
+    let balances = db_conn.get_final_balances_json().unwrap();
+    assert_eq!(balances.methods[0].balance, 787.5);
+    assert_eq!(balances.methods[1].balance, 200.0);
+
+    let old_tx = tx_view.get_tx(1).clone();
+    let edited = TxBuilder::expense(Cent::new(2500))
+        .on(date)
+        .from(bank)
+        .details("Lunch")
+        .tags(["Food"]);
+    db_conn.edit_tx_with(&old_tx, edited).unwrap();
+
+    let tx = db_conn.fetch_tx_with_id(old_tx.id).unwrap();
+    assert_eq!(
+        tx.to_array(true),
+        ["2024-06-15", "Lunch", "Bank", "25.00", "Expense", "Food"]
+    );
+
+    let balances = db_conn.get_final_balances_json().unwrap();
+    assert_eq!(balances.methods[0].balance, 800.0);
+    assert_eq!(balances.methods[1].balance, 175.0);
+}
+
+#[test]
+fn tx_builder_validation() {
+    let mut db_conn = create_test_db();
+
+    let cash = db_conn.cache().get_method_id("Cash").unwrap();
+    let date = NaiveDate::from_ymd_opt(2024, 6, 15).unwrap();
+
+    let cases = [
+        (
+            TxBuilder::expense(Cent::new(0)).on(date).from(cash),
+            TxBuildError::AmountBelowZero,
+        ),
+        (
+            TxBuilder::expense(Cent::new(-100)).on(date).from(cash),
+            TxBuildError::AmountBelowZero,
+        ),
+        (
+            TxBuilder::expense(Cent::new(1_000_000_000_000))
+                .on(date)
+                .from(cash),
+            TxBuildError::AmountTooBig,
+        ),
+        (
+            TxBuilder::income(Cent::new(100)).on(date),
+            TxBuildError::EmptyMethod,
+        ),
+        (
+            TxBuilder::income(Cent::new(100)).on(date).from(999),
+            TxBuildError::InvalidTxMethod(999),
+        ),
+        (
+            TxBuilder::transfer(Cent::new(100)).on(date).from(cash),
+            TxBuildError::EmptyMethod,
+        ),
+        (
+            TxBuilder::transfer(Cent::new(100))
+                .on(date)
+                .from(cash)
+                .to(cash),
+            TxBuildError::SameTxMethod,
+        ),
+        (
+            TxBuilder::lend(Cent::new(100)).on(date).from(cash).to(cash),
+            TxBuildError::UnexpectedToMethod,
+        ),
+        (
+            TxBuilder::borrow(Cent::new(100))
+                .on(NaiveDate::from_ymd_opt(999, 1, 1).unwrap())
+                .from(cash),
+            TxBuildError::InvalidYear,
+        ),
+    ];
+
+    for (builder, error) in cases {
+        assert_eq!(builder.validate(&db_conn).err().as_ref(), Some(&error));
+
+        // The same error is returned when saving so it can be matched on
+        let saved = db_conn.add_tx(builder).unwrap_err();
+        assert!(
+            matches!(saved, RexError::Validation(ValidationError::TxBuild(ref e)) if *e == error)
+        );
+    }
+
+    // Nothing got saved
+    let tx_view = db_conn
+        .fetch_txs_with_date(date, FetchNature::Monthly)
+        .unwrap();
+    assert_eq!(tx_view.len(), 0);
+
+    // Only an exact date time gets a time, today's date included
+    let today = Local::now().date_naive();
+    let on_today = TxBuilder::expense(Cent::new(100)).on(today).from(cash);
+    assert_eq!(
+        on_today.validate(&db_conn).unwrap().date,
+        today.and_time(NaiveTime::MIN)
+    );
+
+    let undated = TxBuilder::expense(Cent::new(100)).from(cash);
+    assert_eq!(
+        undated.validate(&db_conn).unwrap().date,
+        today.and_time(NaiveTime::MIN)
+    );
+
+    // A valid builder still goes through after a failed one
+    add_tx(
+        &mut db_conn,
+        "2024-06-15",
+        "",
+        "Cash",
+        "",
+        "10.00",
+        "Income",
+        "",
+    );
+    let tx = TxBuilder::lend_repay(Cent::new(500)).on(date).from(cash);
+    db_conn.add_tx(tx).unwrap();
+
+    let balances = db_conn.get_final_balances_json().unwrap();
+    assert_eq!(balances.total, 15.0);
+}
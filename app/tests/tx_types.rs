@@ -2,7 +2,6 @@ use chrono::NaiveDate;
 use rex_app::conn::FetchNature;
 use rex_app::modifier::parse_tx_fields;
 use rex_db::ConnCache;
-use std::fs;
 
 use crate::common::{add_tx, create_test_db};
 
@@ -10,8 +9,7 @@ mod common;
 
 #[test]
 fn transfer_moves_money_between_methods() {
-    let file_name = "test_transfer_basic.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
     let bank_id = db_conn.cache().get_method_id("Bank").unwrap();
     let other_id = db_conn.cache().get_method_id("Other").unwrap();
@@ -54,15 +52,11 @@ fn transfer_moves_money_between_methods() {
     // Total across all methods unchanged
     let total: i64 = balance.values().map(|c| c.value()).sum();
     assert_eq!(total, 100000);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn transfer_then_reverse_transfer_returns_to_original() {
-    let file_name = "test_transfer_reverse.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
     let bank_id = db_conn.cache().get_method_id("Bank").unwrap();
 
@@ -106,15 +100,11 @@ fn transfer_then_reverse_transfer_returns_to_original() {
     let balance = tx_view.get_tx_balance(2);
     assert_eq!(balance[&cash_id].value(), 100000); // 1000 - 300 + 300
     assert_eq!(balance[&bank_id].value(), 0); // 0 + 300 - 300
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn delete_transfer_reverses_both_methods() {
-    let file_name = "test_transfer_delete.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
     let bank_id = db_conn.cache().get_method_id("Bank").unwrap();
 
@@ -155,15 +145,11 @@ fn delete_transfer_reverses_both_methods() {
     assert_eq!(tx_view.len(), 1);
     assert_eq!(tx_view.get_tx_balance(0)[&cash_id].value(), 50000);
     assert_eq!(tx_view.get_tx_balance(0)[&bank_id].value(), 0);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn edit_transfer_amount_updates_both_methods() {
-    let file_name = "test_transfer_edit.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
     let bank_id = db_conn.cache().get_method_id("Bank").unwrap();
 
@@ -207,15 +193,11 @@ fn edit_transfer_amount_updates_both_methods() {
         .unwrap();
     assert_eq!(tx_view.get_tx_balance(1)[&cash_id].value(), 50000); // 1000 - 500
     assert_eq!(tx_view.get_tx_balance(1)[&bank_id].value(), 50000); // 0 + 500
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn borrow_increases_balance() {
-    let file_name = "test_borrow.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
 
     add_tx(
@@ -234,15 +216,11 @@ fn borrow_increases_balance() {
         .fetch_txs_with_date(date, FetchNature::Monthly)
         .unwrap();
     assert_eq!(tx_view.get_tx_balance(0)[&cash_id].value(), 50000);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn borrow_repay_decreases_balance() {
-    let file_name = "test_borrow_repay.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
 
     // Need money first
@@ -274,15 +252,11 @@ fn borrow_repay_decreases_balance() {
         .unwrap();
     // Borrow +500, Repay -500 = 0
     assert_eq!(tx_view.get_tx_balance(1)[&cash_id].value(), 0);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn lend_decreases_balance() {
-    let file_name = "test_lend.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
 
     add_tx(
@@ -312,15 +286,11 @@ fn lend_decreases_balance() {
         .fetch_txs_with_date(date, FetchNature::Monthly)
         .unwrap();
     assert_eq!(tx_view.get_tx_balance(1)[&cash_id].value(), 80000);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn lend_repay_increases_balance() {
-    let file_name = "test_lend_repay.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
 
     add_tx(
@@ -351,15 +321,11 @@ fn lend_repay_increases_balance() {
         .unwrap();
     // Lend -200, Repay +200 = 0
     assert_eq!(tx_view.get_tx_balance(1)[&cash_id].value(), 0);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn all_tx_types_impact_balance_correctly() {
-    let file_name = "test_all_tx_types.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
     let cash_id = db_conn.cache().get_method_id("Cash").unwrap();
     let bank_id = db_conn.cache().get_method_id("Bank").unwrap();
 
@@ -457,7 +423,4 @@ fn all_tx_types_impact_balance_correctly() {
     assert_eq!(balance[&cash_id].value(), 32500);
     // Bank: 0 + 200 = 200
     assert_eq!(balance[&bank_id].value(), 20000);
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
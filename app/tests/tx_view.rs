@@ -1,7 +1,6 @@
 use chrono::NaiveDate;
 use rex_app::conn::FetchNature;
 use rex_app::views::PartialTx;
-use std::fs;
 
 use crate::common::{add_tx, create_test_db};
 
@@ -13,8 +12,7 @@ fn get_col(rows: &[Vec<String>], row: usize, col: usize) -> &str {
 
 #[test]
 fn balance_array_with_index_shows_running_balance() {
-    let file_name = "test_balance_array.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -65,15 +63,11 @@ fn balance_array_with_index_shows_running_balance() {
     let arr = tx_view.balance_array(Some(1), &mut db_conn).unwrap();
     assert_eq!(get_col(&arr, 1, 1), "800.00"); // Cash balance
     assert_eq!(get_col(&arr, 1, 4), "800.00"); // Total
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn balance_array_with_none_shows_final_balance() {
-    let file_name = "test_balance_array_none.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -110,20 +104,16 @@ fn balance_array_with_none_shows_final_balance() {
 
     // Changes should all be 0.00 (no specific tx)
     assert_eq!(get_col(&arr, 2, 1), "0.00");
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn balance_array_daily_income_expense() {
-    let file_name = "test_balance_daily.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
-    // Same date: two incomes
+    // Same date at different times: two incomes
     add_tx(
         &mut db_conn,
-        "2024-08-01",
+        "2024-08-01 09:00",
         "Morning",
         "Cash",
         "",
@@ -133,7 +123,7 @@ fn balance_array_daily_income_expense() {
     );
     add_tx(
         &mut db_conn,
-        "2024-08-01",
+        "2024-08-01 15:30",
         "Afternoon",
         "Cash",
         "",
@@ -165,15 +155,11 @@ fn balance_array_daily_income_expense() {
     // At index 2 (Aug 2): daily income = only the Aug 2 tx = 200
     let arr = tx_view.balance_array(Some(2), &mut db_conn).unwrap();
     assert_eq!(get_col(&arr, 5, 1), "200.00"); // only the Aug 2 tx
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn add_tx_balance_array_projecting_new_tx() {
-    let file_name = "test_add_balance_array.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -209,15 +195,11 @@ fn add_tx_balance_array_projecting_new_tx() {
     // Changes row: should show projected change
     assert_eq!(get_col(&arr, 2, 0), "Changes");
     assert!(get_col(&arr, 2, 1).contains("100.00"));
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn add_tx_balance_array_projection_transfer() {
-    let file_name = "test_add_balance_transfer.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -249,15 +231,11 @@ fn add_tx_balance_array_projection_transfer() {
     // Cash: 1000 - 300 = 700, Bank: 0 + 300 = 300
     assert_eq!(get_col(&arr, 1, 1), "700.00"); // Cash
     assert_eq!(get_col(&arr, 1, 2), "300.00"); // Bank
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn get_tx_by_id_not_found() {
-    let file_name = "test_tx_by_id_miss.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -275,15 +253,11 @@ fn get_tx_by_id_not_found() {
         .fetch_txs_with_date(date, FetchNature::Monthly)
         .unwrap();
     assert!(tx_view.get_tx_by_id(999).is_none());
-
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
 }
 
 #[test]
 fn add_tx_balance_array_at_index_zero() {
-    let file_name = "test_add_balance_idx0.sqlite";
-    let mut db_conn = create_test_db(file_name);
+    let mut db_conn = create_test_db();
 
     add_tx(
         &mut db_conn,
@@ -315,7 +289,44 @@ fn add_tx_balance_array_at_index_zero() {
     // Before first tx: balance=0. Project expense -100 → -100
     assert_eq!(get_col(&arr, 1, 0), "Balance");
     assert_eq!(get_col(&arr, 1, 1), "-100.00");
+}
+
+#[test]
+fn tx_view_to_csv() {
+    let mut db_conn = create_test_db();
+
+    add_tx(
+        &mut db_conn,
+        "2024-02-10 09:30",
+        "Lunch, with \"friends\"",
+        "Cash",
+        "",
+        "12.50",
+        "Expense",
+        "Food, Eating Out",
+    );
+    add_tx(
+        &mut db_conn,
+        "2025-01-05 18:00",
+        "",
+        "Cash",
+        "Bank",
+        "100",
+        "Transfer",
+        "Savings",
+    );
 
-    drop(db_conn);
-    fs::remove_file(file_name).unwrap();
+    let txs = db_conn
+        .fetch_txs_with_date(
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            FetchNature::All,
+        )
+        .unwrap();
+
+    assert_eq!(
+        txs.to_csv(),
+        "Date,Details,From Method,To Method,Amount,Type,Tags\n\
+         2024-02-10 09:30,\"Lunch, with \"\"friends\"\"\",Cash,,12.50,Expense,\"Food, Eating Out\"\n\
+         2025-01-05 18:00,,Cash,Bank,100.00,Transfer,Savings\n"
+    );
 }
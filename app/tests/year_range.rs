@@ -0,0 +1,90 @@
+use rex_app::conn::FetchNature;
+
+use crate::common::{add_tx, create_test_db};
+
+mod common;
+
+#[test]
+fn tx_year_range_follows_data() {
+    let mut db_conn = create_test_db();
+
+    assert_eq!(db_conn.get_tx_year_range().unwrap(), None);
+
+    add_tx(
+        &mut db_conn,
+        "2024-03-01",
+        "Salary",
+        "Cash",
+        "",
+        "1000",
+        "Income",
+        "Job",
+    );
+    add_tx(
+        &mut db_conn,
+        "2019-05-01",
+        "Old salary",
+        "Cash",
+        "",
+        "500",
+        "Income",
+        "Job",
+    );
+
+    assert_eq!(db_conn.get_tx_year_range().unwrap(), Some((2019, 2024)));
+}
+
+#[test]
+fn txs_before_2022_are_fetched() {
+    let mut db_conn = create_test_db();
+
+    add_tx(
+        &mut db_conn,
+        "2019-05-01",
+        "Old salary",
+        "Cash",
+        "",
+        "500",
+        "Income",
+        "Job",
+    );
+    add_tx(
+        &mut db_conn,
+        "2021-12-31",
+        "Gift",
+        "Cash",
+        "",
+        "100",
+        "Expense",
+        "Misc",
+    );
+    add_tx(
+        &mut db_conn,
+        "2024-03-01",
+        "Salary",
+        "Cash",
+        "",
+        "1000",
+        "Income",
+        "Job",
+    );
+
+    let tx_view = db_conn
+        .fetch_txs_with_str("May", "2019", FetchNature::Yearly)
+        .unwrap();
+    assert_eq!(tx_view.len(), 1);
+
+    let summary_view = db_conn
+        .get_summary_with_str("January", "2022", FetchNature::All)
+        .unwrap();
+    let net = summary_view.generate_summary(None, &db_conn).net_array();
+    assert_eq!(net[0][1], "1500.00");
+    assert_eq!(net[0][2], "100.00");
+
+    // The balance carries over from the txs before 2022
+    let chart_view = db_conn
+        .get_chart_view_with_str("March", "2024", FetchNature::Monthly)
+        .unwrap();
+    let cash_id = db_conn.get_tx_method_by_name("Cash").unwrap().id;
+    assert_eq!(chart_view.get_balance(0)[&cash_id].value(), 140_000);
+}
@@ -0,0 +1,96 @@
+use crate::common::{add_tx, create_test_db};
+
+mod common;
+
+#[test]
+fn yearly_report_by_month() {
+    let mut db_conn = create_test_db();
+
+    let txs = [
+        ("2023-12-31", "Cash", "", "999", "Income", "Job"),
+        ("2024-01-05", "Cash", "", "1000", "Income", "Job"),
+        ("2024-01-10", "Cash", "", "250", "Expense", "Food"),
+        ("2024-02-03", "Bank", "", "100", "Expense", "Food"),
+        ("2024-03-01", "Cash", "", "500", "Income", "Side"),
+        ("2024-03-01", "Cash", "Bank", "200", "Transfer", "Misc"),
+    ];
+
+    for (date, from_method, to_method, amount, tx_type, tags) in txs {
+        add_tx(
+            &mut db_conn,
+            date,
+            "",
+            from_method,
+            to_method,
+            amount,
+            tx_type,
+            tags,
+        );
+    }
+
+    let report = db_conn.get_yearly_report(2024).unwrap();
+    let rows = report.array();
+
+    let headers = report.headers();
+    assert_eq!(headers.len(), 15);
+    assert_eq!(headers[0], "2024");
+    assert_eq!(headers[1], "Jan");
+    assert_eq!(headers[13], "Total");
+    assert_eq!(headers[14], "Average");
+
+    let names: Vec<&str> = rows.iter().map(|row| row[0].as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "Income",
+            "Expense",
+            "Net",
+            "Savings Rate %",
+            "Food",
+            "Job",
+            "Side"
+        ]
+    );
+
+    // Jan, Feb and Mar have txs so the average is taken over 3 months
+    assert_eq!(rows[0][1..4], ["1000.00", "0.00", "500.00"]);
+    assert_eq!(rows[0][13..], ["1500.00", "500.00"]);
+
+    assert_eq!(rows[1][1..4], ["250.00", "100.00", "0.00"]);
+    assert_eq!(rows[1][13..], ["350.00", "116.67"]);
+
+    assert_eq!(rows[2][1..4], ["750.00", "-100.00", "500.00"]);
+    assert_eq!(rows[2][13..], ["1150.00", "383.33"]);
+
+    assert_eq!(rows[3][1..5], ["75.00", "-", "100.00", "-"]);
+    assert_eq!(rows[3][13..], ["76.67", "87.50"]);
+
+    assert_eq!(rows[4][1..3], ["-250.00", "-100.00"]);
+    assert_eq!(rows[4][13], "-350.00");
+    assert_eq!(rows[5][13], "1000.00");
+
+    let csv = report.to_csv();
+    let mut lines = csv.lines();
+
+    assert_eq!(
+        lines.next().unwrap(),
+        "2024,Jan,Feb,Mar,Apr,May,Jun,Jul,Aug,Sep,Oct,Nov,Dec,Total,Average"
+    );
+    assert_eq!(
+        lines.next().unwrap(),
+        "Income,1000.00,0.00,500.00,0.00,0.00,0.00,0.00,0.00,0.00,0.00,0.00,0.00,1500.00,500.00"
+    );
+    assert_eq!(csv.lines().count(), 8);
+}
+
+#[test]
+fn yearly_report_without_txs() {
+    let mut db_conn = create_test_db();
+
+    let report = db_conn.get_yearly_report(2024).unwrap();
+    let rows = report.array();
+
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[2][13..], ["0.00", "0.00"]);
+    assert_eq!(rows[3][13..], ["-", "-"]);
+}
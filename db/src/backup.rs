@@ -0,0 +1,92 @@
+use diesel::ConnectionError;
+use libsqlite3_sys as ffi;
+use std::ffi::{CStr, CString, c_int};
+use std::ptr;
+
+use crate::SetupError;
+
+/// How long to wait before retrying when another connection is writing to the source
+const BUSY_WAIT_MS: c_int = 50;
+/// Gives up after about 10 seconds of the source being busy
+const BUSY_RETRIES: usize = 200;
+
+/// A raw SQLite handle used only for the backup. Diesel does not expose the one it owns
+struct SqliteHandle(*mut ffi::sqlite3);
+
+impl SqliteHandle {
+    fn open(path: &str, flags: c_int) -> Result<Self, SetupError> {
+        let path = CString::new(path).map_err(ConnectionError::InvalidCString)?;
+        let mut db = ptr::null_mut();
+
+        // SAFETY: `path` is a valid C string and `db` is set to a handle, even on failure,
+        // that is closed once `handle` drops
+        let code = unsafe { ffi::sqlite3_open_v2(path.as_ptr(), &mut db, flags, ptr::null()) };
+        let handle = Self(db);
+
+        if code != ffi::SQLITE_OK {
+            return Err(handle.error());
+        }
+
+        Ok(handle)
+    }
+
+    fn error(&self) -> SetupError {
+        if self.0.is_null() {
+            return SetupError::Backup("Out of memory".to_string());
+        }
+
+        // SAFETY: the handle is open and SQLite owns the returned message
+        let message = unsafe { CStr::from_ptr(ffi::sqlite3_errmsg(self.0)) };
+
+        SetupError::Backup(message.to_string_lossy().into_owned())
+    }
+}
+
+impl Drop for SqliteHandle {
+    fn drop(&mut self) {
+        // SAFETY: closing a null handle is a no-op and no backup uses the handle anymore
+        unsafe {
+            ffi::sqlite3_close(self.0);
+        }
+    }
+}
+
+/// Copies the DB at `source` to `target` with SQLite's online backup API. Unlike copying the
+/// file, the copy is consistent even if another connection is writing to the source.
+/// `target` is replaced if it exists
+pub fn backup_db(source: &str, target: &str) -> Result<(), SetupError> {
+    let source = SqliteHandle::open(source, ffi::SQLITE_OPEN_READONLY)?;
+    let target = SqliteHandle::open(target, ffi::SQLITE_OPEN_READWRITE | ffi::SQLITE_OPEN_CREATE)?;
+
+    // SAFETY: both handles stay open until the backup is finished
+    let code = unsafe {
+        let backup =
+            ffi::sqlite3_backup_init(target.0, c"main".as_ptr(), source.0, c"main".as_ptr());
+
+        if backup.is_null() {
+            return Err(target.error());
+        }
+
+        let mut retries = 0;
+
+        loop {
+            let code = ffi::sqlite3_backup_step(backup, -1);
+
+            if matches!(code, ffi::SQLITE_BUSY | ffi::SQLITE_LOCKED) && retries < BUSY_RETRIES {
+                retries += 1;
+                ffi::sqlite3_sleep(BUSY_WAIT_MS);
+                continue;
+            }
+
+            break;
+        }
+
+        ffi::sqlite3_backup_finish(backup)
+    };
+
+    if code != ffi::SQLITE_OK {
+        return Err(target.error());
+    }
+
+    Ok(())
+}
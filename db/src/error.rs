@@ -0,0 +1,55 @@
+use diesel::ConnectionError;
+use diesel::result::Error as DieselError;
+use thiserror::Error;
+
+#[derive(PartialEq, Debug, Error)]
+pub enum NotFoundError {
+    #[error("Tx Method '{0}' not found")]
+    TxMethod(String),
+    #[error("Tag '{0}' not found")]
+    Tag(String),
+    #[error("Transaction with id {0} not found")]
+    Tx(i32),
+    #[error("Requested data not found in the database")]
+    Record,
+}
+
+/// A value in the DB that does not match anything Rex knows about, usually from a corrupted row
+#[derive(PartialEq, Debug, Error)]
+#[error("Unknown {kind} '{value}' found in the database")]
+pub struct InvalidValueError {
+    pub kind: &'static str,
+    pub value: String,
+}
+
+impl InvalidValueError {
+    #[must_use]
+    pub fn new(kind: &'static str, value: impl ToString) -> Self {
+        Self {
+            kind,
+            value: value.to_string(),
+        }
+    }
+}
+
+/// Model functions return diesel errors so invalid values are passed up as a row that failed
+/// to deserialize
+impl From<InvalidValueError> for DieselError {
+    fn from(error: InvalidValueError) -> Self {
+        DieselError::DeserializationError(Box::new(error))
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SetupError {
+    #[error("Failed to open the database. {0}")]
+    Open(#[from] ConnectionError),
+    #[error("Failed to run database migrations. {0}")]
+    Migration(String),
+    #[error("The source database is on a different schema version")]
+    SchemaMismatch,
+    #[error("Failed to set up the database. {0}")]
+    Query(#[from] DieselError),
+    #[error("Failed to back up the database. {0}")]
+    Backup(String),
+}
@@ -1,16 +1,24 @@
+mod backup;
+mod error;
 pub mod models;
 mod schema;
 
-use anyhow::{Result, anyhow};
 use diesel::prelude::*;
 use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
 use crate::models::{FullTx, Tag, TxMethod};
 
+pub use backup::backup_db;
+pub use error::{InvalidValueError, NotFoundError, SetupError};
+
 // pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("../db/src/migrations");
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("src/migrations");
 
+/// DB url that opens a new DB living in memory. Every change is lost once the connection drops
+pub const IN_MEMORY: &str = ":memory:";
+
 pub trait ConnCache {
     fn conn(&mut self) -> &mut SqliteConnection;
     fn cache(&self) -> &Cache;
@@ -25,33 +33,47 @@ pub struct Cache {
 }
 
 impl Cache {
-    pub fn get_method_id(&self, name: &str) -> Result<i32> {
+    pub fn get_method_id(&self, name: &str) -> Result<i32, NotFoundError> {
         self.tx_methods
             .values()
             .find(|m| m.name == name)
             .map(|m| m.id)
-            .ok_or_else(|| anyhow!("method '{name}' not found"))
+            .ok_or_else(|| NotFoundError::TxMethod(name.to_string()))
     }
 
-    pub fn get_method_by_name(&self, name: &str) -> Result<&TxMethod> {
+    pub fn get_method_by_name(&self, name: &str) -> Result<&TxMethod, NotFoundError> {
         self.tx_methods
             .values()
             .find(|m| m.name == name)
-            .ok_or_else(|| anyhow!("method '{name}' not found"))
+            .ok_or_else(|| NotFoundError::TxMethod(name.to_string()))
     }
 
-    pub fn get_method_by_name_mut(&mut self, name: &str) -> Result<&mut TxMethod> {
+    pub fn get_method_by_name_mut(&mut self, name: &str) -> Result<&mut TxMethod, NotFoundError> {
         let method = self.get_method_by_name(name)?.id;
 
         Ok(self.tx_methods.get_mut(&method).unwrap())
     }
 
-    pub fn get_tag_id(&self, name: &str) -> Result<i32> {
+    /// Method of a row. Errors if the row points to a method that does not exist
+    pub fn get_method(&self, id: i32) -> Result<&TxMethod, InvalidValueError> {
+        self.tx_methods
+            .get(&id)
+            .ok_or_else(|| InvalidValueError::new("tx method id", id))
+    }
+
+    /// Tag of a row. Errors if the row points to a tag that does not exist
+    pub fn get_tag(&self, id: i32) -> Result<&Tag, InvalidValueError> {
+        self.tags
+            .get(&id)
+            .ok_or_else(|| InvalidValueError::new("tag id", id))
+    }
+
+    pub fn get_tag_id(&self, name: &str) -> Result<i32, NotFoundError> {
         self.tags
             .values()
             .find(|m| m.name == name)
             .map(|m| m.id)
-            .ok_or_else(|| anyhow!("tag '{name}' not found"))
+            .ok_or_else(|| NotFoundError::Tag(name.to_string()))
     }
 
     pub fn new_tags(&mut self, tags: Vec<Tag>) {
@@ -107,29 +129,169 @@ impl Cache {
     }
 }
 
-#[must_use]
-pub fn get_connection(db_url: &str) -> SqliteConnection {
-    let mut conn =
-        SqliteConnection::establish(db_url).expect("Failed to create connection to database");
+/// Opens the DB and runs any pending migrations
+pub fn try_get_connection(db_url: &str) -> Result<SqliteConnection, SetupError> {
+    let mut conn = try_get_connection_no_migrations(db_url)?;
 
     conn.run_pending_migrations(MIGRATIONS)
-        .expect("Failed to run database migrations");
+        .map_err(|e| SetupError::Migration(e.to_string()))?;
 
-    diesel::sql_query("PRAGMA foreign_keys = ON;")
-        .execute(&mut conn)
-        .unwrap();
+    Ok(conn)
+}
 
-    conn
+/// Opens the DB as it is without touching the schema
+pub fn try_get_connection_no_migrations(db_url: &str) -> Result<SqliteConnection, SetupError> {
+    let mut conn = SqliteConnection::establish(db_url)?;
+
+    diesel::sql_query("PRAGMA foreign_keys = ON;").execute(&mut conn)?;
+    // Writes wait for a backup that is reading the DB instead of failing right away
+    diesel::sql_query("PRAGMA busy_timeout = 5000;").execute(&mut conn)?;
+
+    Ok(conn)
 }
 
-#[must_use]
-pub fn get_connection_no_migrations(db_url: &str) -> SqliteConnection {
-    let mut conn =
-        SqliteConnection::establish(db_url).expect("Failed to create connection to database");
+#[derive(QueryableByName)]
+struct SchemaEntry {
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    entry_type: String,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    name: String,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    sql: String,
+}
+
+/// Copies the schema, every row and the AUTOINCREMENT counters of the DB at `db_url` into a new
+/// in-memory DB and runs any pending migrations on the copy. The original file is only read from
+pub fn try_copy_to_memory(db_url: &str) -> Result<SqliteConnection, SetupError> {
+    // Attaching a missing file would create an empty DB there
+    if !Path::new(db_url).is_file() {
+        return Err(ConnectionError::BadConnection(format!("{db_url} does not exist")).into());
+    }
+
+    let mut conn = SqliteConnection::establish(IN_MEMORY)?;
+
+    diesel::sql_query("ATTACH DATABASE ? AS source;")
+        .bind::<diesel::sql_types::Text, _>(db_url)
+        .execute(&mut conn)?;
+
+    // Tables go first so the rows can be copied before indexes and triggers exist
+    let entries: Vec<SchemaEntry> = diesel::sql_query(
+        "SELECT type AS entry_type, name, sql FROM source.sqlite_master
+        WHERE sql IS NOT NULL AND name NOT LIKE 'sqlite_%'
+        ORDER BY type != 'table', rowid;",
+    )
+    .load(&mut conn)?;
+
+    for entry in &entries {
+        diesel::sql_query(&entry.sql).execute(&mut conn)?;
+
+        if entry.entry_type == "table" {
+            let name = entry.name.replace('"', "\"\"");
+
+            diesel::sql_query(format!(
+                "INSERT INTO main.\"{name}\" SELECT * FROM source.\"{name}\";"
+            ))
+            .execute(&mut conn)?;
+        }
+    }
+
+    // The copied rows only bump the AUTOINCREMENT counters to the highest id left. Without the
+    // saved counters, ids of deleted txs and activities would be given out again
+    let has_sequence: bool = diesel::select(diesel::dsl::sql::<diesel::sql_types::Bool>(
+        "EXISTS (SELECT 1 FROM source.sqlite_master WHERE name = 'sqlite_sequence')",
+    ))
+    .get_result(&mut conn)?;
+
+    if has_sequence {
+        diesel::sql_query("DELETE FROM main.sqlite_sequence;").execute(&mut conn)?;
+        diesel::sql_query("INSERT INTO main.sqlite_sequence SELECT * FROM source.sqlite_sequence;")
+            .execute(&mut conn)?;
+    }
+
+    diesel::sql_query("DETACH DATABASE source;").execute(&mut conn)?;
+    diesel::sql_query("PRAGMA foreign_keys = ON;").execute(&mut conn)?;
+
+    conn.run_pending_migrations(MIGRATIONS)
+        .map_err(|e| SetupError::Migration(e.to_string()))?;
+
+    Ok(conn)
+}
+
+#[derive(QueryableByName)]
+struct MismatchCount {
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    count: i64,
+}
+
+/// Replaces every row of the DB with the rows of the DB at `source` in a single transaction.
+/// Both DBs must have the same migrations applied. If anything fails, the DB is left as it was
+pub fn replace_rows(conn: &mut SqliteConnection, source: &str) -> Result<(), SetupError> {
+    // Attaching a missing file would create an empty DB there
+    if !Path::new(source).is_file() {
+        return Err(ConnectionError::BadConnection(format!("{source} does not exist")).into());
+    }
+
+    diesel::sql_query("ATTACH DATABASE ? AS source;")
+        .bind::<diesel::sql_types::Text, _>(source)
+        .execute(conn)?;
+
+    let result = conn.immediate_transaction::<_, SetupError, _>(|conn| {
+        let mismatch: MismatchCount = diesel::sql_query(
+            "SELECT (SELECT COUNT(*) FROM (
+                SELECT version FROM main.__diesel_schema_migrations
+                EXCEPT SELECT version FROM source.__diesel_schema_migrations
+            )) + (SELECT COUNT(*) FROM (
+                SELECT version FROM source.__diesel_schema_migrations
+                EXCEPT SELECT version FROM main.__diesel_schema_migrations
+            )) AS count;",
+        )
+        .get_result(conn)?;
+
+        if mismatch.count != 0 {
+            return Err(SetupError::SchemaMismatch);
+        }
 
-    diesel::sql_query("PRAGMA foreign_keys = ON;")
-        .execute(&mut conn)
-        .unwrap();
+        // Rows are deleted and inserted in any order so the foreign keys are only checked
+        // once everything is in place
+        diesel::sql_query("PRAGMA defer_foreign_keys = ON;").execute(conn)?;
 
-    conn
+        let tables: Vec<SchemaEntry> = diesel::sql_query(
+            "SELECT type AS entry_type, name, sql FROM main.sqlite_master
+            WHERE type = 'table' AND name NOT LIKE 'sqlite_%'
+            AND name != '__diesel_schema_migrations';",
+        )
+        .load(conn)?;
+
+        for table in &tables {
+            let name = table.name.replace('"', "\"\"");
+
+            diesel::sql_query(format!("DELETE FROM main.\"{name}\";")).execute(conn)?;
+            diesel::sql_query(format!(
+                "INSERT INTO main.\"{name}\" SELECT * FROM source.\"{name}\";"
+            ))
+            .execute(conn)?;
+        }
+
+        Ok(())
+    });
+
+    diesel::sql_query("DETACH DATABASE source;").execute(conn)?;
+
+    result
+}
+
+/// # Panics
+///
+/// Panics if the DB cannot be opened or migrated. Use [`try_get_connection`] to handle it
+#[must_use]
+pub fn get_connection(db_url: &str) -> SqliteConnection {
+    try_get_connection(db_url).expect("Failed to set up the database")
+}
+
+/// # Panics
+///
+/// Panics if the DB cannot be opened. Use [`try_get_connection_no_migrations`] to handle it
+#[must_use]
+pub fn get_connection_no_migrations(db_url: &str) -> SqliteConnection {
+    try_get_connection_no_migrations(db_url).expect("Failed to set up the database")
 }
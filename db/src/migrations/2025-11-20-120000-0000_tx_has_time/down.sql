@@ -0,0 +1 @@
+ALTER TABLE txs DROP COLUMN has_time;
@@ -0,0 +1 @@
+ALTER TABLE txs ADD COLUMN has_time BOOLEAN NOT NULL DEFAULT FALSE;
@@ -3,9 +3,9 @@ use diesel::prelude::*;
 use diesel::result::Error;
 use rex_shared::models::LAST_POSSIBLE_TIME;
 
-use crate::ConnCache;
 use crate::models::{ActivityNature, ActivityTx, FullActivityTx};
 use crate::schema::activities;
+use crate::{ConnCache, InvalidValueError};
 
 #[derive(Clone, Queryable, Selectable, Insertable)]
 #[diesel(table_name = activities)]
@@ -18,7 +18,7 @@ pub struct NewActivity {
 #[diesel(table_name = activities)]
 pub struct Activity {
     pub id: i32,
-    date: NaiveDateTime,
+    pub date: NaiveDateTime,
     pub activity_type: String,
 }
 
@@ -113,27 +113,32 @@ impl Activity {
 
     #[must_use]
     pub fn to_array(&self) -> Vec<String> {
-        let activity_type: ActivityNature = self.activity_type.as_str().into();
+        // Unknown types are shown as they are saved instead of failing the whole view
+        let activity_type = ActivityNature::try_from(self.activity_type.as_str())
+            .map_or_else(|_| self.activity_type.clone(), |nature| nature.to_string());
+
         vec![
             self.date.format("%a %d %I:%M %p").to_string(),
-            activity_type.to_string(),
+            activity_type,
         ]
     }
 }
 
 impl ActivityWithTxs {
-    #[must_use]
-    pub fn to_array(&self) -> Vec<Vec<String>> {
-        let first_tx = self.txs.first().unwrap();
-
-        let last_tx = self.txs.last().unwrap();
-
-        match self.activity.activity_type.as_str().into() {
-            ActivityNature::PositionSwap | ActivityNature::EditTx => {
-                assert!(
-                    (first_tx.id != last_tx.id),
-                    "Both activity tx id should not have matched"
-                );
+    /// Activity txs as table rows. Rows edited outside of Rex that no longer fit the activity type
+    /// return an error instead of panicking.
+    pub fn to_array(&self) -> Result<Vec<Vec<String>>, InvalidValueError> {
+        let (Some(first_tx), Some(last_tx)) = (self.txs.first(), self.txs.last()) else {
+            return Err(InvalidValueError::new("activity id", self.activity.id));
+        };
+
+        let activity_type = ActivityNature::try_from(self.activity.activity_type.as_str());
+
+        match activity_type {
+            Ok(nature @ (ActivityNature::PositionSwap | ActivityNature::EditTx)) => {
+                if first_tx.id == last_tx.id {
+                    return Err(InvalidValueError::new("activity id", self.activity.id));
+                }
 
                 let lower_id_tx = if first_tx.id < last_tx.id {
                     first_tx
@@ -150,14 +155,13 @@ impl ActivityWithTxs {
                 let mut lower_id_tx_array = lower_id_tx.to_array();
                 let mut higher_id_tx_array = higher_id_tx.to_array();
 
-                match self.activity.activity_type.as_str().into() {
+                match nature {
                     ActivityNature::PositionSwap => {
-                        let higher_display_order = higher_id_tx
-                            .display_order
-                            .expect("Display order should not be none for this type of activity");
-                        let lower_display_order = lower_id_tx
-                            .display_order
-                            .expect("Display order should not be none for this type of activity");
+                        let (Some(higher_display_order), Some(lower_display_order)) =
+                            (higher_id_tx.display_order, lower_id_tx.display_order)
+                        else {
+                            return Err(InvalidValueError::new("activity id", self.activity.id));
+                        };
 
                         lower_id_tx_array
                             .push(format!("{higher_display_order} → {lower_display_order}"));
@@ -165,21 +169,19 @@ impl ActivityWithTxs {
                         higher_id_tx_array
                             .push(format!("{lower_display_order} → {higher_display_order}"));
 
-                        vec![lower_id_tx_array, higher_id_tx_array]
+                        Ok(vec![lower_id_tx_array, higher_id_tx_array])
                     }
                     ActivityNature::EditTx => {
                         lower_id_tx_array.push("New Tx".to_string());
 
                         higher_id_tx_array.push("Old Tx".to_string());
 
-                        vec![lower_id_tx_array, higher_id_tx_array]
+                        Ok(vec![lower_id_tx_array, higher_id_tx_array])
                     }
                     _ => unreachable!(),
                 }
             }
-            _ => {
-                vec![first_tx.to_array()]
-            }
+            _ => Ok(vec![first_tx.to_array()]),
         }
     }
 }
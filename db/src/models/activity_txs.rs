@@ -27,14 +27,14 @@ pub struct ActivityTx {
 
 pub struct FullActivityTx {
     pub id: i32,
-    date: Option<String>,
+    pub date: Option<String>,
     pub details: Option<String>,
-    from_method: Option<TxMethod>,
-    to_method: Option<TxMethod>,
-    amount: Option<AmountNature>,
-    tx_type: Option<TxType>,
+    pub from_method: Option<TxMethod>,
+    pub to_method: Option<TxMethod>,
+    pub amount: Option<AmountNature>,
+    pub tx_type: Option<TxType>,
     pub display_order: Option<i32>,
-    tags: Vec<Tag>,
+    pub tags: Vec<Tag>,
 }
 
 #[derive(Insertable)]
@@ -237,27 +237,28 @@ impl ActivityTx {
                 let tag_ids = tx_tags_map.get(&tx.id).unwrap_or(&EMPTY);
                 let mut v = Vec::with_capacity(tag_ids.len());
                 for tag_id in tag_ids {
-                    v.push(db_conn.cache().tags.get(tag_id).unwrap().clone());
+                    v.push(db_conn.cache().get_tag(*tag_id)?.clone());
                 }
                 v
             };
 
             let from_method = tx
                 .from_method
-                .as_ref()
-                .map(|method_id| db_conn.cache().tx_methods.get(method_id).unwrap().clone());
+                .map(|method_id| db_conn.cache().get_method(method_id).cloned())
+                .transpose()?;
 
             let to_method = tx
                 .to_method
-                .as_ref()
-                .map(|method_id| db_conn.cache().tx_methods.get(method_id).unwrap().clone());
+                .map(|method_id| db_conn.cache().get_method(method_id).cloned())
+                .transpose()?;
 
-            let tx_type = tx.tx_type.as_ref().map(|tx_type| tx_type.as_str().into());
+            let tx_type = tx.tx_type.as_deref().map(TxType::try_from).transpose()?;
 
             let mut amount = None;
 
             if let Some(a) = tx.amount.as_ref() {
-                let amount_type: AmountType = tx.amount_type.as_ref().unwrap().as_str().into();
+                let amount_type = tx.amount_type.as_deref().unwrap_or_default();
+                let amount_type = AmountType::try_from(amount_type)?;
                 amount = Some(AmountNature::from_type(amount_type, Cent::new(*a)));
             }
 
@@ -6,7 +6,7 @@ use rex_shared::models::Cent;
 use std::collections::{HashMap, HashSet};
 
 use crate::ConnCache;
-use crate::models::FetchNature;
+use crate::models::{FetchNature, Tx, TxType};
 use crate::schema::balances;
 
 #[derive(Clone, Debug, Queryable, Insertable, Selectable)]
@@ -118,12 +118,13 @@ impl Balance {
             pending_balance_tx_methods.insert(key);
         }
 
-        let date_year = date.year();
-        let mut date_month = date.month() as i32;
+        // Balances are saved monthly. Use the month the period starts in
+        let start_date = nature
+            .date_range(date)
+            .map_or(date, |(start_date, _)| start_date);
 
-        if let FetchNature::Yearly = nature {
-            date_month = 1;
-        }
+        let date_year = start_date.year();
+        let date_month = start_date.month() as i32;
 
         let tx_method_ids: Vec<i32> = db_conn.cache().tx_methods.keys().copied().collect();
 
@@ -170,19 +171,9 @@ impl Balance {
     ) -> Result<HashMap<i32, Cent>, Error> {
         use crate::schema::balances::dsl::{balances, is_final_balance, method_id, month, year};
 
-        let mut ongoing_date = date;
-
-        if let FetchNature::Yearly = nature {
-            ongoing_date = NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap();
-        }
-
-        let mut found_method_balances = HashMap::new();
-
-        let mut pending_balance_tx_methods = HashSet::new();
-
         // All means all TXs were fetched. The last balance is the balance before the first TX
         // which is 0
-        if let FetchNature::All = nature {
+        let Some((start_date, _)) = nature.date_range(date) else {
             let mut to_return = HashMap::new();
 
             for key in db_conn.cache().tx_methods.keys().copied() {
@@ -190,7 +181,13 @@ impl Balance {
             }
 
             return Ok(to_return);
-        }
+        };
+
+        let mut ongoing_date = start_date;
+
+        let mut found_method_balances = HashMap::new();
+
+        let mut pending_balance_tx_methods = HashSet::new();
 
         for key in db_conn.cache().tx_methods.keys().copied() {
             pending_balance_tx_methods.insert(key);
@@ -225,7 +222,7 @@ impl Balance {
         }
 
         // Fallback. Start from the previous month and look for the last non-final balance
-        let date = date - Months::new(1);
+        let date = start_date - Months::new(1);
 
         if !pending_balance_tx_methods.is_empty() {
             for mid in pending_balance_tx_methods {
@@ -254,6 +251,38 @@ impl Balance {
             }
         }
 
+        // Periods starting mid-month also need the txs of the month before the period start
+        if start_date.day() != 1 {
+            let month_start = start_date.with_day(1).unwrap();
+            let month_end = start_date.pred_opt().unwrap();
+
+            let txs = Tx::get_txs(
+                month_start,
+                FetchNature::Custom(month_start, month_end),
+                db_conn,
+            )?;
+
+            for tx in txs {
+                let amount = Cent::new(tx.amount);
+
+                match TxType::try_from(tx.tx_type.as_str())? {
+                    TxType::Income | TxType::Borrow | TxType::LendRepay => {
+                        *found_method_balances.entry(tx.from_method).or_default() += amount;
+                    }
+                    TxType::Expense | TxType::Lend | TxType::BorrowRepay => {
+                        *found_method_balances.entry(tx.from_method).or_default() -= amount;
+                    }
+                    TxType::Transfer => {
+                        *found_method_balances.entry(tx.from_method).or_default() -= amount;
+
+                        if let Some(to_method) = tx.to_method {
+                            *found_method_balances.entry(to_method).or_default() += amount;
+                        }
+                    }
+                }
+            }
+        }
+
         Ok(found_method_balances)
     }
 
@@ -1,9 +1,11 @@
-use chrono::NaiveDateTime;
+use chrono::{Datelike, Days, Months, NaiveDate, NaiveDateTime};
 use rex_shared::models::Cent;
 use std::fmt::{self, Display};
-use strum_macros::{Display, EnumIter};
+use strum_macros::{Display, EnumIter, IntoStaticStr};
 
-#[derive(Clone, Debug, Copy, Display, EnumIter)]
+use crate::InvalidValueError;
+
+#[derive(Clone, Debug, Copy, Display, EnumIter, IntoStaticStr)]
 pub enum TxType {
     #[strum(to_string = "Income")]
     Income,
@@ -26,6 +28,84 @@ pub enum FetchNature {
     Monthly,
     Yearly,
     All,
+    /// Monday to Sunday week
+    Weekly,
+    /// Jan-Mar, Apr-Jun, Jul-Sep and Oct-Dec
+    Quarterly,
+    /// An inclusive start and end date. The date passed along with it is ignored
+    Custom(NaiveDate, NaiveDate),
+}
+
+impl FetchNature {
+    /// Returns the inclusive start and end date of the period the given date falls in.
+    /// None means there is no date limit.
+    #[must_use]
+    pub fn date_range(&self, d: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+        let (start_date, end_date) = match self {
+            FetchNature::Monthly => {
+                let start_date = NaiveDate::from_ymd_opt(d.year(), d.month(), 1).unwrap();
+                (start_date, start_date + Months::new(1) - Days::new(1))
+            }
+            FetchNature::Yearly => {
+                let start_date = NaiveDate::from_ymd_opt(d.year(), 1, 1).unwrap();
+                (start_date, start_date + Months::new(12) - Days::new(1))
+            }
+            FetchNature::Weekly => {
+                let start_date = d - Days::new(u64::from(d.weekday().num_days_from_monday()));
+                (start_date, start_date + Days::new(6))
+            }
+            FetchNature::Quarterly => {
+                let start_month = (d.month0() / 3) * 3 + 1;
+                let start_date = NaiveDate::from_ymd_opt(d.year(), start_month, 1).unwrap();
+                (start_date, start_date + Months::new(3) - Days::new(1))
+            }
+            FetchNature::Custom(start_date, end_date) => {
+                (*start_date.min(end_date), *start_date.max(end_date))
+            }
+            FetchNature::All => return None,
+        };
+
+        Some((start_date, end_date))
+    }
+
+    /// Returns a date within the period right before the one the given date falls in
+    /// along with the nature to fetch it with. Custom ranges go back by their own length.
+    #[must_use]
+    pub fn previous_period(&self, d: NaiveDate) -> Option<(NaiveDate, FetchNature)> {
+        let (start_date, end_date) = self.date_range(d)?;
+
+        let previous_date = start_date.pred_opt()?;
+
+        if let FetchNature::Custom(..) = self {
+            let length = end_date.signed_duration_since(start_date).num_days() as u64;
+            let previous_start = previous_date.checked_sub_days(Days::new(length))?;
+
+            return Some((
+                previous_start,
+                FetchNature::Custom(previous_start, previous_date),
+            ));
+        }
+
+        Some((previous_date, *self))
+    }
+
+    /// Returns a date within the period right after the one the given date falls in
+    /// along with the nature to fetch it with. Custom ranges go forward by their own length.
+    #[must_use]
+    pub fn next_period(&self, d: NaiveDate) -> Option<(NaiveDate, FetchNature)> {
+        let (start_date, end_date) = self.date_range(d)?;
+
+        let next_date = end_date.succ_opt()?;
+
+        if let FetchNature::Custom(..) = self {
+            let length = end_date.signed_duration_since(start_date).num_days() as u64;
+            let next_end = next_date.checked_add_days(Days::new(length))?;
+
+            return Some((next_date, FetchNature::Custom(next_date, next_end)));
+        }
+
+        Some((next_date, *self))
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -121,15 +201,17 @@ impl From<AmountType> for String {
     }
 }
 
-impl From<&str> for AmountType {
-    fn from(value: &str) -> Self {
+impl TryFrom<&str> for AmountType {
+    type Error = InvalidValueError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
-            "exact" => AmountType::Exact,
-            "more_than" => AmountType::MoreThan,
-            "more_than_equal" => AmountType::MoreThanEqual,
-            "less_than" => AmountType::LessThan,
-            "less_than_equal" => AmountType::LessThanEqual,
-            other => panic!("Invalid AmountType string: {other}"),
+            "exact" => Ok(AmountType::Exact),
+            "more_than" => Ok(AmountType::MoreThan),
+            "more_than_equal" => Ok(AmountType::MoreThanEqual),
+            "less_than" => Ok(AmountType::LessThan),
+            "less_than_equal" => Ok(AmountType::LessThanEqual),
+            other => Err(InvalidValueError::new("amount type", other)),
         }
     }
 }
@@ -143,15 +225,17 @@ pub enum ActivityNature {
     PositionSwap,
 }
 
-impl From<&str> for ActivityNature {
-    fn from(s: &str) -> Self {
+impl TryFrom<&str> for ActivityNature {
+    type Error = InvalidValueError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
         match s {
-            "add_tx" => ActivityNature::AddTx,
-            "edit_tx" => ActivityNature::EditTx,
-            "delete_tx" => ActivityNature::DeleteTx,
-            "search_tx" => ActivityNature::SearchTx,
-            "position_swap" => ActivityNature::PositionSwap,
-            other => panic!("Invalid TxType string: {other}"),
+            "add_tx" => Ok(ActivityNature::AddTx),
+            "edit_tx" => Ok(ActivityNature::EditTx),
+            "delete_tx" => Ok(ActivityNature::DeleteTx),
+            "search_tx" => Ok(ActivityNature::SearchTx),
+            "position_swap" => Ok(ActivityNature::PositionSwap),
+            other => Err(InvalidValueError::new("activity type", other)),
         }
     }
 }
@@ -180,17 +264,19 @@ impl Display for ActivityNature {
     }
 }
 
-impl From<&str> for TxType {
-    fn from(s: &str) -> Self {
+impl TryFrom<&str> for TxType {
+    type Error = InvalidValueError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
         match s {
-            "Income" => TxType::Income,
-            "Expense" => TxType::Expense,
-            "Transfer" => TxType::Transfer,
-            "Borrow" => TxType::Borrow,
-            "Lend" => TxType::Lend,
-            "Borrow Repay" => TxType::BorrowRepay,
-            "Lend Repay" => TxType::LendRepay,
-            other => panic!("Invalid TxType string: {other}"),
+            "Income" => Ok(TxType::Income),
+            "Expense" => Ok(TxType::Expense),
+            "Transfer" => Ok(TxType::Transfer),
+            "Borrow" => Ok(TxType::Borrow),
+            "Lend" => Ok(TxType::Lend),
+            "Borrow Repay" => Ok(TxType::BorrowRepay),
+            "Lend Repay" => Ok(TxType::LendRepay),
+            other => Err(InvalidValueError::new("tx type", other)),
         }
     }
 }
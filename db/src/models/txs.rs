@@ -1,5 +1,5 @@
-use chrono::{Datelike, Days, Months, NaiveDate, NaiveDateTime, NaiveTime};
-use diesel::dsl::{count_star, sql};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+use diesel::dsl::{count_star, max, min, sql};
 use diesel::prelude::*;
 use diesel::result::Error;
 use diesel::sql_types::{Integer, Text};
@@ -140,6 +140,9 @@ pub struct FullTx {
     pub tx_type: TxType,
     pub tags: Vec<Tag>,
     pub display_order: i32,
+    /// Whether the user set the time. Without one the time is not shown and does not order
+    /// the tx
+    pub has_time: bool,
 }
 
 #[derive(Clone, Queryable, Selectable, Insertable)]
@@ -152,6 +155,7 @@ pub struct Tx {
     pub amount: i64,
     pub tx_type: String,
     display_order: i32,
+    has_time: bool,
 }
 
 #[derive(Clone, Insertable)]
@@ -163,6 +167,7 @@ pub struct NewTx<'a> {
     pub to_method: Option<i32>,
     pub amount: i64,
     pub tx_type: &'a str,
+    pub has_time: bool,
 }
 
 impl<'a> NewTx<'a> {
@@ -182,9 +187,17 @@ impl<'a> NewTx<'a> {
             to_method,
             amount,
             tx_type,
+            has_time: false,
         }
     }
 
+    /// Marks whether the time of the date was set by the user
+    #[must_use]
+    pub fn with_time(mut self, has_time: bool) -> Self {
+        self.has_time = has_time;
+        self
+    }
+
     pub fn insert(self, db_conn: &mut impl ConnCache) -> Result<Tx, Error> {
         use crate::schema::txs::dsl::txs;
 
@@ -238,7 +251,7 @@ impl FullTx {
                 let tag_ids = tx_tags_map.get(&tx.id).unwrap_or(&EMPTY);
                 let mut v = Vec::with_capacity(tag_ids.len());
                 for tag_id in tag_ids {
-                    v.push(db_conn.cache().tags.get(tag_id).unwrap().clone());
+                    v.push(db_conn.cache().get_tag(*tag_id)?.clone());
                 }
                 v
             };
@@ -247,19 +260,16 @@ impl FullTx {
                 id: tx.id,
                 date: tx.date,
                 details: tx.details,
-                from_method: db_conn
-                    .cache()
-                    .tx_methods
-                    .get(&tx.from_method)
-                    .unwrap()
-                    .clone(),
+                from_method: db_conn.cache().get_method(tx.from_method)?.clone(),
                 to_method: tx
                     .to_method
-                    .map(|method_id| db_conn.cache().tx_methods.get(&method_id).unwrap().clone()),
+                    .map(|method_id| db_conn.cache().get_method(method_id).cloned())
+                    .transpose()?,
                 amount: Cent::new(tx.amount),
-                tx_type: tx.tx_type.as_str().into(),
+                tx_type: tx.tx_type.as_str().try_into()?,
                 tags,
                 display_order: tx.display_order,
+                has_time: tx.has_time,
             };
 
             to_return.push(full_tx);
@@ -394,6 +404,16 @@ impl FullTx {
         ]
     }
 
+    /// Time that orders the tx within its day. Untimed txs count as midnight
+    #[must_use]
+    pub fn order_time(&self) -> NaiveTime {
+        if self.has_time {
+            self.date.time()
+        } else {
+            NaiveTime::MIN
+        }
+    }
+
     pub fn set_display_order(&self, db_conn: &mut impl ConnCache) -> Result<usize, Error> {
         use crate::schema::txs::dsl::{display_order, id, txs};
 
@@ -426,33 +446,14 @@ impl Tx {
         nature: FetchNature,
         db_conn: &mut impl ConnCache,
     ) -> Result<Vec<Self>, Error> {
-        let d = d.and_time(NaiveTime::MIN);
-
         use crate::schema::txs::dsl::{date, display_order, id, txs};
 
-        let dates = match nature {
-            FetchNature::Monthly => {
-                let start_date = NaiveDate::from_ymd_opt(d.year(), d.month(), 1).unwrap();
-
-                let end_date = start_date + Months::new(1) - Days::new(1);
-
-                let start_date = start_date.and_time(NaiveTime::MIN);
-                let end_date = end_date.and_time(LAST_POSSIBLE_TIME);
-
-                Some((start_date, end_date))
-            }
-            FetchNature::Yearly => {
-                let start_date = NaiveDate::from_ymd_opt(d.year(), 1, 1).unwrap();
-
-                let end_date = start_date + Months::new(12) - Days::new(1);
-
-                let start_date = start_date.and_time(NaiveTime::MIN);
-                let end_date = end_date.and_time(LAST_POSSIBLE_TIME);
-
-                Some((start_date, end_date))
-            }
-            FetchNature::All => None,
-        };
+        let dates = nature.date_range(d).map(|(start_date, end_date)| {
+            (
+                start_date.and_time(NaiveTime::MIN),
+                end_date.and_time(LAST_POSSIBLE_TIME),
+            )
+        });
 
         let mut query = txs.into_boxed();
 
@@ -460,9 +461,12 @@ impl Tx {
             query = query.filter(date.ge(start_date)).filter(date.le(end_date));
         }
 
+        // Same day txs go by the time the user set first. Untimed txs count as midnight so they
+        // come first and keep the display order among themselves
         query
             .order((
                 sql::<Text>("DATE(date) ASC"),
+                sql::<Text>("CASE WHEN has_time THEN TIME(date) ELSE '00:00:00' END ASC"),
                 sql::<Integer>("CASE WHEN display_order = 0 THEN 1 ELSE 0 END ASC"),
                 display_order.asc(),
                 id.asc(),
@@ -488,9 +492,31 @@ impl Tx {
             amount: new_tx.amount,
             tx_type: new_tx.tx_type.to_string(),
             display_order: 0,
+            has_time: new_tx.has_time,
         }
     }
 
+    /// Returns the date of the earliest and the latest tx, if there are any txs
+    pub fn get_date_range(
+        db_conn: &mut impl ConnCache,
+    ) -> Result<Option<(NaiveDate, NaiveDate)>, Error> {
+        use crate::schema::txs::dsl::{date, txs};
+
+        let (first_date, last_date) =
+            txs.select((min(date), max(date)))
+                .first::<(Option<NaiveDateTime>, Option<NaiveDateTime>)>(db_conn.conn())?;
+
+        Ok(first_date
+            .zip(last_date)
+            .map(|(first_date, last_date)| (first_date.date(), last_date.date())))
+    }
+
+    /// Returns the year of the earliest and the latest tx, if there are any txs
+    pub fn get_year_range(db_conn: &mut impl ConnCache) -> Result<Option<(i32, i32)>, Error> {
+        Ok(Self::get_date_range(db_conn)?
+            .map(|(first_date, last_date)| (first_date.year(), last_date.year())))
+    }
+
     pub fn get_all_details(db_conn: &mut impl ConnCache) -> Result<Vec<String>, Error> {
         use crate::schema::txs::dsl::{details, txs};
 
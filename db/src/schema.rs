@@ -74,6 +74,7 @@ diesel::table! {
         amount -> BigInt,
         tx_type -> Text,
         display_order -> Integer,
+        has_time -> Bool,
     }
 }
 
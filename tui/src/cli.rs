@@ -0,0 +1,544 @@
+use anyhow::{Result, anyhow};
+use chrono::{Datelike, Local, NaiveDate};
+use clap::{Args, Parser, Subcommand};
+use rex_app::conn::{DbConn, FetchNature};
+use rex_app::modifier::{parse_search_fields, parse_tx_fields};
+use rex_app::ui_helper::{DateType, VerifierError};
+use rex_app::views::{
+    FullSummary, FullTx, ReportFormat, SummaryJson, SummaryReport, TagSummaryJson,
+};
+use rex_shared::models::Cent;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::page_handler::MONTHS;
+use crate::server::serve;
+
+/// A TUI app for managing Incomes and Expenses. Starts the TUI if no command is given
+#[derive(Parser)]
+#[command(name = "rex", version)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Start the TUI on an in-memory copy of the database. Every change is discarded on exit
+    #[arg(long)]
+    pub ephemeral: bool,
+    /// Directory of the app data. Overrides REX_DATA_DIR and the OS data directory
+    #[arg(long, global = true, value_name = "DIR")]
+    pub data_dir: Option<PathBuf>,
+    /// Database file to open in the default profile. Overrides REX_DB and the location set
+    /// in the config
+    #[arg(long, global = true, value_name = "FILE")]
+    pub db: Option<PathBuf>,
+    /// Config file to use instead of rex.json in the data directory
+    #[arg(long, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Add a new transaction
+    Add(AddArgs),
+    /// List the transactions of a month, a year or all time
+    List(ListArgs),
+    /// Show the current balance of every method
+    Balance(BalanceArgs),
+    /// Search for transactions. Every field is optional
+    Search(SearchArgs),
+    /// Print the summary of a month, a year or all time as Markdown, HTML or JSON
+    Summary(SummaryArgs),
+    /// Show the activities of a month
+    Activity(ActivityArgs),
+    /// Export all transactions as CSV
+    Export(ExportArgs),
+    /// Serve a local HTTP JSON API for adding, editing, deleting and fetching transactions
+    Serve(ServeArgs),
+    /// Encrypt the database and its backups with a passphrase that is asked for on every start
+    Encrypt,
+    /// Decrypt the database and its backups back to plain files
+    Decrypt,
+}
+
+#[derive(Args)]
+pub struct AddArgs {
+    /// YYYY-MM-DD. Defaults to today
+    #[arg(long)]
+    date: Option<String>,
+    /// HH:MM
+    #[arg(long)]
+    time: Option<String>,
+    #[arg(long, default_value = "")]
+    details: String,
+    /// The method the amount is taken from or added to
+    #[arg(long)]
+    method: String,
+    /// The method the amount goes to. Required for Transfer
+    #[arg(long, default_value = "")]
+    to: String,
+    /// Supports expressions such as 100+20 or 1.5k
+    #[arg(long)]
+    amount: String,
+    /// Income, Expense, Transfer, Borrow, Lend, Borrow Repay or Lend Repay. Short forms such as E or I work too
+    #[arg(long = "type")]
+    tx_type: String,
+    /// Comma separated tags
+    #[arg(long, default_value = "")]
+    tags: String,
+}
+
+/// Selects a month with `--month`, a year with `--year` or all time with neither
+#[derive(Args, Default)]
+pub struct PeriodArgs {
+    /// YYYY-MM
+    #[arg(long, conflicts_with = "year")]
+    pub(crate) month: Option<String>,
+    /// YYYY
+    #[arg(long)]
+    pub(crate) year: Option<i32>,
+}
+
+#[derive(Args)]
+pub struct ListArgs {
+    #[command(flatten)]
+    period: PeriodArgs,
+    /// Print JSON instead of tab separated rows
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Args)]
+pub struct BalanceArgs {
+    /// Print JSON instead of tab separated rows
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Args)]
+pub struct SearchArgs {
+    /// YYYY, YYYY-MM or YYYY-MM-DD
+    #[arg(long, default_value = "")]
+    date: String,
+    #[arg(long, default_value = "")]
+    details: String,
+    #[arg(long, default_value = "")]
+    method: String,
+    #[arg(long, default_value = "")]
+    to: String,
+    /// An exact amount or a comparison such as >100 or <=50
+    #[arg(long, default_value = "")]
+    amount: String,
+    #[arg(long = "type", default_value = "")]
+    tx_type: String,
+    /// Comma separated tags
+    #[arg(long, default_value = "")]
+    tags: String,
+    /// Print JSON instead of tab separated rows
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Args)]
+pub struct SummaryArgs {
+    #[command(flatten)]
+    period: PeriodArgs,
+    /// Print HTML instead of Markdown
+    #[arg(long)]
+    html: bool,
+    /// Print JSON instead of Markdown
+    #[arg(long, conflicts_with = "html")]
+    json: bool,
+}
+
+#[derive(Args)]
+pub struct ActivityArgs {
+    /// YYYY-MM. Defaults to the current month
+    #[arg(long)]
+    month: Option<String>,
+    /// Print JSON instead of tab separated rows
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Args)]
+pub struct ServeArgs {
+    /// Address to listen on. Use 0.0.0.0 to allow other devices on the network
+    #[arg(long, default_value = "127.0.0.1:8750")]
+    bind: String,
+}
+
+/// JSON output of the summary command
+#[derive(Serialize)]
+pub(crate) struct SummaryOutput {
+    period: String,
+    summary: SummaryJson,
+    tags: Vec<TagSummaryJson>,
+}
+
+#[derive(Args)]
+pub struct ExportArgs {
+    /// File to save the CSV to. Prints to the terminal if not given
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+impl Command {
+    /// Whether the command changes the DB
+    #[must_use]
+    pub fn modifies_db(&self) -> bool {
+        matches!(self, Command::Add(_))
+    }
+}
+
+/// Turns a verifier result into an error with the corrected value, if there is one
+fn verified(result: Result<impl Sized, VerifierError>, corrected: &str) -> Result<()> {
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) if corrected.is_empty() => Err(anyhow!("{e}")),
+        Err(e) => Err(anyhow!("{e}. Did you mean {corrected}?")),
+    }
+}
+
+/// Parses a YYYY-MM month to the first day of the month
+fn parse_month(month: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{month}-01"), "%Y-%m-%d")
+        .map_err(|_| anyhow!("Month {month} is invalid. Example Month: 2025-03"))
+}
+
+impl PeriodArgs {
+    /// Date, fetch nature and name of the selected period
+    pub(crate) fn period(&self) -> Result<(NaiveDate, FetchNature, String)> {
+        if let Some(month) = &self.month {
+            let date = parse_month(month)?;
+
+            let name = format!("{} {}", MONTHS[date.month0() as usize], date.year());
+
+            return Ok((date, FetchNature::Monthly, name));
+        }
+
+        if let Some(year) = self.year {
+            let date = NaiveDate::from_ymd_opt(year, 1, 1)
+                .ok_or_else(|| anyhow!("Year {year} is invalid"))?;
+
+            return Ok((date, FetchNature::Yearly, year.to_string()));
+        }
+
+        Ok((
+            Local::now().date_naive(),
+            FetchNature::All,
+            "All Time".to_string(),
+        ))
+    }
+}
+
+fn print_rows(headers: &[&str], rows: &[Vec<String>]) {
+    println!("{}", headers.join("\t"));
+
+    for row in rows {
+        println!("{}", row.join("\t"));
+    }
+}
+
+fn print_json(value: &impl Serialize) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+
+    Ok(())
+}
+
+/// Fields of a tx as they would be typed on the Add Transaction page
+pub(crate) struct TxFields {
+    /// YYYY-MM-DD. Defaults to today
+    pub date: Option<String>,
+    /// HH:MM
+    pub time: Option<String>,
+    pub details: String,
+    pub method: String,
+    /// Only used by Transfer txs
+    pub to: String,
+    pub amount: String,
+    pub tx_type: String,
+    /// Comma separated tags
+    pub tags: String,
+}
+
+impl From<AddArgs> for TxFields {
+    fn from(args: AddArgs) -> Self {
+        Self {
+            date: args.date,
+            time: args.time,
+            details: args.details,
+            method: args.method,
+            to: args.to,
+            amount: args.amount,
+            tx_type: args.tx_type,
+            tags: args.tags,
+        }
+    }
+}
+
+impl TxFields {
+    /// Verifies and corrects every field the same way the Add Transaction page does.
+    /// Returns the date with the time if there is one
+    fn verify(&mut self, conn: &mut DbConn) -> Result<String> {
+        let mut date = self
+            .date
+            .take()
+            .unwrap_or_else(|| Local::now().date_naive().to_string());
+
+        verified(conn.verify().date(&mut date, DateType::Exact), &date)?;
+
+        if let Some(mut time) = self.time.take() {
+            verified(conn.verify().time(&mut time), &time)?;
+            date = format!("{date} {time}");
+        }
+
+        verified(conn.verify().tx_method(&mut self.method), &self.method)?;
+        verified(conn.verify().amount(&mut self.amount), "")?;
+        verified(conn.verify().tx_type(&mut self.tx_type), &self.tx_type)?;
+
+        if self.tx_type == "Transfer" {
+            if self.to.is_empty() {
+                return Err(anyhow!("Transfer requires the to method"));
+            }
+
+            verified(conn.verify().tx_method(&mut self.to), &self.to)?;
+
+            if self.to == self.method {
+                return Err(anyhow!("Transfer methods must be different"));
+            }
+        } else {
+            self.to.clear();
+        }
+
+        conn.verify().tags(&mut self.tags);
+
+        Ok(date)
+    }
+
+    /// Verifies the fields and saves the tx. Replaces `old_tx` if given
+    pub(crate) fn save(mut self, old_tx: Option<&FullTx>, conn: &mut DbConn) -> Result<String> {
+        let date = self.verify(conn)?;
+
+        let new_tx = parse_tx_fields(
+            &date,
+            &self.details,
+            &self.method,
+            &self.to,
+            &self.amount,
+            &self.tx_type,
+            conn,
+        )?;
+
+        if let Some(old_tx) = old_tx {
+            conn.edit_tx(old_tx, new_tx, &self.tags)?;
+        } else {
+            conn.add_new_tx(new_tx, &self.tags)?;
+        }
+
+        Ok(format!("{} of {} on {date}", self.tx_type, self.amount))
+    }
+}
+
+fn add_tx(args: AddArgs, conn: &mut DbConn) -> Result<()> {
+    let saved = TxFields::from(args).save(None, conn)?;
+
+    println!("Added {saved}");
+
+    Ok(())
+}
+
+fn list_txs(args: &ListArgs, conn: &mut DbConn) -> Result<()> {
+    let (date, nature, _) = args.period.period()?;
+
+    let txs = conn.fetch_txs_with_date(date, nature)?;
+
+    if args.json {
+        return print_json(&txs.json(conn));
+    }
+
+    let rows: Vec<Vec<String>> = (0..txs.len())
+        .map(|index| txs.get_tx(index).to_array(true))
+        .collect();
+
+    print_rows(
+        &["Date", "Details", "Method", "Amount", "Type", "Tags"],
+        &rows,
+    );
+
+    Ok(())
+}
+
+fn print_balance(args: &BalanceArgs, conn: &mut DbConn) -> Result<()> {
+    if args.json {
+        return print_json(&conn.get_final_balances_json()?);
+    }
+
+    let balances = conn.get_final_balances()?;
+
+    let mut total = Cent::new(0);
+    let mut rows = Vec::new();
+
+    for method in conn.get_tx_methods_sorted() {
+        let balance = Cent::new(balances.get(&method.id).map_or(0, |b| b.balance));
+        total += balance;
+
+        rows.push(vec![
+            method.name.clone(),
+            format!("{:.2}", balance.dollar()),
+        ]);
+    }
+
+    rows.push(vec!["Total".to_string(), format!("{:.2}", total.dollar())]);
+
+    print_rows(&["Method", "Balance"], &rows);
+
+    Ok(())
+}
+
+fn search_txs(args: &SearchArgs, conn: &mut DbConn) -> Result<()> {
+    let search = parse_search_fields(
+        &args.date,
+        &args.details,
+        &args.method,
+        &args.to,
+        &args.amount,
+        &args.tx_type,
+        &args.tags,
+        conn,
+    )?;
+
+    let txs = conn.search_txs(search)?;
+
+    if args.json {
+        return print_json(&txs.json());
+    }
+
+    print_rows(
+        &["Date", "Details", "Method", "Amount", "Type", "Tags"],
+        &txs.tx_array(),
+    );
+
+    Ok(())
+}
+
+/// Summary of a period compared with the period before it, like the Summary page shows
+fn period_summary(
+    date: NaiveDate,
+    nature: FetchNature,
+    conn: &mut DbConn,
+) -> Result<(FullSummary, Vec<Vec<String>>, Vec<TagSummaryJson>)> {
+    let summary_view = conn.get_summary_with_date(date, nature)?;
+
+    let last_summary_view = match nature.previous_period(date) {
+        Some((date, nature)) => Some(conn.get_summary_with_date(date, nature)?),
+        None => None,
+    };
+
+    let last_full_summary = last_summary_view
+        .as_ref()
+        .map(|view| view.generate_summary(None, conn));
+
+    let full_summary = summary_view.generate_summary(last_full_summary.as_ref(), conn);
+    let tags = summary_view.tags_array(last_summary_view.as_ref(), conn);
+    let tags_json = summary_view.tags_json(last_summary_view.as_ref(), conn);
+
+    Ok((full_summary, tags, tags_json))
+}
+
+pub(crate) fn summary_output(period: &PeriodArgs, conn: &mut DbConn) -> Result<SummaryOutput> {
+    let (date, nature, name) = period.period()?;
+    let (full_summary, _, tags) = period_summary(date, nature, conn)?;
+
+    Ok(SummaryOutput {
+        period: name,
+        summary: full_summary.json(),
+        tags,
+    })
+}
+
+fn print_summary(args: &SummaryArgs, conn: &mut DbConn) -> Result<()> {
+    if args.json {
+        return print_json(&summary_output(&args.period, conn)?);
+    }
+
+    let (date, nature, name) = args.period.period()?;
+    let (full_summary, tags, _) = period_summary(date, nature, conn)?;
+
+    let format = if args.html {
+        ReportFormat::Html
+    } else {
+        ReportFormat::Markdown
+    };
+
+    let title = format!("Rex Summary - {name}");
+    let report = SummaryReport::new(&title, nature, &full_summary, &tags);
+
+    print!("{}", report.render(format));
+
+    Ok(())
+}
+
+fn print_activities(args: &ActivityArgs, conn: &mut DbConn) -> Result<()> {
+    let date = match &args.month {
+        Some(month) => parse_month(month)?,
+        None => Local::now().date_naive(),
+    };
+
+    let month = MONTHS[date.month0() as usize];
+    let activities = conn.get_activity_view_with_str(month, &date.year().to_string())?;
+
+    if args.json {
+        return print_json(&activities.json());
+    }
+
+    let mut rows = Vec::new();
+
+    for (index, activity) in activities.get_activity_table().into_iter().enumerate() {
+        for tx in activities.get_activity_txs_table(Some(index)) {
+            let mut row = activity.clone();
+            row.extend(tx);
+            rows.push(row);
+        }
+    }
+
+    print_rows(
+        &[
+            "Time", "Activity", "Date", "Details", "Method", "Amount", "Type", "Tags", "Extra",
+        ],
+        &rows,
+    );
+
+    Ok(())
+}
+
+fn export_txs(args: &ExportArgs, conn: &mut DbConn) -> Result<()> {
+    let txs = conn.fetch_txs_with_date(Local::now().date_naive(), FetchNature::All)?;
+    let csv = txs.to_csv();
+
+    if let Some(path) = &args.output {
+        fs::write(path, csv)?;
+        println!("Exported {} transactions to {}", txs.len(), path.display());
+    } else {
+        print!("{csv}");
+    }
+
+    Ok(())
+}
+
+/// Runs a CLI command on the DB without starting the TUI
+pub fn run_command(command: Command, config: &mut Config, conn: &mut DbConn) -> Result<()> {
+    match command {
+        Command::Add(args) => add_tx(args, conn),
+        Command::List(args) => list_txs(&args, conn),
+        Command::Balance(args) => print_balance(&args, conn),
+        Command::Search(args) => search_txs(&args, conn),
+        Command::Summary(args) => print_summary(&args, conn),
+        Command::Activity(args) => print_activities(&args, conn),
+        Command::Export(args) => export_txs(&args, conn),
+        Command::Serve(args) => serve(&args.bind, config, conn),
+        Command::Encrypt | Command::Decrypt => {
+            unreachable!("Encryption commands are handled before the DB is opened")
+        }
+    }
+}
@@ -1,10 +1,105 @@
 use anyhow::{Result, anyhow};
+use chrono::Local;
+use dirs::data_local_dir;
+use std::cmp::Reverse;
+use std::env;
 use std::fs::{self, File};
 use std::io::Read;
-use std::path::PathBuf;
+use std::path::{self, Path, PathBuf};
 
+use rex_app::backup::{
+    AutoBackup, BackupFile, Retention, copy_db, list_backups, prune_backups, reserve_backup_path,
+};
+use rex_app::crypto::EncryptedDb;
 use serde::{Deserialize, Serialize};
 
+use crate::server::random_token;
+
+pub const DB_NAME: &str = "rex.sqlite";
+pub const ENCRYPTED_DB_NAME: &str = "rex.sqlite.enc";
+/// Name of the profile that lives in the app data directory itself
+pub const DEFAULT_PROFILE: &str = "Default";
+/// Directory of the profiles inside the app data directory and inside each backup path
+const PROFILES_DIR: &str = "profiles";
+/// Replaces the app data directory. `--data-dir` takes precedence
+pub const DATA_DIR_ENV: &str = "REX_DATA_DIR";
+/// Replaces the DB of the default profile. `--db` takes precedence
+pub const DB_ENV: &str = "REX_DB";
+
+/// Where the app data is read from and written to. Every path is absolute so nothing depends
+/// on the directory the app was started from
+pub struct DataPaths {
+    pub data_dir: PathBuf,
+    /// The rex.json to use. The one in the data directory if not set
+    pub config: PathBuf,
+    /// DB file that replaces the DB of the default profile
+    pub db: Option<PathBuf>,
+}
+
+impl DataPaths {
+    /// Picks each path from the command line first, then the environment and then the default
+    /// location. The data directory is created if it does not exist
+    pub fn resolve(
+        data_dir: Option<PathBuf>,
+        db: Option<PathBuf>,
+        config: Option<PathBuf>,
+    ) -> Result<Self> {
+        let data_dir = match data_dir.or_else(|| env_path(DATA_DIR_ENV)) {
+            Some(data_dir) => data_dir,
+            None => data_local_dir()
+                .ok_or_else(|| anyhow!("Could not find local data directory"))?
+                .join("Rex"),
+        };
+
+        fs::create_dir_all(&data_dir)?;
+
+        let data_dir = path::absolute(data_dir)?;
+
+        let config = match config {
+            Some(config) => path::absolute(config)?,
+            None => data_dir.join("rex.json"),
+        };
+
+        let db = db
+            .or_else(|| env_path(DB_ENV))
+            .map(path::absolute)
+            .transpose()?;
+
+        Ok(Self {
+            data_dir,
+            config,
+            db,
+        })
+    }
+
+    /// The DB of the versions before the schema migration
+    #[must_use]
+    pub fn old_db(&self) -> PathBuf {
+        self.data_dir.join("data.sqlite")
+    }
+
+    /// The DB of the default profile in the data directory
+    #[must_use]
+    pub fn original_db(&self) -> PathBuf {
+        self.data_dir.join(DB_NAME)
+    }
+
+    /// Loads the config of the data directory with the DB override applied
+    pub fn root_config(&self) -> Result<Config> {
+        let mut config = Config::load(&self.config, &self.data_dir)?;
+        config.db_override.clone_from(&self.db);
+
+        Ok(config)
+    }
+}
+
+/// An empty variable counts as not set
+fn env_path(name: &str) -> Option<PathBuf> {
+    env::var_os(name)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
 #[derive(Serialize, Deserialize)]
 struct LocationInfo {
     location: String,
@@ -15,28 +110,75 @@ struct BackupPaths {
     locations: Vec<String>,
 }
 
-#[derive(Serialize, Deserialize)]
+/// A named set of books. The profile directory holds its own DB and a rex.json with the backup
+/// paths and the theme of the profile
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Profile {
+    pub name: String,
+    pub location: PathBuf,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Config {
+    /// Path of the rex.json file
     #[serde(skip)]
     pub location: PathBuf,
+    /// Directory of the original DB
+    #[serde(skip)]
+    pub data_dir: PathBuf,
+    /// DB file set from the command line or the environment. Replaces the DB of the default
+    /// profile for this run only
+    #[serde(skip)]
+    pub db_override: Option<PathBuf>,
     pub backup_db_path: Option<Vec<PathBuf>>,
     pub new_location: Option<PathBuf>,
     pub theme_index: Option<usize>,
+    /// Token the API server requires in every request
+    pub api_token: Option<String>,
+    /// How many timestamped backups are kept in each backup path
+    #[serde(default)]
+    pub backup_retention: Option<Retention>,
+    /// When backups are made while the TUI is running
+    #[serde(default)]
+    pub auto_backup: Option<AutoBackup>,
+    /// Profiles other than the default one. Only used in the config of the app data directory
+    #[serde(default)]
+    pub profiles: Vec<Profile>,
+    /// The profile that was opened last. None for the default profile
+    #[serde(default)]
+    pub active_profile: Option<String>,
+    /// The profile this config belongs to. None for the default profile
+    #[serde(skip)]
+    pub profile_name: Option<String>,
 }
 
 impl Config {
-    pub fn get_config(original_db_path: &PathBuf) -> Result<Self> {
-        let mut target_dir = original_db_path.to_owned();
-        target_dir.pop();
+    pub fn get_config(original_db_path: &Path) -> Result<Self> {
+        let mut data_dir = original_db_path.to_owned();
+        data_dir.pop();
+
+        Config::load(&data_dir.join("rex.json"), &data_dir)
+    }
 
-        target_dir.push("rex.json");
+    /// Loads the config file at `config_path` for the DB in `data_dir`. The file does not
+    /// need to exist yet
+    pub fn load(config_path: &Path, data_dir: &Path) -> Result<Self> {
+        let target_dir = config_path.to_owned();
 
         if !target_dir.exists() {
             return Ok(Config {
                 backup_db_path: None,
                 new_location: None,
                 location: target_dir,
+                data_dir: data_dir.to_owned(),
+                db_override: None,
                 theme_index: Some(0),
+                api_token: None,
+                backup_retention: None,
+                auto_backup: None,
+                profiles: Vec::new(),
+                active_profile: None,
+                profile_name: None,
             });
         }
 
@@ -46,6 +188,7 @@ impl Config {
         let mut config: Config = serde_json::from_str(&file_content)?;
 
         config.location = target_dir;
+        config.data_dir = data_dir.to_owned();
 
         if config.theme_index.is_none() {
             config.theme_index = Some(0);
@@ -54,8 +197,16 @@ impl Config {
         Ok(config)
     }
 
+    /// Writes the config. On Unix only the owner can read it as it holds the API token
     pub fn save_config(&self) -> Result<()> {
         let mut file = File::create(&self.location)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        }
+
         serde_json::to_writer(&mut file, self)?;
         Ok(())
     }
@@ -71,8 +222,7 @@ impl Config {
     }
 
     pub fn set_backup_db_path(&mut self, mut backup_db_path: Vec<PathBuf>) -> Result<()> {
-        let mut original_db_path = self.location.clone();
-        original_db_path.pop();
+        let original_db_path = self.data_dir.clone();
 
         if let Some(path) = self.new_location.as_ref() {
             backup_db_path.retain(|a| a != path);
@@ -91,8 +241,7 @@ impl Config {
     }
 
     pub fn set_new_location(&mut self, new_location: PathBuf) -> Result<()> {
-        let mut original_db_path = self.location.clone();
-        original_db_path.pop();
+        let original_db_path = self.data_dir.clone();
 
         if let Some(ref backups) = self.backup_db_path
             && backups.iter().any(|p| p == &new_location)
@@ -110,46 +259,159 @@ impl Config {
             ));
         }
 
-        let mut og_db_path = self.location.clone();
-        og_db_path.pop();
-
-        og_db_path.push("rex.sqlite");
+        let og_db_dir = self.data_dir.clone();
 
-        let mut new_db_path = new_location.clone();
-        new_db_path.push("rex.sqlite");
+        // An encrypted DB is moved as it is
+        let file_name = if og_db_dir.join(ENCRYPTED_DB_NAME).is_file() {
+            ENCRYPTED_DB_NAME
+        } else {
+            DB_NAME
+        };
 
-        fs::copy(og_db_path, new_db_path)?;
+        fs::copy(og_db_dir.join(file_name), new_location.join(file_name))?;
 
         self.new_location = Some(new_location);
         self.save_config()
     }
 
-    pub fn save_backup(&self, db_path: &PathBuf) {
-        let mut original_db_path = self.location.clone();
-        original_db_path.pop();
+    /// The profile to open on start. A DB set from the command line always opens in the
+    /// default profile
+    #[must_use]
+    pub fn start_profile(&self) -> &str {
+        if self.db_override.is_some() {
+            DEFAULT_PROFILE
+        } else {
+            self.active_profile()
+        }
+    }
 
-        original_db_path.push("rex.sqlite");
+    /// The profile that was opened last. Falls back to the default profile if it was removed
+    #[must_use]
+    pub fn active_profile(&self) -> &str {
+        self.active_profile
+            .as_deref()
+            .filter(|name| self.profiles.iter().any(|p| p.name == *name))
+            .unwrap_or(DEFAULT_PROFILE)
+    }
 
-        if let Some(paths) = &self.backup_db_path {
-            for path in paths {
-                let mut target_path = path.clone();
-                if !target_path.exists() {
-                    println!("Failed to find path {}", target_path.to_string_lossy());
-                    continue;
-                }
-                target_path.push("rex.sqlite");
+    /// Every profile name with the default profile first
+    #[must_use]
+    pub fn profile_names(&self) -> Vec<String> {
+        [DEFAULT_PROFILE.to_string()]
+            .into_iter()
+            .chain(self.profiles.iter().map(|p| p.name.clone()))
+            .collect()
+    }
 
-                if let Err(e) = fs::copy(db_path, &target_path) {
-                    println!(
-                        "Failed to copy DB to backup path {}. Error: {e:?}",
-                        target_path.to_string_lossy()
-                    );
-                }
+    /// Loads the config of a profile. The default profile uses this config
+    pub fn profile_config(&self, name: &str) -> Result<Config> {
+        if name == DEFAULT_PROFILE {
+            return Ok(self.clone());
+        }
+
+        let Some(profile) = self.profiles.iter().find(|p| p.name == name) else {
+            return Err(anyhow!("Profile {name} does not exist"));
+        };
+
+        fs::create_dir_all(&profile.location)?;
+
+        let mut config = Config::get_config(&profile.location.join(DB_NAME))?;
+        config.profile_name = Some(name.to_string());
+
+        Ok(config)
+    }
+
+    /// Adds a new profile with its own directory inside the profiles directory of the app data
+    pub fn add_profile(&mut self, name: &str) -> Result<()> {
+        if let Some(reason) = invalid_profile_name(name, &self.profile_names()) {
+            return Err(anyhow!(reason));
+        }
+
+        let mut location = self.data_dir.clone();
+        location.push(PROFILES_DIR);
+        location.push(name);
+
+        fs::create_dir_all(&location)?;
+
+        self.profiles.push(Profile {
+            name: name.to_string(),
+            location,
+        });
+        self.save_config()
+    }
+
+    pub fn set_active_profile(&mut self, name: &str) -> Result<()> {
+        if !self.profile_names().iter().any(|p| p == name) {
+            return Err(anyhow!("Profile {name} does not exist"));
+        }
+
+        self.active_profile = (name != DEFAULT_PROFILE).then(|| name.to_string());
+        self.save_config()
+    }
+
+    /// Returns the API token. Generates and saves a new one if there is none
+    pub fn get_api_token(&mut self) -> Result<String> {
+        if let Some(token) = &self.api_token {
+            if token.trim().is_empty() {
+                return Err(anyhow!(
+                    "api_token in {} cannot be empty. Remove it to generate a new one",
+                    self.location.display()
+                ));
             }
+
+            return Ok(token.clone());
         }
 
-        if &original_db_path != db_path
-            && let Err(e) = fs::copy(db_path, &original_db_path)
+        let token = random_token()?;
+
+        self.api_token = Some(token.clone());
+        self.save_config()?;
+
+        Ok(token)
+    }
+
+    pub fn save_backup(&self, db_path: &Path) {
+        self.write_backups(false, db_path, |target| {
+            copy_db(db_path, target)?;
+            Ok(())
+        });
+    }
+
+    /// Encrypts the DB back to its location then writes the encrypted DB to every backup path.
+    /// The plain DB never leaves the private temporary directory
+    pub fn save_encrypted_backup(&self, db: &EncryptedDb) -> Result<()> {
+        db.save()?;
+
+        self.write_backups(true, db.path(), |target| {
+            db.save_to(target)?;
+            Ok(())
+        });
+
+        Ok(())
+    }
+
+    /// Writes a new timestamped backup to every backup path and deletes the old backups the
+    /// retention does not keep. The DB is also copied to the original location if it was moved
+    fn write_backups(&self, encrypted: bool, db_path: &Path, write: impl Fn(&Path) -> Result<()>) {
+        for error in self.backup_to_paths(encrypted, &write) {
+            println!("{error}");
+        }
+
+        // A DB set for this run only is not the DB of the original location
+        if self.db_override.is_some() {
+            return;
+        }
+
+        let mut original_db_path = self.data_dir.clone();
+
+        original_db_path.push(if encrypted {
+            ENCRYPTED_DB_NAME
+        } else {
+            DB_NAME
+        });
+
+        if original_db_path != db_path
+            && let Err(e) = write(&original_db_path)
         {
             println!(
                 "Failed to copy DB to original path {}. Error: {e:?}",
@@ -157,6 +419,176 @@ impl Config {
             );
         }
     }
+
+    /// Directory of the DB in use
+    pub fn db_dir(&self) -> PathBuf {
+        let mut db_path = self.db_path();
+        db_path.pop();
+        db_path
+    }
+
+    /// The DB file in use. A DB set from the command line comes first, then the new location
+    /// and then the original location
+    pub fn db_path(&self) -> PathBuf {
+        if let Some(db_path) = &self.db_override {
+            return db_path.clone();
+        }
+
+        self.new_location
+            .as_ref()
+            .unwrap_or(&self.data_dir)
+            .join(DB_NAME)
+    }
+
+    /// Writes a new timestamped backup to every backup path and deletes the old backups the
+    /// retention does not keep. Returns what went wrong without stopping at the first error
+    pub fn backup_to_paths(
+        &self,
+        encrypted: bool,
+        write: impl Fn(&Path) -> Result<()>,
+    ) -> Vec<String> {
+        let retention = self.backup_retention.unwrap_or_default();
+        let created = Local::now().naive_local();
+        let mut errors = Vec::new();
+
+        for backup_path in self.backup_paths() {
+            if !backup_path.exists() {
+                errors.push(format!(
+                    "Failed to find path {}",
+                    backup_path.to_string_lossy()
+                ));
+                continue;
+            }
+
+            let path = self.backup_dir(backup_path);
+
+            if let Err(e) = fs::create_dir_all(&path) {
+                errors.push(format!(
+                    "Failed to create backup directory {}. Error: {e:?}",
+                    path.to_string_lossy()
+                ));
+                continue;
+            }
+
+            let target_path = match reserve_backup_path(&path, created, encrypted) {
+                Ok(target_path) => target_path,
+                Err(e) => {
+                    errors.push(format!(
+                        "Failed to create a backup in {}. Error: {e:?}",
+                        path.to_string_lossy()
+                    ));
+                    continue;
+                }
+            };
+
+            if let Err(e) = write(&target_path) {
+                let _ = fs::remove_file(&target_path);
+                errors.push(format!(
+                    "Failed to copy DB to backup path {}. Error: {e:?}",
+                    target_path.to_string_lossy()
+                ));
+                continue;
+            }
+
+            if let Err(e) = prune_backups(&path, &retention) {
+                errors.push(format!(
+                    "Failed to delete old backups in {}. Error: {e:?}",
+                    path.to_string_lossy()
+                ));
+            }
+        }
+
+        errors
+    }
+
+    /// The backup paths. A DB set from the command line is kept away from them so a throwaway
+    /// DB never adds to, prunes or replaces the real backups
+    fn backup_paths(&self) -> &[PathBuf] {
+        if self.db_override.is_some() {
+            return &[];
+        }
+
+        self.backup_db_path.as_deref().unwrap_or_default()
+    }
+
+    /// Where the backups of this profile go inside a backup path. Profiles other than the
+    /// default one get their own directory so profiles sharing a backup path never list or
+    /// prune each other's backups
+    fn backup_dir(&self, backup_path: &Path) -> PathBuf {
+        match &self.profile_name {
+            Some(name) => backup_path.join(PROFILES_DIR).join(name),
+            None => backup_path.to_path_buf(),
+        }
+    }
+
+    /// Every timestamped backup in the backup paths and the safety copies made next to the DB
+    /// before a restore, newest first
+    pub fn backups(&self) -> Vec<BackupFile> {
+        let db_dir = self.db_dir();
+
+        let mut backups = self
+            .backup_paths()
+            .iter()
+            .map(|path| self.backup_dir(path))
+            .chain([db_dir])
+            .filter_map(|path| list_backups(&path).ok())
+            .flatten()
+            .collect::<Vec<BackupFile>>();
+
+        backups.sort_by_key(|backup| Reverse((backup.created, backup.sequence)));
+
+        backups
+    }
+
+    /// Deletes `file_name` from every backup path and from the original location
+    pub fn remove_backups(&self, file_name: &str) {
+        // A DB set for this run only is not the DB of the original location
+        if self.db_override.is_some() {
+            return;
+        }
+
+        let original_db_path = self.data_dir.clone();
+
+        let paths = self.backup_paths().iter().map(|path| self.backup_dir(path));
+
+        for path in paths.chain([original_db_path]) {
+            let target_path = path.join(file_name);
+
+            if target_path.is_file()
+                && let Err(e) = fs::remove_file(&target_path)
+            {
+                println!(
+                    "Failed to delete {}. Error: {e:?}",
+                    target_path.to_string_lossy()
+                );
+            }
+        }
+    }
+}
+
+/// Why the name cannot be used for a new profile. None if it can. The name is also the
+/// directory name of the profile
+pub fn invalid_profile_name(name: &str, existing: &[String]) -> Option<&'static str> {
+    if name.trim().is_empty() {
+        return Some("Profile name cannot be empty");
+    }
+
+    if name.trim() != name {
+        return Some("Profile name cannot start or end with a space");
+    }
+
+    if !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+    {
+        return Some("Only letters, numbers, spaces, - and _ can be used");
+    }
+
+    if existing.iter().any(|p| p.eq_ignore_ascii_case(name)) {
+        return Some("Cannot use existing profile name");
+    }
+
+    None
 }
 
 pub fn migrate_config(config_path: &PathBuf) -> Result<()> {
@@ -164,7 +596,15 @@ pub fn migrate_config(config_path: &PathBuf) -> Result<()> {
         backup_db_path: None,
         new_location: None,
         location: PathBuf::new(),
+        data_dir: PathBuf::new(),
+        db_override: None,
         theme_index: Some(0),
+        api_token: None,
+        backup_retention: None,
+        auto_backup: None,
+        profiles: Vec::new(),
+        active_profile: None,
+        profile_name: None,
     };
 
     let mut backup_path = config_path.to_owned();
@@ -37,6 +37,7 @@ pub fn add_tx_keys(handler: &mut InputKeyHandler) -> Result<Option<HandlingOutpu
                 KeyCode::Tab => handler.do_autofill(),
                 _ => match handler.add_tx_tab {
                     TxTab::Date => handler.handle_date(),
+                    TxTab::Time => handler.handle_time(),
                     TxTab::Details => handler.handle_details(),
                     TxTab::FromMethod | TxTab::ToMethod => handler.handle_tx_method()?,
                     TxTab::Amount => handler.handle_amount()?,
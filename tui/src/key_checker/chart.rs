@@ -17,9 +17,12 @@ pub fn chart_keys(handler: &mut InputKeyHandler) -> Result<Option<HandlingOutput
             KeyCode::Char('j') => handler.do_config_popup(),
             KeyCode::Char('r') => handler.do_chart_hidden_mode(),
             KeyCode::Char('R') => handler.do_chart_lgeneds(),
+            KeyCode::Char('p') => handler.do_chart_forecast()?,
+            KeyCode::Char('b') => handler.do_chart_type(),
             KeyCode::Char('w') => handler.go_search(),
             KeyCode::Char('y') => handler.go_activity(),
             KeyCode::Char('t') => handler.next_theme()?,
+            KeyCode::Char('c') => handler.do_custom_range_popup(),
             KeyCode::Right => handler.handle_right_arrow()?,
             KeyCode::Left => handler.handle_left_arrow()?,
             KeyCode::Up => handler.handle_up_arrow(),
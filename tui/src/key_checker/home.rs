@@ -23,6 +23,8 @@ pub fn home_keys(handler: &mut InputKeyHandler) -> Result<Option<HandlingOutput>
             KeyCode::Char('.') => handler.switch_tx_position_down()?,
             KeyCode::Char('v') => handler.show_home_tx_details(),
             KeyCode::Char('t') => handler.next_theme()?,
+            KeyCode::Char('p') => handler.next_home_mode()?,
+            KeyCode::Char('c') => handler.do_custom_range_popup(),
             KeyCode::Right => handler.handle_right_arrow()?,
             KeyCode::Left => handler.handle_left_arrow()?,
             KeyCode::Up => handler.handle_up_arrow(),
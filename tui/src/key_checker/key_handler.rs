@@ -1,22 +1,32 @@
 use anyhow::{Result, anyhow};
-use crossterm::event::{KeyCode, KeyEvent};
+use chrono::{Local, NaiveDate};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use rex_app::backup::{OpenedBackup, save_safety_copy};
 use rex_app::conn::{DbConn, FetchNature};
-use rex_app::ui_helper::{DateType, StepType};
-use rex_app::views::{ActivityView, ChartView, FullSummary, SearchView, SummaryView, TxViewGroup};
+use rex_app::crypto::EncryptedDb;
+use rex_app::ui_helper::{DateStep, DateType, StepType};
+use rex_app::views::{
+    ActivityView, ChartView, FORECAST_MONTHS, ForecastView, FullSummary, REPORT_TOTAL_ROWS,
+    ReportFormat, SearchView, SummaryReport, SummaryView, TxViewGroup,
+};
+use rfd::FileDialog;
 use std::collections::HashMap;
 use std::fmt::Write;
+use std::fs;
+use std::path::Path;
 
 use crate::config::Config;
 use crate::outputs::HandlingOutput;
 use crate::outputs::TxType;
 use crate::page_handler::{
-    ActivityTab, ChartTab, CurrentUi, HomeTab, IndexedData, LogType, MONTHS, SortingType,
-    SummaryTab, TableData, TxTab,
+    ActivityTab, BackupWorker, ChartTab, ChartType, CompareData, CurrentUi, HomeTab, IndexedData,
+    LogType, MONTHS, PeriodData, ReportData, SortingType, SummaryTab, TableData, TxTab,
+    chart_tag_activation,
 };
 use crate::pages::{
-    ACTIVITY_TABLE_ID, ChoicePopupState, ConfigChoices, DeletionChoices, HOME_TABLE_ID,
-    InfoPopupState, MovementDirection, NewPathChoices, PopupType, SEARCH_TABLE_ID,
-    SUMMARY_TABLE_ID,
+    ACTIVITY_TABLE_ID, ChoicePopupState, ConfigChoices, DeletionChoices, HOME_TABLE_ID, InfoPopup,
+    InfoPopupState, InputPopupState, MovementDirection, NewPathChoices, PopupType, SEARCH_TABLE_ID,
+    SUMMARY_TABLE_ID, backups_text, restore_preview_text,
 };
 use crate::theme::Theme;
 use crate::tx_handler::TxData;
@@ -36,6 +46,7 @@ pub struct InputKeyHandler<'a> {
     home_tab: &'a mut HomeTab,
     add_tx_data: &'a mut TxData,
     chart_view: &'a mut ChartView,
+    chart_forecast: &'a mut Option<ForecastView>,
     summary_view: &'a mut SummaryView,
     full_summary: &'a mut FullSummary,
     home_table: &'a mut TableData,
@@ -43,14 +54,20 @@ pub struct InputKeyHandler<'a> {
     summary_table: &'a mut TableData,
     home_months: &'a mut IndexedData,
     home_years: &'a mut IndexedData,
+    home_modes: &'a mut IndexedData,
+    home_period: &'a mut PeriodData,
     chart_months: &'a mut IndexedData,
     chart_years: &'a mut IndexedData,
     chart_modes: &'a mut IndexedData,
+    chart_period: &'a mut PeriodData,
     chart_tx_methods: &'a mut IndexedData,
     summary_months: &'a mut IndexedData,
     summary_years: &'a mut IndexedData,
     summary_modes: &'a mut IndexedData,
+    summary_period: &'a mut PeriodData,
     summary_sort: &'a mut SortingType,
+    summary_report: &'a mut ReportData,
+    summary_compare: &'a mut CompareData,
     search_data: &'a mut TxData,
     search_date_type: &'a mut DateType,
     pub search_tab: &'a mut TxTab,
@@ -63,12 +80,22 @@ pub struct InputKeyHandler<'a> {
     activity_table: &'a mut TableData,
     chart_hidden_mode: &'a mut bool,
     chart_hidden_legends: &'a mut bool,
+    chart_type: &'a mut ChartType,
+    chart_tags: &'a mut IndexedData,
+    chart_activated_tags: &'a mut HashMap<String, bool>,
+    chart_forecast_months: &'a mut Option<u32>,
     summary_hidden_mode: &'a mut bool,
     chart_activated_methods: &'a mut HashMap<String, bool>,
     lerp_state: &'a mut LerpState,
     config: &'a mut Config,
     theme: &'a mut Theme,
     conn: &'a mut DbConn,
+    /// The DB file the connection writes to. None in an ephemeral session
+    live_db: Option<&'a Path>,
+    encrypted_db: Option<&'a EncryptedDb>,
+    backup_worker: Option<&'a BackupWorker>,
+    /// Config of the app data directory that lists the profiles
+    root_config: &'a Config,
 }
 
 impl<'a> InputKeyHandler<'a> {
@@ -83,6 +110,7 @@ impl<'a> InputKeyHandler<'a> {
         home_tab: &'a mut HomeTab,
         add_tx_data: &'a mut TxData,
         chart_view: &'a mut ChartView,
+        chart_forecast: &'a mut Option<ForecastView>,
         summary_view: &'a mut SummaryView,
         full_summary: &'a mut FullSummary,
         home_table: &'a mut TableData,
@@ -90,14 +118,20 @@ impl<'a> InputKeyHandler<'a> {
         summary_table: &'a mut TableData,
         home_months: &'a mut IndexedData,
         home_years: &'a mut IndexedData,
+        home_modes: &'a mut IndexedData,
+        home_period: &'a mut PeriodData,
         chart_months: &'a mut IndexedData,
         chart_years: &'a mut IndexedData,
         chart_modes: &'a mut IndexedData,
+        chart_period: &'a mut PeriodData,
         chart_tx_methods: &'a mut IndexedData,
         summary_months: &'a mut IndexedData,
         summary_years: &'a mut IndexedData,
         summary_modes: &'a mut IndexedData,
+        summary_period: &'a mut PeriodData,
         summary_sort: &'a mut SortingType,
+        summary_report: &'a mut ReportData,
+        summary_compare: &'a mut CompareData,
         search_data: &'a mut TxData,
         search_date_type: &'a mut DateType,
         search_tab: &'a mut TxTab,
@@ -110,12 +144,20 @@ impl<'a> InputKeyHandler<'a> {
         activity_table: &'a mut TableData,
         chart_hidden_mode: &'a mut bool,
         chart_hidden_legends: &'a mut bool,
+        chart_type: &'a mut ChartType,
+        chart_tags: &'a mut IndexedData,
+        chart_activated_tags: &'a mut HashMap<String, bool>,
+        chart_forecast_months: &'a mut Option<u32>,
         summary_hidden_mode: &'a mut bool,
         chart_activated_methods: &'a mut HashMap<String, bool>,
         lerp_state: &'a mut LerpState,
         config: &'a mut Config,
         theme: &'a mut Theme,
         conn: &'a mut DbConn,
+        live_db: Option<&'a Path>,
+        encrypted_db: Option<&'a EncryptedDb>,
+        backup_worker: Option<&'a BackupWorker>,
+        root_config: &'a Config,
     ) -> InputKeyHandler<'a> {
         InputKeyHandler {
             key,
@@ -128,6 +170,7 @@ impl<'a> InputKeyHandler<'a> {
             home_tab,
             add_tx_data,
             chart_view,
+            chart_forecast,
             summary_view,
             full_summary,
             home_table,
@@ -135,14 +178,20 @@ impl<'a> InputKeyHandler<'a> {
             summary_table,
             home_months,
             home_years,
+            home_modes,
+            home_period,
             chart_months,
             chart_years,
             chart_modes,
+            chart_period,
             chart_tx_methods,
             summary_months,
             summary_years,
             summary_modes,
+            summary_period,
             summary_sort,
+            summary_report,
+            summary_compare,
             search_data,
             search_date_type,
             search_tab,
@@ -155,12 +204,20 @@ impl<'a> InputKeyHandler<'a> {
             activity_table,
             chart_hidden_mode,
             chart_hidden_legends,
+            chart_type,
+            chart_tags,
+            chart_activated_tags,
+            chart_forecast_months,
             summary_hidden_mode,
             chart_activated_methods,
             lerp_state,
             config,
             theme,
             conn,
+            live_db,
+            encrypted_db,
+            backup_worker,
+            root_config,
         }
     }
 
@@ -294,6 +351,29 @@ impl<'a> InputKeyHandler<'a> {
         *self.chart_hidden_legends = !*self.chart_hidden_legends;
     }
 
+    /// Cycles through the balance, cash flow and tag trend chart
+    pub fn do_chart_type(&mut self) {
+        *self.chart_type = self.chart_type.next_type();
+        self.lerp_state.clear();
+    }
+
+    /// Cycles the chart balance forecast through off and the supported months
+    pub fn do_chart_forecast(&mut self) -> Result<()> {
+        *self.chart_forecast_months = match *self.chart_forecast_months {
+            None => Some(FORECAST_MONTHS[0]),
+            Some(months) => FORECAST_MONTHS
+                .iter()
+                .position(|m| *m == months)
+                .and_then(|index| FORECAST_MONTHS.get(index + 1))
+                .copied(),
+        };
+
+        self.reload_chart_forecast()?;
+        self.lerp_state.clear();
+
+        Ok(())
+    }
+
     /// Hides summary top widgets
     pub fn do_summary_hidden_mode(&mut self) {
         *self.summary_hidden_mode = !*self.summary_hidden_mode;
@@ -307,6 +387,18 @@ impl<'a> InputKeyHandler<'a> {
         }
     }
 
+    /// Cycles through the Home page modes
+    pub fn next_home_mode(&mut self) -> Result<()> {
+        self.home_modes.next();
+        *self.home_tab = HomeTab::Months;
+        self.reload_home_table(true)
+    }
+
+    /// Opens the input popup to select a custom date range for the current page
+    pub fn do_custom_range_popup(&mut self) {
+        *self.popup_status = PopupType::new_input(InputPopupState::CustomRange);
+    }
+
     /// Handles Enter key press if there is a new update and the update popup is on
     pub fn handle_update_popup(&mut self) -> Result<(), HandlingOutput> {
         *self.popup_status = PopupType::Nothing;
@@ -362,6 +454,7 @@ impl<'a> InputKeyHandler<'a> {
                 self.go_home_reset();
                 // We just added a new TX, select the month tab again + reload the data of balance and table widgets to get updated data
                 *self.home_tab = HomeTab::Months;
+                self.reload_years();
                 self.reload_home_table(true)?;
                 self.reload_chart_data()?;
                 self.reload_summary()?;
@@ -434,6 +527,7 @@ impl<'a> InputKeyHandler<'a> {
                     KeyCode::Char('4') => *self.add_tx_tab = TxTab::FromMethod,
                     KeyCode::Char('5') => *self.add_tx_tab = TxTab::Amount,
                     KeyCode::Char('6') => *self.add_tx_tab = TxTab::Tags,
+                    KeyCode::Char('7') => *self.add_tx_tab = TxTab::Time,
                     _ => {}
                 },
                 TxType::Transfer => match self.key.code {
@@ -444,6 +538,7 @@ impl<'a> InputKeyHandler<'a> {
                     KeyCode::Char('5') => *self.add_tx_tab = TxTab::ToMethod,
                     KeyCode::Char('6') => *self.add_tx_tab = TxTab::Amount,
                     KeyCode::Char('7') => *self.add_tx_tab = TxTab::Tags,
+                    KeyCode::Char('8') => *self.add_tx_tab = TxTab::Time,
                     _ => {}
                 },
             },
@@ -477,18 +572,31 @@ impl<'a> InputKeyHandler<'a> {
     /// Handles left arrow key press for multiple pages
     pub fn handle_left_arrow(&mut self) -> Result<()> {
         match self.page {
-            CurrentUi::Home => match self.home_tab {
-                HomeTab::Months => {
-                    self.home_months.previous();
-                    self.reload_home_table(true)?;
-                }
-                HomeTab::Years => {
-                    self.home_years.previous_yearly();
-                    self.home_months.set_index_zero();
-                    self.reload_home_table(true)?;
+            CurrentUi::Home => {
+                let period_mode = PeriodData::is_period_mode(self.home_modes.get_selected_value());
+
+                match self.home_tab {
+                    HomeTab::Months if period_mode => {
+                        self.home_modes.previous();
+                        self.reload_home_table(true)?;
+                    }
+                    HomeTab::Years if period_mode => {
+                        self.home_period
+                            .previous(self.home_modes.get_selected_value());
+                        self.reload_home_table(true)?;
+                    }
+                    HomeTab::Months => {
+                        self.home_months.previous();
+                        self.reload_home_table(true)?;
+                    }
+                    HomeTab::Years => {
+                        self.home_years.previous_yearly();
+                        self.home_months.set_index_zero();
+                        self.reload_home_table(true)?;
+                    }
+                    HomeTab::Table => {}
                 }
-                HomeTab::Table => {}
-            },
+            }
             CurrentUi::AddTx => self.add_tx_data.move_index_left(self.add_tx_tab),
             CurrentUi::Search => self.search_data.move_index_left(self.search_tab),
             CurrentUi::Chart => {
@@ -499,6 +607,16 @@ impl<'a> InputKeyHandler<'a> {
                             self.lerp_state.clear();
                             self.reload_chart_data()?;
                         }
+                        ChartTab::Years
+                            if PeriodData::is_period_mode(
+                                self.chart_modes.get_selected_value(),
+                            ) =>
+                        {
+                            self.chart_period
+                                .previous(self.chart_modes.get_selected_value());
+                            self.lerp_state.clear();
+                            self.reload_chart_data()?;
+                        }
                         ChartTab::Years => {
                             self.chart_years.previous_yearly();
                             self.lerp_state.clear();
@@ -511,7 +629,13 @@ impl<'a> InputKeyHandler<'a> {
                             self.reload_chart_data()?;
                         }
                         ChartTab::TxMethods => {
-                            self.chart_tx_methods.previous();
+                            if let ChartType::TagTrend = self.chart_type {
+                                if !self.chart_tags.titles.is_empty() {
+                                    self.chart_tags.previous();
+                                }
+                            } else {
+                                self.chart_tx_methods.previous();
+                            }
                         }
                     }
                 }
@@ -523,6 +647,15 @@ impl<'a> InputKeyHandler<'a> {
                             self.summary_modes.previous();
                             self.reload_summary()?;
                         }
+                        SummaryTab::Years
+                            if PeriodData::is_period_mode(
+                                self.summary_modes.get_selected_value(),
+                            ) =>
+                        {
+                            self.summary_period
+                                .previous(self.summary_modes.get_selected_value());
+                            self.reload_summary()?;
+                        }
                         SummaryTab::Years => {
                             self.summary_months.set_index_zero();
                             self.summary_years.previous_yearly();
@@ -532,8 +665,14 @@ impl<'a> InputKeyHandler<'a> {
                             self.summary_months.previous();
                             self.reload_summary()?;
                         }
-                        SummaryTab::Table => {}
+                        SummaryTab::Table => {
+                            if self.is_summary_report() {
+                                self.summary_report.scroll_left();
+                            }
+                        }
                     }
+                } else if self.is_summary_report() {
+                    self.summary_report.scroll_left();
                 }
             }
             CurrentUi::Activity => match self.activity_tab {
@@ -557,18 +696,30 @@ impl<'a> InputKeyHandler<'a> {
     /// Handles right arrow key press for multiple pages
     pub fn handle_right_arrow(&mut self) -> Result<()> {
         match self.page {
-            CurrentUi::Home => match self.home_tab {
-                HomeTab::Months => {
-                    self.home_months.next();
-                    self.reload_home_table(true)?;
-                }
-                HomeTab::Years => {
-                    self.home_years.next_yearly();
-                    self.home_months.set_index_zero();
-                    self.reload_home_table(true)?;
+            CurrentUi::Home => {
+                let period_mode = PeriodData::is_period_mode(self.home_modes.get_selected_value());
+
+                match self.home_tab {
+                    HomeTab::Months if period_mode => {
+                        self.home_modes.next();
+                        self.reload_home_table(true)?;
+                    }
+                    HomeTab::Years if period_mode => {
+                        self.home_period.next(self.home_modes.get_selected_value());
+                        self.reload_home_table(true)?;
+                    }
+                    HomeTab::Months => {
+                        self.home_months.next();
+                        self.reload_home_table(true)?;
+                    }
+                    HomeTab::Years => {
+                        self.home_years.next_yearly();
+                        self.home_months.set_index_zero();
+                        self.reload_home_table(true)?;
+                    }
+                    HomeTab::Table => {}
                 }
-                HomeTab::Table => {}
-            },
+            }
             CurrentUi::AddTx => self.add_tx_data.move_index_right(self.add_tx_tab),
             CurrentUi::Search => {
                 self.search_data.move_index_right(self.search_tab);
@@ -582,6 +733,16 @@ impl<'a> InputKeyHandler<'a> {
                             self.chart_modes.next();
                             self.reload_chart_data()?;
                         }
+                        ChartTab::Years
+                            if PeriodData::is_period_mode(
+                                self.chart_modes.get_selected_value(),
+                            ) =>
+                        {
+                            self.lerp_state.clear();
+                            self.chart_period
+                                .next(self.chart_modes.get_selected_value());
+                            self.reload_chart_data()?;
+                        }
                         ChartTab::Years => {
                             self.lerp_state.clear();
                             self.chart_years.next_yearly();
@@ -594,7 +755,13 @@ impl<'a> InputKeyHandler<'a> {
                             self.reload_chart_data()?;
                         }
                         ChartTab::TxMethods => {
-                            self.chart_tx_methods.next();
+                            if let ChartType::TagTrend = self.chart_type {
+                                if !self.chart_tags.titles.is_empty() {
+                                    self.chart_tags.next();
+                                }
+                            } else {
+                                self.chart_tx_methods.next();
+                            }
                         }
                     }
                 }
@@ -606,6 +773,15 @@ impl<'a> InputKeyHandler<'a> {
                             self.summary_modes.next();
                             self.reload_summary()?;
                         }
+                        SummaryTab::Years
+                            if PeriodData::is_period_mode(
+                                self.summary_modes.get_selected_value(),
+                            ) =>
+                        {
+                            self.summary_period
+                                .next(self.summary_modes.get_selected_value());
+                            self.reload_summary()?;
+                        }
                         SummaryTab::Years => {
                             self.summary_months.set_index_zero();
                             self.summary_years.next_yearly();
@@ -615,10 +791,16 @@ impl<'a> InputKeyHandler<'a> {
                             self.summary_months.next();
                             self.reload_summary()?;
                         }
-                        SummaryTab::Table => {}
+                        SummaryTab::Table => {
+                            if self.is_summary_report() {
+                                self.summary_report.scroll_right();
+                            }
+                        }
                     }
 
                     self.lerp_state.clear_lerp(SUMMARY_TABLE_ID);
+                } else if self.is_summary_report() {
+                    self.summary_report.scroll_right();
                 }
             }
             CurrentUi::Activity => {
@@ -682,6 +864,13 @@ impl<'a> InputKeyHandler<'a> {
         }
     }
 
+    /// Checks and verifies time field
+    pub fn handle_time(&mut self) {
+        if let CurrentUi::AddTx = self.page {
+            self.check_add_tx_time();
+        }
+    }
+
     /// Checks and verifies details field
     pub fn handle_details(&mut self) {
         match self.page {
@@ -784,18 +973,169 @@ impl<'a> InputKeyHandler<'a> {
 
     /// Cycles through tag, income, expense table sorting on summary page
     pub fn change_summary_sort(&mut self) {
+        // The report and comparison rows are in a fixed order
+        if self.is_summary_report() || self.summary_compare.base.is_some() {
+            return;
+        }
+
         *self.summary_sort = self.summary_sort.next_type();
         let summary_data = self.summary_table.items.clone();
         let sorted_data = sort_table_data(summary_data, self.summary_sort);
         *self.summary_table = TableData::new(sorted_data);
     }
 
+    /// Turns the month by month yearly report on the Summary page on or off.
+    /// The report is only available in the Yearly mode so the mode is switched to it
+    pub fn do_summary_report(&mut self) -> Result<()> {
+        self.summary_report.enabled = !self.summary_report.enabled;
+        self.summary_report.column_offset = 0;
+
+        if self.summary_report.enabled {
+            self.summary_modes.select_title("Yearly");
+            self.summary_compare.base = None;
+        }
+
+        if let SummaryTab::Months = self.summary_tab {
+            *self.summary_tab = SummaryTab::ModeSelection;
+        }
+
+        self.reload_summary()?;
+        self.lerp_state.clear();
+
+        Ok(())
+    }
+
+    /// Pins the selected Summary period to compare other periods with it.
+    /// Turns the comparison off if a period is already pinned
+    pub fn do_summary_compare(&mut self) -> Result<()> {
+        if self.summary_compare.base.is_some() {
+            self.summary_compare.base = None;
+        } else {
+            self.summary_compare.base = Some(self.summary_selected_period());
+            self.summary_report.enabled = false;
+        }
+
+        self.reload_summary()?;
+        self.lerp_state.clear();
+
+        Ok(())
+    }
+
+    /// Date, fetch nature and name of the period selected on the Summary page
+    fn summary_selected_period(&self) -> (NaiveDate, FetchNature, String) {
+        let mode = self.summary_modes.get_selected_value();
+        let nature = self.summary_period.fetch_nature(mode);
+
+        if PeriodData::is_period_mode(mode) {
+            let name = self
+                .summary_period
+                .get_tab(mode)
+                .get_selected_value()
+                .to_string();
+            return (self.summary_period.date, nature, name);
+        }
+
+        let year = self.summary_years.get_selected_value();
+        let month = self.summary_months.get_selected_value();
+
+        let date = NaiveDate::from_ymd_opt(
+            year.parse().unwrap(),
+            self.summary_months.index as u32 + 1,
+            1,
+        )
+        .unwrap();
+
+        let name = match nature {
+            FetchNature::Monthly => format!("{month} {year}"),
+            FetchNature::Yearly => year.to_string(),
+            _ => mode.to_string(),
+        };
+
+        (date, nature, name)
+    }
+
+    /// Asks for a file location and saves the yearly report there as CSV.
+    /// Saves the summary of the selected period as Markdown or HTML if the report is not shown
+    pub fn export_summary_report(&mut self) -> Result<()> {
+        if !self.is_summary_report() {
+            return self.export_summary();
+        }
+
+        let Some(report) = &self.summary_report.report else {
+            return Ok(());
+        };
+
+        let Some(path) = FileDialog::new()
+            .set_directory("~/")
+            .set_file_name(format!("rex_report_{}.csv", report.year()))
+            .save_file()
+        else {
+            return Ok(());
+        };
+
+        fs::write(&path, report.to_csv())?;
+
+        let state = InfoPopupState::Info(format!("Report saved to {}", path.display()));
+        *self.popup_status = PopupType::new_info(state);
+
+        Ok(())
+    }
+
+    /// Asks for a file location and saves the selected summary there. The format is picked
+    /// from the file extension
+    fn export_summary(&mut self) -> Result<()> {
+        // The comparison table rows are not summary tags
+        if self.summary_compare.base.is_some() {
+            return Ok(());
+        }
+
+        let (_, nature, name) = self.summary_selected_period();
+        let file_name = name.replace([' ', ':'], "_");
+
+        let Some(path) = FileDialog::new()
+            .set_directory("~/")
+            .set_file_name(format!("rex_summary_{file_name}.md"))
+            .add_filter("Markdown", &["md"])
+            .add_filter("HTML", &["html", "htm"])
+            .save_file()
+        else {
+            return Ok(());
+        };
+
+        let format = ReportFormat::from_extension(
+            path.extension()
+                .and_then(|extension| extension.to_str())
+                .unwrap_or_default(),
+        );
+
+        let title = format!("Rex Summary - {name}");
+        let report =
+            SummaryReport::new(&title, nature, self.full_summary, &self.summary_table.items);
+
+        fs::write(&path, report.render(format))?;
+
+        let state = InfoPopupState::Info(format!("Summary saved to {}", path.display()));
+        *self.popup_status = PopupType::new_info(state);
+
+        Ok(())
+    }
+
+    /// Whether the Summary page is showing the yearly report
+    fn is_summary_report(&self) -> bool {
+        self.summary_report.enabled && self.summary_modes.index == 1
+    }
+
     /// If Enter is pressed on Summary page while a tag is selected
     /// go to search page and search for it
     pub fn search_tag(&mut self) -> Result<()> {
         if let SummaryTab::Table = self.summary_tab
             && let Some(index) = self.summary_table.state.selected()
         {
+            // The first report rows are totals and not tags
+            if self.is_summary_report() && index < REPORT_TOTAL_ROWS {
+                return Ok(());
+            }
+
             let tag_name = &self.summary_table.items[index][0];
             let search_param = TxData::custom("", "", "", "", "", "", tag_name, 0);
             *self.search_data = search_param;
@@ -806,13 +1146,13 @@ impl<'a> InputKeyHandler<'a> {
         Ok(())
     }
 
-    pub fn handle_choice_popup_selection(&mut self) -> Result<()> {
+    pub fn handle_choice_popup_selection(&mut self) -> Result<Option<HandlingOutput>> {
         let PopupType::Choice(choice) = self.popup_status else {
             self.do_empty_popup();
-            return Ok(());
+            return Ok(None);
         };
 
-        match choice.showing {
+        match &choice.showing {
             ChoicePopupState::Delete => {
                 let Some(choice) = self.popup_status.get_deletion_choice() else {
                     return Err(anyhow!("Popup choice should not have been None"));
@@ -845,6 +1185,18 @@ impl<'a> InputKeyHandler<'a> {
                     ConfigChoices::BackupPaths => {
                         *self.popup_status = PopupType::new_path(false, self.config);
                     }
+                    ConfigChoices::ListBackups => {
+                        let status = self.backup_worker.and_then(BackupWorker::status);
+                        let state = InfoPopupState::Backups(backups_text(self.config, status));
+                        *self.popup_status = PopupType::new_info(state);
+                    }
+                    ConfigChoices::RestoreBackup => {
+                        *self.popup_status = PopupType::new_choice_backups(
+                            self.config,
+                            self.encrypted_db,
+                            self.theme,
+                        )?;
+                    }
                     ConfigChoices::NewLocation => {
                         *self.popup_status = PopupType::new_path(true, self.config);
                     }
@@ -852,7 +1204,11 @@ impl<'a> InputKeyHandler<'a> {
                         *self.popup_status = PopupType::new_choice_methods(self.conn, self.theme)?;
                     }
                     ConfigChoices::AddNewTxMethod => {
-                        *self.popup_status = PopupType::new_input(None);
+                        *self.popup_status = PopupType::new_input(InputPopupState::NewMethod);
+                    }
+                    ConfigChoices::SwitchProfile => {
+                        *self.popup_status =
+                            PopupType::new_choice_profiles(self.root_config, self.theme);
                     }
                 }
             }
@@ -861,10 +1217,90 @@ impl<'a> InputKeyHandler<'a> {
                     return Err(anyhow!("Popup choice should not have been None"));
                 };
 
-                *self.popup_status = PopupType::new_input(Some(choice));
+                *self.popup_status = PopupType::new_input(InputPopupState::RenameMethod(choice));
+            }
+            ChoicePopupState::Backups(_) => {
+                let Some(backup) = self.popup_status.get_choice_backup() else {
+                    return Err(anyhow!("Popup choice should not have been None"));
+                };
+
+                let opened = OpenedBackup::open(&backup, self.encrypted_db)?;
+                let summary = opened.summary()?;
+                let diff = opened.diff(self.conn)?;
+
+                let preview =
+                    restore_preview_text(&backup, &summary, &diff, self.live_db.is_some());
+
+                *self.popup_status =
+                    PopupType::new_info(InfoPopupState::RestorePreview(backup, preview));
+            }
+            ChoicePopupState::Profiles(_) => {
+                let Some(profile) = self.popup_status.get_choice_profile() else {
+                    let state = InputPopupState::NewProfile(self.root_config.profile_names());
+                    *self.popup_status = PopupType::new_input(state);
+                    return Ok(None);
+                };
+
+                if profile == self.root_config.active_profile() {
+                    return Ok(None);
+                }
+
+                return Ok(Some(HandlingOutput::SwitchProfile(profile)));
             }
         }
 
+        Ok(None)
+    }
+
+    /// Restores the backup of the preview popup. A copy of the current DB is saved next to it
+    /// first unless the session is ephemeral
+    pub fn handle_restore_popup(&mut self) -> Result<()> {
+        let PopupType::Info(InfoPopup {
+            showing: InfoPopupState::RestorePreview(backup, _),
+            ..
+        }) = self.popup_status
+        else {
+            return Ok(());
+        };
+
+        let backup = backup.clone();
+
+        let safety_copy = match self.live_db {
+            Some(db_path) => Some(save_safety_copy(
+                db_path,
+                &self.config.db_dir(),
+                self.encrypted_db,
+                Local::now().naive_local(),
+            )?),
+            None => None,
+        };
+
+        let opened = OpenedBackup::open(&backup, self.encrypted_db)?;
+        self.conn.restore_from(opened.db_path())?;
+
+        self.go_home_reset();
+        *self.home_tab = HomeTab::Months;
+        self.reload_years();
+        self.reload_home_table(true)?;
+        self.reload_chart_data()?;
+        self.reload_summary()?;
+        self.reset_search_data();
+        self.reload_activity_table()?;
+
+        let mut message = format!(
+            "Restored the backup from {}",
+            backup.created.format("%Y-%m-%d %H:%M:%S")
+        );
+
+        if let Some(path) = safety_copy {
+            message.push_str(&format!(
+                "\n\nThe previous state was saved to {}",
+                path.display()
+            ));
+        }
+
+        *self.popup_status = PopupType::new_info(InfoPopupState::Info(message));
+
         Ok(())
     }
 
@@ -943,8 +1379,19 @@ impl<'a> InputKeyHandler<'a> {
         Ok(None)
     }
 
-    pub fn handle_popup_input(&mut self) -> Result<()> {
+    pub fn handle_popup_input(&mut self) -> Result<Option<HandlingOutput>> {
         match self.key.code {
+            KeyCode::Enter if self.popup_status.is_new_profile_input() => {
+                if let Some(profile) = self.popup_status.get_new_profile() {
+                    return Ok(Some(HandlingOutput::NewProfile(profile)));
+                }
+            }
+            KeyCode::Enter if self.popup_status.is_custom_range_input() => {
+                if let Some((start_date, end_date)) = self.popup_status.get_custom_range() {
+                    *self.popup_status = PopupType::Nothing;
+                    self.set_custom_range(start_date, end_date)?;
+                }
+            }
             KeyCode::Enter => {
                 let completed = self.popup_status.accept_input(self.conn)?;
 
@@ -968,6 +1415,41 @@ impl<'a> InputKeyHandler<'a> {
             _ => {}
         }
 
+        Ok(None)
+    }
+
+    /// Switches the current page to the Custom mode with the given range
+    fn set_custom_range(&mut self, start_date: NaiveDate, end_date: NaiveDate) -> Result<()> {
+        match self.page {
+            CurrentUi::Home => {
+                self.home_period.set_custom(start_date, end_date);
+                self.home_modes.select_title("Custom");
+                *self.home_tab = HomeTab::Months;
+                self.reload_home_table(true)?;
+            }
+            CurrentUi::Chart => {
+                self.chart_period.set_custom(start_date, end_date);
+                self.chart_modes.select_title("Custom");
+                self.lerp_state.clear();
+                self.reload_chart_data()?;
+
+                if let ChartTab::Months = self.chart_tab {
+                    *self.chart_tab = ChartTab::Years;
+                }
+            }
+            CurrentUi::Summary => {
+                self.summary_period.set_custom(start_date, end_date);
+                self.summary_modes.select_title("Custom");
+                self.reload_summary()?;
+                self.lerp_state.clear_lerp(SUMMARY_TABLE_ID);
+
+                if let SummaryTab::Months = self.summary_tab {
+                    *self.summary_tab = SummaryTab::Years;
+                }
+            }
+            _ => {}
+        }
+
         Ok(())
     }
 
@@ -1062,8 +1544,9 @@ impl<'a> InputKeyHandler<'a> {
         if let Some(index) = self.home_table.state.selected() {
             let selected_tx = self.home_txs.get_tx(index);
             let tx_details = selected_tx.details.clone().unwrap_or_default();
+            let tx_date = selected_tx.date.format("%Y-%m-%d %H:%M");
 
-            let status = InfoPopupState::ShowDetails(tx_details);
+            let status = InfoPopupState::ShowDetails(format!("{tx_date}\n\n{tx_details}"));
             *self.popup_status = PopupType::new_info(status);
         }
     }
@@ -1097,6 +1580,26 @@ impl<'a> InputKeyHandler<'a> {
     pub fn switch_chart_tx_method_activation(&mut self) -> Result<()> {
         if !*self.chart_hidden_mode
             && let ChartTab::TxMethods = self.chart_tab
+            && let ChartType::TagTrend = self.chart_type
+        {
+            // No tag to select in the chart period
+            if self.chart_tags.titles.is_empty() {
+                return Ok(());
+            }
+
+            let selected_tag = self.chart_tags.get_selected_value();
+
+            let activation_status =
+                self.chart_activated_tags
+                    .get_mut(selected_tag)
+                    .ok_or(anyhow!(
+                        "Tag {selected_tag} not found in the chart activated tags list"
+                    ))?;
+
+            *activation_status = !*activation_status;
+            self.lerp_state.clear();
+        } else if !*self.chart_hidden_mode
+            && let ChartTab::TxMethods = self.chart_tab
         {
             let selected_index = self.chart_tx_methods.index;
             let all_tx_methods = self.conn.get_tx_methods_cumulative();
@@ -1230,7 +1733,7 @@ impl InputKeyHandler<'_> {
                     }
                     _ => *self.summary_tab = self.summary_tab.change_tab_up_monthly(),
                 },
-                1 => match self.summary_tab {
+                1 | 3..=5 => match self.summary_tab {
                     SummaryTab::Table => {
                         if self.summary_table.state.selected() == Some(0) {
                             *self.summary_tab = self.summary_tab.change_tab_up_yearly();
@@ -1307,7 +1810,7 @@ impl InputKeyHandler<'_> {
                     }
                     _ => *self.summary_tab = self.summary_tab.change_tab_down_monthly(),
                 },
-                1 => match self.summary_tab {
+                1 | 3..=5 => match self.summary_tab {
                     SummaryTab::Table => {
                         if self.summary_table.state.selected() == Some(total_tags - 1) {
                             *self.summary_tab = self.summary_tab.change_tab_down_yearly();
@@ -1363,7 +1866,7 @@ impl InputKeyHandler<'_> {
         if !*self.chart_hidden_mode {
             match self.chart_modes.index {
                 0 => *self.chart_tab = self.chart_tab.change_tab_up_monthly(),
-                1 => *self.chart_tab = self.chart_tab.change_tab_up_yearly(),
+                1 | 3..=5 => *self.chart_tab = self.chart_tab.change_tab_up_yearly(),
                 2 => *self.chart_tab = self.chart_tab.change_tab_up_all_time(),
                 _ => {}
             }
@@ -1375,7 +1878,7 @@ impl InputKeyHandler<'_> {
         if !*self.chart_hidden_mode {
             match self.chart_modes.index {
                 0 => *self.chart_tab = self.chart_tab.change_tab_down_monthly(),
-                1 => *self.chart_tab = self.chart_tab.change_tab_down_yearly(),
+                1 | 3..=5 => *self.chart_tab = self.chart_tab.change_tab_down_yearly(),
                 2 => *self.chart_tab = self.chart_tab.change_tab_down_all_time(),
                 _ => {}
             }
@@ -1390,7 +1893,7 @@ impl InputKeyHandler<'_> {
 
                 match status {
                     Ok(data) => {
-                        *self.add_tx_tab = TxTab::Details;
+                        *self.add_tx_tab = TxTab::Time;
                         self.go_correct_index();
 
                         self.add_tx_data
@@ -1424,6 +1927,36 @@ impl InputKeyHandler<'_> {
         }
     }
 
+    /// Handle key inputs for the Time field on the Add TX page
+    fn check_add_tx_time(&mut self) {
+        match self.key.code {
+            KeyCode::Enter | KeyCode::Esc => {
+                let status = self.add_tx_data.check_time(self.conn);
+
+                match status {
+                    Ok(data) => {
+                        if self.key.code == KeyCode::Enter {
+                            *self.add_tx_tab = TxTab::Details;
+                            self.go_correct_index();
+                        } else {
+                            *self.add_tx_tab = TxTab::Nothing;
+                        }
+
+                        self.add_tx_data
+                            .add_tx_status(data.to_string(), LogType::Info);
+                    }
+                    Err(e) => {
+                        self.add_tx_data
+                            .add_tx_status(e.to_string(), LogType::Error);
+                    }
+                }
+            }
+            KeyCode::Backspace => self.add_tx_data.edit_time(None),
+            KeyCode::Char(a) => self.add_tx_data.edit_time(Some(a)),
+            _ => {}
+        }
+    }
+
     /// Handle key inputs for the Details field on the Add TX page
     fn check_add_tx_details(&mut self) {
         match self.key.code {
@@ -1903,11 +2436,18 @@ impl InputKeyHandler<'_> {
 
     /// Reload Home page's table data by fetching from the DB
     fn reload_home_table(&mut self, clear_lerp: bool) -> Result<()> {
-        *self.home_txs = self.conn.fetch_txs_with_str(
-            self.home_months.get_selected_value(),
-            self.home_years.get_selected_value(),
-            FetchNature::Monthly,
-        )?;
+        let mode = self.home_modes.get_selected_value();
+
+        *self.home_txs = if PeriodData::is_period_mode(mode) {
+            self.conn
+                .fetch_txs_with_date(self.home_period.date, self.home_period.fetch_nature(mode))?
+        } else {
+            self.conn.fetch_txs_with_str(
+                self.home_months.get_selected_value(),
+                self.home_years.get_selected_value(),
+                FetchNature::Monthly,
+            )?
+        };
 
         *self.home_table = TableData::new(self.home_txs.tx_array());
 
@@ -1920,30 +2460,44 @@ impl InputKeyHandler<'_> {
 
     /// Reset summary table data by recreating it from gathered Summary Data
     fn reload_summary(&mut self) -> Result<()> {
-        let fetch_nature = match self.summary_modes.index {
-            0 => FetchNature::Monthly,
-            1 => FetchNature::Yearly,
-            2 => FetchNature::All,
-            _ => panic!("Invalid summary mode"),
-        };
+        let mode = self.summary_modes.get_selected_value();
+        let fetch_nature = self.summary_period.fetch_nature(mode);
+        let period_mode = PeriodData::is_period_mode(mode);
 
-        let summary_view = self.conn.get_summary_with_str(
-            self.summary_months.get_selected_value(),
-            self.summary_years.get_selected_value(),
-            fetch_nature,
-        )?;
+        let summary_view = if period_mode {
+            self.conn
+                .get_summary_with_date(self.summary_period.date, fetch_nature)?
+        } else {
+            self.conn.get_summary_with_str(
+                self.summary_months.get_selected_value(),
+                self.summary_years.get_selected_value(),
+                fetch_nature,
+            )?
+        };
 
         *self.summary_sort = SortingType::Tags;
 
-        let (previous_month, previous_year) = self.get_summary_previous_value();
-
-        if let Some(month) = previous_month
-            && let Some(year) = previous_year
-        {
-            let last_summary_view = self
-                .conn
-                .get_summary_with_str(&month, &year, fetch_nature)?;
+        let last_summary_view = if period_mode {
+            match fetch_nature.previous_period(self.summary_period.date) {
+                Some((date, nature)) => Some(self.conn.get_summary_with_date(date, nature)?),
+                None => None,
+            }
+        } else {
+            let (previous_month, previous_year) = self.get_summary_previous_value();
+
+            if let Some(month) = previous_month
+                && let Some(year) = previous_year
+            {
+                Some(
+                    self.conn
+                        .get_summary_with_str(&month, &year, fetch_nature)?,
+                )
+            } else {
+                None
+            }
+        };
 
+        if let Some(last_summary_view) = last_summary_view {
             let last_full_summary = last_summary_view.generate_summary(None, self.conn);
 
             let mut summary_tags_table =
@@ -1967,23 +2521,47 @@ impl InputKeyHandler<'_> {
             *self.summary_view = summary_view;
         }
 
+        if self.is_summary_report() {
+            let year = self.summary_years.get_selected_value().parse::<i32>()?;
+            let report = self.conn.get_yearly_report(year)?;
+
+            *self.summary_table = TableData::new(report.array());
+            self.summary_report.report = Some(report);
+        } else {
+            self.summary_report.report = None;
+        }
+
+        if let Some((date, nature, _)) = &self.summary_compare.base {
+            let base_view = self.conn.get_summary_with_date(*date, *nature)?;
+            let comparison = base_view.compare(self.summary_view, self.conn);
+
+            *self.summary_table = TableData::new(comparison.tags_array());
+            self.summary_compare.current_name = self.summary_selected_period().2;
+            self.summary_compare.comparison = Some(comparison);
+        } else {
+            self.summary_compare.comparison = None;
+        }
+
         Ok(())
     }
 
     /// Reload chart data by fetching from the DB
     fn reload_chart_data(&mut self) -> Result<()> {
-        let fetch_nature = match self.chart_modes.index {
-            0 => FetchNature::Monthly,
-            1 => FetchNature::Yearly,
-            2 => FetchNature::All,
-            _ => panic!("Invalid chart mode"),
+        let mode = self.chart_modes.get_selected_value();
+        let fetch_nature = self.chart_period.fetch_nature(mode);
+
+        *self.chart_view = if PeriodData::is_period_mode(mode) {
+            self.conn
+                .get_chart_view_with_date(self.chart_period.date, fetch_nature)?
+        } else {
+            self.conn.get_chart_view_with_str(
+                self.chart_months.get_selected_value(),
+                self.chart_years.get_selected_value(),
+                fetch_nature,
+            )?
         };
 
-        *self.chart_view = self.conn.get_chart_view_with_str(
-            self.chart_months.get_selected_value(),
-            self.chart_years.get_selected_value(),
-            fetch_nature,
-        )?;
+        self.reload_chart_forecast()?;
 
         *self.chart_tx_methods = IndexedData::new_tx_methods_cumulative(self.conn);
         *self.chart_activated_methods = self
@@ -1993,9 +2571,46 @@ impl InputKeyHandler<'_> {
             .map(|s| (s, true))
             .collect();
 
+        self.reload_chart_tags();
+
+        Ok(())
+    }
+
+    /// Reload the tag trend chart tags while keeping the earlier selection of the tags that remain
+    fn reload_chart_tags(&mut self) {
+        let selected_tag = self.chart_tags.titles.get(self.chart_tags.index).cloned();
+
+        *self.chart_tags = IndexedData::new_chart_tags(self.chart_view);
+        *self.chart_activated_tags =
+            chart_tag_activation(self.chart_tags, self.chart_activated_tags);
+
+        if let Some(index) =
+            selected_tag.and_then(|tag| self.chart_tags.titles.iter().position(|t| *t == tag))
+        {
+            self.chart_tags.index = index;
+        }
+    }
+
+    /// Reload the forecast from the last date of the chart if the forecast is enabled
+    fn reload_chart_forecast(&mut self) -> Result<()> {
+        *self.chart_forecast =
+            if self.chart_forecast_months.is_some() && !self.chart_view.is_empty() {
+                Some(self.conn.get_forecast_view(self.chart_view.end_date())?)
+            } else {
+                None
+            };
+
         Ok(())
     }
 
+    /// Extends the year lists in case the new tx was added outside of them
+    fn reload_years(&mut self) {
+        self.home_years.reload_yearly(self.conn);
+        self.chart_years.reload_yearly(self.conn);
+        self.summary_years.reload_yearly(self.conn);
+        self.activity_years.reload_yearly(self.conn);
+    }
+
     /// Reset all currently shown search related data
     fn reset_search_data(&mut self) {
         *self.search_table = TableData::new(Vec::new());
@@ -2026,11 +2641,24 @@ impl InputKeyHandler<'_> {
         }
     }
 
+    /// Shift steps the date by a week and Ctrl by a month
+    fn get_date_step(&self) -> DateStep {
+        if self.key.modifiers.contains(KeyModifiers::CONTROL) {
+            DateStep::Month
+        } else if self.key.modifiers.contains(KeyModifiers::SHIFT) {
+            DateStep::Week
+        } else {
+            DateStep::Day
+        }
+    }
+
     fn do_add_tx_step(&mut self, step_type: StepType) {
         let status = match self.add_tx_tab {
-            TxTab::Date => self
-                .add_tx_data
-                .step_date(DateType::Exact, step_type, self.conn),
+            TxTab::Date => {
+                let date_step = self.get_date_step();
+                self.add_tx_data
+                    .step_date(DateType::Exact, step_type, date_step, self.conn)
+            }
             TxTab::FromMethod => self.add_tx_data.step_from_method(step_type, self.conn),
             TxTab::ToMethod => self.add_tx_data.step_to_method(step_type, self.conn),
             TxTab::Amount => self.add_tx_data.step_amount(false, step_type, self.conn),
@@ -2046,9 +2674,11 @@ impl InputKeyHandler<'_> {
 
     fn do_search_step(&mut self, step_type: StepType) {
         let status = match self.search_tab {
-            TxTab::Date => self
-                .search_data
-                .step_date(*self.search_date_type, step_type, self.conn),
+            TxTab::Date => {
+                let date_step = self.get_date_step();
+                self.search_data
+                    .step_date(*self.search_date_type, step_type, date_step, self.conn)
+            }
             TxTab::FromMethod => self.search_data.step_from_method(step_type, self.conn),
             TxTab::ToMethod => self.search_data.step_to_method(step_type, self.conn),
             TxTab::Amount => self.search_data.step_amount(false, step_type, self.conn),
@@ -2069,7 +2699,7 @@ impl InputKeyHandler<'_> {
                 }
                 Ok(())
             }
-            TxTab::Details => Ok(()),
+            TxTab::Details | TxTab::Time => Ok(()),
         };
 
         if let Err(e) = status {
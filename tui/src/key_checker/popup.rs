@@ -10,7 +10,7 @@ pub fn popup_keys(handler: &mut InputKeyHandler) -> Result<Option<HandlingOutput
         PopupType::Choice(_) => match handler.key.code {
             KeyCode::Up => handler.popup_up(),
             KeyCode::Down => handler.popup_down(),
-            KeyCode::Enter => handler.handle_choice_popup_selection()?,
+            KeyCode::Enter => return handler.handle_choice_popup_selection(),
             KeyCode::Char('h') => handler.do_popup_help_popup(),
             _ => handler.do_empty_popup(),
         },
@@ -26,11 +26,18 @@ pub fn popup_keys(handler: &mut InputKeyHandler) -> Result<Option<HandlingOutput
                     KeyCode::Down => handler.popup_down(),
                     _ => handler.do_empty_popup(),
                 }
+            } else if info.is_restore_preview() {
+                match handler.key.code {
+                    KeyCode::Enter => handler.handle_restore_popup()?,
+                    KeyCode::Up => handler.popup_up(),
+                    KeyCode::Down => handler.popup_down(),
+                    _ => handler.do_empty_popup(),
+                }
             } else {
                 match handler.key.code {
                     KeyCode::Up => handler.popup_up(),
                     KeyCode::Down => handler.popup_down(),
-                    KeyCode::Enter => handler.handle_choice_popup_selection()?,
+                    KeyCode::Enter => return handler.handle_choice_popup_selection(),
                     KeyCode::Char('h') => handler.do_popup_help_popup(),
                     _ => handler.do_empty_popup(),
                 }
@@ -52,7 +59,7 @@ pub fn popup_keys(handler: &mut InputKeyHandler) -> Result<Option<HandlingOutput
             KeyCode::Char('h') => handler.do_popup_help_popup(),
             _ => handler.do_empty_popup(),
         },
-        PopupType::Input(_) => handler.handle_popup_input()?,
+        PopupType::Input(_) => return handler.handle_popup_input(),
         PopupType::Nothing => unreachable!(),
     }
     Ok(None)
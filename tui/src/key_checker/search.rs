@@ -47,7 +47,7 @@ pub fn search_keys(handler: &mut InputKeyHandler) -> Result<Option<HandlingOutpu
                     TxTab::Amount => handler.handle_amount()?,
                     TxTab::TxType => handler.handle_tx_type()?,
                     TxTab::Tags => handler.handle_tags(),
-                    TxTab::Nothing => {}
+                    TxTab::Time | TxTab::Nothing => {}
                 },
             },
         },
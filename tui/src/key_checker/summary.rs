@@ -18,8 +18,12 @@ pub fn summary_keys(handler: &mut InputKeyHandler) -> Result<Option<HandlingOutp
             KeyCode::Char('h') => handler.do_help_popup(),
             KeyCode::Char('z') => handler.do_summary_hidden_mode(),
             KeyCode::Char('x') => handler.change_summary_sort(),
+            KeyCode::Char('m') => handler.do_summary_report()?,
+            KeyCode::Char('e') => handler.export_summary_report()?,
+            KeyCode::Char('p') => handler.do_summary_compare()?,
             KeyCode::Char('y') => handler.go_activity(),
             KeyCode::Char('t') => handler.next_theme()?,
+            KeyCode::Char('c') => handler.do_custom_range_popup(),
             KeyCode::Right => handler.handle_right_arrow()?,
             KeyCode::Left => handler.handle_left_arrow()?,
             KeyCode::Up => handler.handle_up_arrow(),
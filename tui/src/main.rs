@@ -1,46 +1,56 @@
+mod cli;
 mod config;
 mod key_checker;
 mod outputs;
 mod page_handler;
 mod pages;
+mod server;
 mod theme;
 mod tx_handler;
 mod utility;
 
-use dirs::data_local_dir;
-use page_handler::initialize_app;
-use std::env::{current_dir, set_current_dir};
-use std::fs;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
+use cli::Cli;
+use config::DataPaths;
+use page_handler::{initialize_app, initialize_cli};
+use std::env::current_dir;
 
 fn main() {
-    if let Some(dir) = data_local_dir() {
-        // The path where the application was opened initially
-        let original_dir = current_dir().unwrap();
+    let cli = Cli::parse();
 
-        // The OS based path where data will be stored
-        let mut working_path = dir;
-        working_path.push("Rex");
-        // Create folder if non-existing then move the current working directory
-        // to the OS data directory
-        let Ok(()) = fs::create_dir_all(&working_path) else {
-            println!("Failed to work with the working path. Exiting program...");
-            return;
-        };
+    // The path flags are global so they work with commands but this one is only for the TUI
+    if cli.ephemeral && cli.command.is_some() {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--ephemeral cannot be used with a command",
+            )
+            .exit();
+    }
 
-        let Ok(()) = set_current_dir(&working_path) else {
-            println!("Failed to set the working path. Exiting program...");
-            return;
-        };
+    // The path where the application was opened initially
+    let original_dir = current_dir().unwrap();
 
-        let mut migrated_path = working_path.clone();
-        migrated_path.push("rex.sqlite");
+    // The OS based path where data will be stored unless another one is given
+    let paths = match DataPaths::resolve(cli.data_dir, cli.db, cli.config) {
+        Ok(paths) => paths,
+        Err(e) => {
+            println!("Failed to work with the working path. Error: {e}. Exiting program...");
+            return;
+        }
+    };
 
-        working_path.push("data.sqlite");
-        if let Err(e) = initialize_app(&working_path, &migrated_path, &original_dir) {
-            println!("Failed to initialize app. Error: {e:?}");
+    if let Some(command) = cli.command {
+        if let Err(e) = initialize_cli(&paths, command) {
+            eprintln!("Error: {e}");
             std::process::exit(1);
         }
-    } else {
-        println!("Could not find local data directory. Exiting program...");
+        return;
+    }
+
+    if let Err(e) = initialize_app(&paths, &original_dir, cli.ephemeral) {
+        println!("Failed to initialize app. Error: {e:?}");
+        std::process::exit(1);
     }
 }
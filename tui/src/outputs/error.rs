@@ -1,3 +1,4 @@
+use rex_app::error::RexError;
 use std::error::Error;
 use std::io::Error as ioError;
 use strum_macros::Display;
@@ -27,3 +28,46 @@ pub enum CheckingError {
 }
 
 impl Error for CheckingError {}
+
+/// Message for the Error popup. Errors from the DB get a hint on what went wrong and how to
+/// recover while anything else is shown as it is
+#[must_use]
+pub fn error_message(error: &anyhow::Error) -> String {
+    let Some(rex_error) = error.downcast_ref::<RexError>() else {
+        return error.to_string();
+    };
+
+    match rex_error {
+        RexError::NotFound(e) => format!(
+            "{e}.\n\nIt may have been deleted or renamed. Reload the page by switching to it again."
+        ),
+        RexError::Validation(e) => e.to_string(),
+        RexError::Integrity(e) => format!("{e}\n\nThe change was rejected and nothing was saved."),
+        RexError::Locked => "The database is being used by another process, possibly another Rex instance or a running `rex serve`.\n\nClose it and try again. Nothing was saved.".to_string(),
+        RexError::Open(e) => format!(
+            "Failed to open the database. {e}\n\nCheck that the database file exists, is not corrupted and is readable."
+        ),
+        RexError::Migration(e) => format!(
+            "Failed to update the database to the current version. {e}\n\nCheck that the database file is not corrupted or restore it from a backup."
+        ),
+        RexError::OldDbMigration(e) => format!(
+            "Failed to migrate the old database. {e}\n\nThe old database file was left untouched."
+        ),
+        RexError::SchemaMismatch(e) => format!(
+            "{e}.\n\nThe backup may come from a newer version of Rex. The database was left as it was."
+        ),
+        RexError::Backup(e) => format!(
+            "Failed to back up the database. {e}\n\nCheck that the backup paths exist and are writable."
+        ),
+        RexError::Crypto(e) => format!(
+            "{e}\n\nThe encrypted database was left untouched."
+        ),
+        RexError::Io(e) => format!(
+            "Failed to read or write a file. {e}\n\nCheck that the app data directory exists and is writable."
+        ),
+        RexError::Database(e) => format!(
+            "The database returned an unexpected error. {e}\n\nIf this keeps happening, restore the database from a backup."
+        ),
+        RexError::Other(e) => e.to_string(),
+    }
+}
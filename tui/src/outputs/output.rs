@@ -1,6 +1,10 @@
 pub enum HandlingOutput {
     QuitUi,
     PrintNewUpdate,
+    /// Ends the session and reopens the app on the profile
+    SwitchProfile(String),
+    /// Adds the profile then switches to it
+    NewProfile(String),
 }
 
 #[derive(Debug, PartialEq)]
@@ -0,0 +1,153 @@
+use anyhow::{Result, anyhow};
+use chrono::{Local, NaiveDateTime};
+use rex_app::backup::{Snapshot, copy_db};
+use rex_app::crypto::EncryptedDb;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+
+/// How often the worker wakes up to check if the backup interval has passed
+const TICK: Duration = Duration::from_secs(30);
+
+/// Outcome of the last backup made by the worker
+#[derive(Clone)]
+pub enum BackupStatus {
+    Saved(NaiveDateTime),
+    Failed(NaiveDateTime, String),
+}
+
+/// Makes backups in a background thread while the TUI is running so a crash or a killed
+/// terminal does not lose the session. The UI only sends the change count and never waits on a
+/// backup. The config is copied when the worker starts
+pub struct BackupWorker {
+    sender: Sender<usize>,
+    status: Arc<Mutex<Option<BackupStatus>>>,
+    handle: JoinHandle<()>,
+}
+
+impl BackupWorker {
+    /// Starts the worker for the DB at `db_path`. Returns None if periodic backups are turned off
+    /// or there is nowhere to write them. An encrypted DB is also written back encrypted on
+    /// every backup
+    pub fn start(
+        config: &Config,
+        db_path: &Path,
+        encrypted: Option<Arc<EncryptedDb>>,
+    ) -> Option<Self> {
+        let settings = config.auto_backup.unwrap_or_default();
+        let has_backup_paths = config
+            .backup_db_path
+            .as_ref()
+            .is_some_and(|p| !p.is_empty());
+
+        if !settings.is_enabled() || (!has_backup_paths && encrypted.is_none()) {
+            return None;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let status = Arc::new(Mutex::new(None));
+
+        let worker = Worker {
+            config: config.clone(),
+            db_path: db_path.to_path_buf(),
+            encrypted,
+            status: status.clone(),
+        };
+
+        let handle = thread::spawn(move || worker.run(&receiver));
+
+        Some(Self {
+            sender,
+            status,
+            handle,
+        })
+    }
+
+    /// Sends the number of changes made through the connection so far
+    pub fn notify(&self, changes: usize) {
+        // Fails only if the worker has stopped, there is nothing left to do then
+        let _ = self.sender.send(changes);
+    }
+
+    #[must_use]
+    pub fn status(&self) -> Option<BackupStatus> {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Stops the worker and waits for a running backup to finish
+    pub fn stop(self) {
+        drop(self.sender);
+        let _ = self.handle.join();
+    }
+}
+
+struct Worker {
+    config: Config,
+    db_path: PathBuf,
+    encrypted: Option<Arc<EncryptedDb>>,
+    status: Arc<Mutex<Option<BackupStatus>>>,
+}
+
+impl Worker {
+    fn run(self, receiver: &Receiver<usize>) {
+        let settings = self.config.auto_backup.unwrap_or_default();
+
+        let mut changes = 0;
+        let mut backed_up_changes = 0;
+        let mut last_backup = Instant::now();
+
+        loop {
+            match receiver.recv_timeout(TICK) {
+                Ok(new_changes) => changes = new_changes,
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            let pending = changes.saturating_sub(backed_up_changes);
+
+            if !settings.is_due(last_backup.elapsed(), pending) {
+                continue;
+            }
+
+            let now = Local::now().naive_local();
+
+            let status = match self.backup() {
+                Ok(()) => BackupStatus::Saved(now),
+                Err(e) => BackupStatus::Failed(now, e.to_string()),
+            };
+
+            *self.status.lock().unwrap() = Some(status);
+
+            backed_up_changes = changes;
+            last_backup = Instant::now();
+        }
+    }
+
+    fn backup(&self) -> Result<()> {
+        let errors = match &self.encrypted {
+            Some(db) => {
+                let snapshot = Snapshot::new(&self.db_path)?;
+                db.encrypt_file(snapshot.path(), db.path())?;
+
+                self.config.backup_to_paths(true, |target| {
+                    db.encrypt_file(snapshot.path(), target)?;
+                    Ok(())
+                })
+            }
+            None => self.config.backup_to_paths(false, |target| {
+                copy_db(&self.db_path, target)?;
+                Ok(())
+            }),
+        };
+
+        if !errors.is_empty() {
+            return Err(anyhow!(errors.join(". ")));
+        }
+
+        Ok(())
+    }
+}
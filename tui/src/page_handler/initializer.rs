@@ -1,7 +1,10 @@
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use atty::Stream;
-use rex_app::conn::get_conn;
-use std::env::set_current_dir;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use rex_app::conn::{DbConn, try_get_conn};
+use rex_app::crypto::{CryptoError, EncryptedDb, encrypted_path};
+use rex_app::error::RexError;
+use std::env;
 use std::fs::{self, File};
 use std::io::prelude::*;
 use std::path::{Path, PathBuf};
@@ -9,22 +12,22 @@ use std::process;
 use std::sync::{Arc, Mutex};
 use std::thread::spawn;
 
-use crate::config::{Config, migrate_config};
-use crate::outputs::HandlingOutput;
-use crate::page_handler::start_app;
+use crate::cli::{Command, run_command};
+use crate::config::{Config, DB_NAME, DataPaths, ENCRYPTED_DB_NAME, migrate_config};
+use crate::outputs::{HandlingOutput, error_message};
+use crate::page_handler::{BackupWorker, TableData, start_app};
+use crate::pages::{error_ui, profile_ui};
+use crate::theme::Theme;
 use crate::utility::{
-    check_version, enter_tui_interface, exit_tui_interface, migrate_to_new_schema, start_terminal,
+    PASSPHRASE_ENV, check_version, enter_tui_interface, exit_tui_interface, migrate_to_new_schema,
+    read_passphrase, start_terminal,
 };
 
 /// Initialize the TUI loop
-pub fn initialize_app(
-    old_db_path: &PathBuf,
-    migrated_db_path: &Path,
-    original_dir: &PathBuf,
-) -> Result<()> {
+pub fn initialize_app(paths: &DataPaths, original_dir: &Path, ephemeral: bool) -> Result<()> {
     // If is not terminal, try to start a terminal otherwise create an error.txt file with the error message
     if !atty::is(Stream::Stdout) && !start_terminal(original_dir.to_str().unwrap()) {
-        let mut error_location = PathBuf::from(&original_dir);
+        let mut error_location = original_dir.to_path_buf();
         error_location.push("Error.txt");
 
         let mut open = File::create(error_location)?;
@@ -35,8 +38,308 @@ pub fn initialize_app(
         process::exit(1);
     }
 
-    let result =
-        migrate_to_new_schema(old_db_path, migrated_db_path.display().to_string().as_str());
+    let mut root_config = prepare_db(paths)?;
+
+    let new_update = Arc::new(Mutex::new(None));
+
+    let update_clone = new_update.clone();
+
+    spawn(move || {
+        let new_version = check_version().unwrap_or_default().unwrap_or_default();
+
+        let mut lock = update_clone.lock().unwrap();
+        *lock = Some(new_version);
+    });
+
+    let Some(mut profile) = profile_screen(&mut root_config)? else {
+        return Ok(());
+    };
+
+    // Every switch ends the session and opens the next profile from scratch
+    loop {
+        let mut config = root_config.profile_config(&profile)?;
+        let new_db_path = profile_db_path(&config)?;
+
+        // Asked before the TUI starts so the passphrase is typed on the plain terminal
+        let encrypted = unlock_db(&new_db_path)?;
+        let db_path = encrypted
+            .as_ref()
+            .map_or_else(|| new_db_path.clone(), EncryptedDb::db_path);
+
+        let theme = Theme::new_index(config.theme_index.unwrap_or(0));
+
+        let Some(migrated_conn) = open_db(&db_path, &theme, ephemeral)? else {
+            return Ok(());
+        };
+
+        let output = start_tui(
+            &mut config,
+            &root_config,
+            &new_db_path,
+            new_update.clone(),
+            migrated_conn,
+            ephemeral,
+            encrypted,
+        )?;
+
+        // The default profile session writes to the same file so it is read again
+        root_config = paths.root_config()?;
+
+        profile = match output {
+            HandlingOutput::SwitchProfile(name) => name,
+            HandlingOutput::NewProfile(name) => {
+                root_config.add_profile(&name)?;
+                name
+            }
+            _ => break,
+        };
+
+        root_config.set_active_profile(&profile)?;
+
+        // The update popup was already shown once
+        if let Some(data) = new_update.lock().unwrap().as_mut() {
+            data.clear();
+        }
+    }
+
+    Ok(())
+}
+
+/// Lets the user pick a profile before anything is opened. Skipped if there is only the
+/// default profile or the DB was set from the command line. Returns None if the user quits
+fn profile_screen(root_config: &mut Config) -> Result<Option<String>> {
+    let active = root_config.start_profile().to_string();
+
+    if root_config.profiles.is_empty() || root_config.db_override.is_some() {
+        return Ok(Some(active));
+    }
+
+    let theme = Theme::new_index(root_config.theme_index.unwrap_or(0));
+    let names = root_config.profile_names();
+
+    let mut table = TableData::new(names.iter().map(|name| vec![name.clone()]).collect());
+    table
+        .state
+        .select(names.iter().position(|name| name == &active));
+
+    let mut terminal = enter_tui_interface()?;
+
+    let selected = loop {
+        terminal.draw(|f| profile_ui(f, &mut table, &theme))?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Up => table.previous(),
+                KeyCode::Down => table.next(),
+                KeyCode::Enter => break table.state.selected().map(|i| names[i].clone()),
+                KeyCode::Char('q' | 'Q') | KeyCode::Esc => break None,
+                _ => {}
+            }
+        }
+    };
+
+    exit_tui_interface()?;
+
+    if let Some(profile) = &selected
+        && profile != &active
+    {
+        root_config.set_active_profile(profile)?;
+    }
+
+    Ok(selected)
+}
+
+/// Decrypts the DB if it is encrypted. A wrong passphrase can be retried a few times
+fn unlock_db(db_path: &Path) -> Result<Option<EncryptedDb>> {
+    let path = encrypted_path(db_path);
+
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    let mut attempts = 0;
+
+    loop {
+        let passphrase = read_passphrase("Database passphrase: ")?;
+        attempts += 1;
+
+        match EncryptedDb::open(&path, &passphrase) {
+            Ok(db) => return Ok(Some(db)),
+            Err(RexError::Crypto(CryptoError::WrongPassphrase))
+                if attempts < 3 && env::var(PASSPHRASE_ENV).is_err() =>
+            {
+                println!("Wrong passphrase. Try again");
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Asks for a new passphrase twice so a typo does not lock the DB
+fn new_passphrase() -> Result<String> {
+    if let Ok(passphrase) = env::var(PASSPHRASE_ENV) {
+        return Ok(passphrase);
+    }
+
+    let passphrase = read_passphrase("New passphrase: ")?;
+
+    if passphrase.is_empty() {
+        return Err(CryptoError::EmptyPassphrase.into());
+    }
+
+    if read_passphrase("Repeat the passphrase: ")? != passphrase {
+        return Err(anyhow!("The passphrases do not match"));
+    }
+
+    Ok(passphrase)
+}
+
+/// Encrypts the DB and every backup then deletes the plain copies
+fn encrypt_db(config: &Config, db_path: &Path) -> Result<()> {
+    let path = encrypted_path(db_path);
+
+    if path.exists() {
+        return Err(anyhow!("The database is already encrypted"));
+    }
+
+    // Creates the DB if there is none yet so there is always something to encrypt
+    drop(try_get_conn(db_path.display().to_string().as_str())?);
+
+    let passphrase = new_passphrase()?;
+
+    let db = EncryptedDb::create(db_path, &path, &passphrase)?;
+    config.save_encrypted_backup(&db)?;
+
+    fs::remove_file(db_path)?;
+    config.remove_backups(DB_NAME);
+
+    for backup in config.backups().iter().filter(|b| !b.encrypted) {
+        let result = db
+            .encrypt_file(&backup.path, &encrypted_path(&backup.path))
+            .map_err(anyhow::Error::from)
+            .and_then(|()| Ok(fs::remove_file(&backup.path)?));
+
+        if let Err(e) = result {
+            println!(
+                "Failed to encrypt backup {}. Error: {e}",
+                backup.path.display()
+            );
+        }
+    }
+
+    println!("Database encrypted. The passphrase will be asked for on every start");
+    println!("There is no way to recover the data without the passphrase");
+
+    Ok(())
+}
+
+/// Decrypts the DB and every backup then deletes the encrypted copies
+fn decrypt_db(config: &Config, db_path: &Path) -> Result<()> {
+    let Some(db) = unlock_db(db_path)? else {
+        return Err(anyhow!("The database is not encrypted"));
+    };
+
+    fs::copy(db.db_path(), db_path)?;
+    config.save_backup(db_path);
+
+    fs::remove_file(db.path())?;
+    config.remove_backups(ENCRYPTED_DB_NAME);
+
+    for backup in config.backups().iter().filter(|b| b.encrypted) {
+        // Drops the .enc extension
+        let result = db
+            .decrypt_file(&backup.path, &backup.path.with_extension(""))
+            .map_err(anyhow::Error::from)
+            .and_then(|()| Ok(fs::remove_file(&backup.path)?));
+
+        if let Err(e) = result {
+            println!(
+                "Failed to decrypt backup {}. It was left encrypted. Error: {e}",
+                backup.path.display()
+            );
+        }
+    }
+
+    println!("Database decrypted");
+
+    Ok(())
+}
+
+/// Writes the DB back to the disk if it is encrypted and copies it to the backup paths
+fn save_db(config: &Config, db_path: &Path, encrypted: Option<&EncryptedDb>) -> Result<()> {
+    match encrypted {
+        Some(db) => config.save_encrypted_backup(db),
+        None => {
+            config.save_backup(db_path);
+            Ok(())
+        }
+    }
+}
+
+/// Opens the DB. If it fails, the error screen is shown until the DB opens or the user quits.
+/// An ephemeral session gets an in-memory copy of the DB so nothing is written to the disk
+fn open_db(db_path: &Path, theme: &Theme, ephemeral: bool) -> Result<Option<DbConn>> {
+    let location = db_path.display().to_string();
+
+    loop {
+        let conn = if !ephemeral {
+            try_get_conn(&location)
+        } else if db_path.is_file() {
+            DbConn::in_memory_copy(&location)
+        } else {
+            DbConn::in_memory()
+        };
+
+        match conn {
+            Ok(conn) => return Ok(Some(conn)),
+            Err(e) => {
+                if !error_screen(&e.into(), theme)? {
+                    return Ok(None);
+                }
+            }
+        }
+    }
+}
+
+/// Shows the error until the user picks to retry or quit. Returns true on retry
+fn error_screen(error: &anyhow::Error, theme: &Theme) -> Result<bool> {
+    let message = error_message(error);
+    let mut terminal = enter_tui_interface()?;
+
+    let retry = loop {
+        terminal.draw(|f| error_ui(f, &message, theme))?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Char('r' | 'R') => break true,
+                KeyCode::Char('q' | 'Q') | KeyCode::Esc => break false,
+                _ => {}
+            }
+        }
+    };
+
+    exit_tui_interface()?;
+
+    Ok(retry)
+}
+
+/// Migrates the old DB and the config if required. Returns the config of the app data directory
+fn prepare_db(paths: &DataPaths) -> Result<Config> {
+    let old_db_path = paths.old_db();
+    let migrated_db_path = paths.original_db();
+
+    let result = migrate_to_new_schema(
+        &old_db_path,
+        migrated_db_path.display().to_string().as_str(),
+    );
 
     match result {
         Ok(result) => {
@@ -47,74 +350,161 @@ pub fn initialize_app(
         }
         Err(e) => {
             println!("Failed to migrate to new schema. Error: {e:?}");
-            fs::remove_file(migrated_db_path)?;
+            fs::remove_file(&migrated_db_path)?;
             process::exit(1);
         }
     }
 
-    let new_update = Arc::new(Mutex::new(None));
+    if let Err(e) = migrate_config(&old_db_path) {
+        println!("Failed to migrate config. Error: {e:?}");
+        process::exit(1);
+    }
 
-    let update_clone = new_update.clone();
+    paths.root_config()
+}
 
-    spawn(move || {
-        let new_version = check_version().unwrap_or_default().unwrap_or_default();
+/// The DB path of a profile. Errors if the new location of the profile no longer exists
+fn profile_db_path(config: &Config) -> Result<PathBuf> {
+    if config.db_override.is_none()
+        && let Some(location) = &config.new_location
+        && !location.is_dir()
+    {
+        return Err(anyhow!(
+            "Failed to find the new location {}. Does the path exist?",
+            location.display()
+        ));
+    }
 
-        let mut lock = update_clone.lock().unwrap();
-        *lock = Some(new_version);
-    });
+    Ok(config.db_path())
+}
 
-    if let Err(e) = migrate_config(old_db_path) {
-        println!("Failed to migrate config. Error: {e:?}");
-        process::exit(1);
+/// Run a CLI command without starting the TUI
+pub fn initialize_cli(paths: &DataPaths, command: Command) -> Result<()> {
+    let root_config = prepare_db(paths)?;
+    let mut config = root_config.profile_config(root_config.start_profile())?;
+    let db_path = profile_db_path(&config)?;
+
+    match command {
+        Command::Encrypt => return encrypt_db(&config, &db_path),
+        Command::Decrypt => return decrypt_db(&config, &db_path),
+        _ => {}
     }
 
-    let mut config = Config::get_config(&migrated_db_path.to_path_buf())?;
+    let encrypted = unlock_db(&db_path)?;
 
-    let new_db_path = if let Some(mut location) = config.new_location.clone() {
-        let result = set_current_dir(&location);
+    // The encrypted DB is only written back once the command ends
+    if encrypted.is_some() && matches!(command, Command::Serve(_)) {
+        return Err(anyhow!(
+            "The API server cannot run on an encrypted database. Run rex decrypt first"
+        ));
+    }
 
-        if let Err(e) = result {
-            println!(
-                "Failed to set the new path. Does the path exists? Exiting program. Error: {e}"
-            );
-            process::exit(1);
-        } else {
-            location.push("rex.sqlite");
-            location.clone()
-        }
+    let conn_path = encrypted
+        .as_ref()
+        .map_or_else(|| db_path.clone(), EncryptedDb::db_path);
+
+    let mut conn = try_get_conn(conn_path.display().to_string().as_str())?;
+
+    let modifies_db = command.modifies_db();
+
+    run_command(command, &mut config, &mut conn)?;
+
+    drop(conn);
+
+    if modifies_db {
+        save_db(&config, &db_path, encrypted.as_ref())?;
+    }
+
+    Ok(())
+}
+
+/// Initialize the TUI loop. Returns once the session ends with the output that ended it
+fn start_tui(
+    config: &mut Config,
+    root_config: &Config,
+    new_db_path: &Path,
+    new_update: Arc<Mutex<Option<Vec<String>>>>,
+    mut migrated_conn: DbConn,
+    ephemeral: bool,
+    encrypted: Option<EncryptedDb>,
+) -> Result<HandlingOutput> {
+    // Shared with the backup worker that writes the encrypted DB back during the session
+    let encrypted = encrypted.map(Arc::new);
+
+    // Restoring a backup writes a safety copy of this file first
+    let live_db = encrypted
+        .as_deref()
+        .map_or_else(|| new_db_path.to_path_buf(), EncryptedDb::db_path);
+
+    let mut backup_worker = if ephemeral {
+        None
     } else {
-        migrated_db_path.to_path_buf()
+        BackupWorker::start(config, &live_db, encrypted.clone())
     };
 
-    let mut migrated_conn = get_conn(new_db_path.display().to_string().as_str());
+    let live_db = (!ephemeral).then_some(live_db.as_path());
 
     loop {
         let mut terminal = enter_tui_interface()?;
         let result = start_app(
             &mut terminal,
             new_update.clone(),
-            &mut config,
+            config,
             &mut migrated_conn,
+            live_db,
+            encrypted.as_deref(),
+            backup_worker.as_ref(),
+            root_config,
         );
         exit_tui_interface()?;
 
         match result {
             Ok(output) => match output {
-                HandlingOutput::QuitUi => {
+                HandlingOutput::QuitUi
+                | HandlingOutput::SwitchProfile(_)
+                | HandlingOutput::NewProfile(_) => {
+                    if let Some(worker) = backup_worker.take() {
+                        worker.stop();
+                    }
+
                     drop(migrated_conn);
-                    config.save_backup(&new_db_path.clone());
-                    break;
+
+                    if ephemeral {
+                        println!("Ephemeral session ended. No changes were saved");
+                    } else {
+                        save_db(config, new_db_path, encrypted.as_deref())?;
+                    }
+                    return Ok(output);
                 }
                 HandlingOutput::PrintNewUpdate => println!(
                     "Could not open browser.\n\nLatest Version Link: https://github.com/TheRustyPickle/Rex/releases/latest"
                 ),
             },
             Err(error) => {
-                println!("{error}");
-                process::exit(1);
+                let theme = Theme::new_index(config.theme_index.unwrap_or(0));
+                let error = error.into();
+
+                if !error_screen(&error, &theme)? {
+                    if let Some(worker) = backup_worker.take() {
+                        worker.stop();
+                    }
+
+                    // Exiting skips the drops so the encrypted DB has to be saved and the
+                    // decrypted copy deleted here. The worker has stopped so this is the last
+                    // reference
+                    if let Some(db) = encrypted {
+                        drop(migrated_conn);
+
+                        if !ephemeral {
+                            db.save()?;
+                        }
+                        drop(db);
+                    }
+
+                    println!("{error}");
+                    process::exit(1);
+                }
             }
         }
     }
-
-    Ok(())
 }
@@ -1,9 +1,12 @@
+mod backup_worker;
+
 mod initializer;
 
 mod ui_handler;
 
 mod ui_state;
 
-pub use initializer::initialize_app;
+pub use backup_worker::*;
+pub use initializer::{initialize_app, initialize_cli};
 pub use ui_handler::*;
 pub use ui_state::*;
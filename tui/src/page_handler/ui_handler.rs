@@ -2,8 +2,11 @@ use crossterm::event::{self, Event, KeyEventKind, poll};
 use ratatui::Terminal;
 use ratatui::backend::Backend;
 use rex_app::conn::{DbConn, FetchNature};
+use rex_app::crypto::EncryptedDb;
 use rex_app::ui_helper::DateType;
 use rex_app::views::SearchView;
+use std::collections::HashMap;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
@@ -12,10 +15,10 @@ use crate::key_checker::{
     InputKeyHandler, activity_keys, add_tx_keys, chart_keys, home_keys, initial_keys, search_keys,
     summary_keys,
 };
-use crate::outputs::{HandlingOutput, UiHandlingError};
+use crate::outputs::{HandlingOutput, UiHandlingError, error_message};
 use crate::page_handler::{
-    ActivityTab, ChartTab, CurrentUi, HomeTab, IndexedData, SortingType, SummaryTab, TableData,
-    TxTab,
+    ActivityTab, BackupWorker, ChartTab, ChartType, CompareData, CurrentUi, HomeTab, IndexedData,
+    PeriodData, ReportData, SortingType, SummaryTab, TableData, TxTab, chart_tag_activation,
 };
 use crate::pages::{
     InfoPopupState, PopupType, activity_ui, add_tx_ui, chart_ui, home_ui, initial_ui, search_ui,
@@ -31,6 +34,10 @@ pub fn start_app<B: Backend>(
     new_version_data: Arc<Mutex<Option<Vec<String>>>>,
     config: &mut Config,
     conn: &mut DbConn,
+    live_db: Option<&Path>,
+    encrypted_db: Option<&EncryptedDb>,
+    backup_worker: Option<&BackupWorker>,
+    root_config: &Config,
 ) -> Result<HandlingOutput, UiHandlingError> {
     // Setting up some default values. Let's go through all of them
 
@@ -39,24 +46,32 @@ pub fn start_app<B: Backend>(
     // Contains the homepage month list that is indexed
     let mut home_months = IndexedData::new_monthly();
     // Contains the homepage year list that is indexed
-    let mut home_years = IndexedData::new_yearly();
+    let mut home_years = IndexedData::new_yearly(conn);
+    // Contains the homepage mode list that is indexed
+    let mut home_modes = IndexedData::new_home_modes();
+    // The homepage period used by the Weekly, Quarterly and Custom modes
+    let mut home_period = PeriodData::new();
     // Contains the chart page month list that is indexed
     let mut chart_months = IndexedData::new_monthly_no_local();
     // Contains the chart page year list that is indexed
-    let mut chart_years = IndexedData::new_yearly_no_local();
+    let mut chart_years = IndexedData::new_yearly_no_local(conn);
     // Contains the chart page mode selection list that is indexed
     let mut chart_modes = IndexedData::new_modes();
+    // The chart page period used by the Weekly, Quarterly and Custom modes
+    let mut chart_period = PeriodData::new();
     // Contains the chart page TX method selection list that is indexed
     let mut chart_tx_methods = IndexedData::new_tx_methods_cumulative(conn);
 
     // Contains the summary page month list that is indexed
     let mut summary_months = IndexedData::new_monthly_no_local();
     // Contains the summary page year list that is indexed
-    let mut summary_years = IndexedData::new_yearly_no_local();
+    let mut summary_years = IndexedData::new_yearly_no_local(conn);
     // Contains the summary page mode selection list that is indexed
     let mut summary_modes = IndexedData::new_modes();
+    // The summary page period used by the Weekly, Quarterly and Custom modes
+    let mut summary_period = PeriodData::new();
     // Contains the Activity page month list that is indexed
-    let mut activity_years = IndexedData::new_yearly();
+    let mut activity_years = IndexedData::new_yearly(conn);
     // Contains the Activity page month list that is indexed
     let mut activity_months = IndexedData::new_monthly();
 
@@ -66,6 +81,12 @@ pub fn start_app<B: Backend>(
     // How summary table will be sorted
     let mut summary_sort = SortingType::Tags;
 
+    // The month by month yearly report of the Summary page
+    let mut summary_report = ReportData::new();
+
+    // The pinned period comparison of the Summary page
+    let mut summary_compare = CompareData::new();
+
     // An empty search view, contains relevant data to create the search UI
     let mut search_txs = SearchView::new_empty();
 
@@ -113,6 +134,9 @@ pub fn start_app<B: Backend>(
         )
         .unwrap();
 
+    // The balance forecast drawn after the chart. Only loaded when the forecast is enabled
+    let mut chart_forecast = None;
+
     // Summary view contains TX list to create the summary.
     let mut summary_view = conn
         .get_summary_with_str(
@@ -151,6 +175,12 @@ pub fn start_app<B: Backend>(
     // Whether the chart has hidden legends
     let mut chart_hidden_legends = false;
 
+    // Whether the chart page shows the balance, the cash flow or the tag trend chart
+    let mut chart_type = ChartType::Balance;
+
+    // Months ahead the chart balance forecast is drawn for. None if it's disabled
+    let mut chart_forecast_months = None;
+
     // Whether the summary is in hidden mode
     let mut summary_hidden_mode = false;
 
@@ -161,6 +191,12 @@ pub fn start_app<B: Backend>(
         .map(|s| (s, true))
         .collect();
 
+    // Contains the chart page tag selection list of the tag trend chart that is indexed
+    let mut chart_tags = IndexedData::new_chart_tags(&chart_view);
+
+    // Map of which tags are selected in the tag trend chart
+    let mut chart_activated_tags = chart_tag_activation(&chart_tags, &HashMap::new());
+
     // The generated balance section on the Add TX UI
     let mut add_tx_balance = Vec::new();
     // Home and add TX page balance section's column space
@@ -169,6 +205,9 @@ pub fn start_app<B: Backend>(
 
     let mut version_checked = false;
 
+    // The change count last sent to the backup worker
+    let mut notified_changes = conn.changes();
+
     // How it work:
     // Default value from above -> Goes to an interface page and render -> Wait for an event key press.
     //
@@ -202,6 +241,8 @@ pub fn start_app<B: Backend>(
                         f,
                         &home_months,
                         &home_years,
+                        &home_modes,
+                        &home_period,
                         &mut home_table,
                         &home_tab,
                         &mut lerp_state,
@@ -227,13 +268,18 @@ pub fn start_app<B: Backend>(
                         &chart_months,
                         &chart_years,
                         &chart_modes,
+                        &chart_period,
                         &chart_tx_methods,
                         &chart_tab,
                         chart_hidden_mode,
                         chart_hidden_legends,
+                        &chart_type,
+                        &chart_tags,
+                        &chart_activated_tags,
                         &chart_activated_methods,
                         &mut lerp_state,
                         &chart_view,
+                        chart_forecast.as_ref().zip(chart_forecast_months),
                         &theme,
                         conn,
                     ),
@@ -243,10 +289,13 @@ pub fn start_app<B: Backend>(
                         &summary_months,
                         &summary_years,
                         &summary_modes,
+                        &summary_period,
                         &mut summary_table,
                         &summary_tab,
                         summary_hidden_mode,
                         &summary_sort,
+                        &summary_report,
+                        &summary_compare,
                         &mut lerp_state,
                         &full_summary,
                         &theme,
@@ -321,6 +370,7 @@ pub fn start_app<B: Backend>(
                 &mut home_tab,
                 &mut add_tx_data,
                 &mut chart_view,
+                &mut chart_forecast,
                 &mut summary_view,
                 &mut full_summary,
                 &mut home_table,
@@ -328,14 +378,20 @@ pub fn start_app<B: Backend>(
                 &mut summary_table,
                 &mut home_months,
                 &mut home_years,
+                &mut home_modes,
+                &mut home_period,
                 &mut chart_months,
                 &mut chart_years,
                 &mut chart_modes,
+                &mut chart_period,
                 &mut chart_tx_methods,
                 &mut summary_months,
                 &mut summary_years,
                 &mut summary_modes,
+                &mut summary_period,
                 &mut summary_sort,
+                &mut summary_report,
+                &mut summary_compare,
                 &mut search_data,
                 &mut search_date_type,
                 &mut search_tab,
@@ -348,12 +404,20 @@ pub fn start_app<B: Backend>(
                 &mut activity_table,
                 &mut chart_hidden_mode,
                 &mut chart_hidden_legends,
+                &mut chart_type,
+                &mut chart_tags,
+                &mut chart_activated_tags,
+                &mut chart_forecast_months,
                 &mut summary_hidden_mode,
                 &mut chart_activated_methods,
                 &mut lerp_state,
                 config,
                 &mut theme,
                 conn,
+                live_db,
+                encrypted_db,
+                backup_worker,
+                root_config,
             );
 
             let status = match handler.page {
@@ -373,10 +437,17 @@ pub fn start_app<B: Backend>(
                     }
                 }
                 Err(e) => {
-                    let state = InfoPopupState::Error(e.to_string());
+                    let state = InfoPopupState::Error(error_message(&e));
                     popup_status = PopupType::new_info(state);
                 }
             }
+
+            if let Some(worker) = backup_worker
+                && conn.changes() != notified_changes
+            {
+                notified_changes = conn.changes();
+                worker.notify(notified_changes);
+            }
         }
     }
 }
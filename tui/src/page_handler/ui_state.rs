@@ -1,7 +1,9 @@
-use chrono::Datelike;
 use chrono::prelude::Local;
+use chrono::{Datelike, NaiveDate};
 use ratatui::widgets::TableState;
-use rex_app::conn::DbConn;
+use rex_app::conn::{DbConn, FetchNature};
+use rex_app::views::{ChartView, SummaryComparison, YearlyReport};
+use std::collections::HashMap;
 use strum_macros::Display;
 
 pub const MONTHS: [&str; 12] = [
@@ -19,12 +21,28 @@ pub const MONTHS: [&str; 12] = [
     "December",
 ];
 
-pub const YEARS: [&str; 16] = [
-    "2022", "2023", "2024", "2025", "2026", "2027", "2028", "2029", "2030", "2031", "2032", "2033",
-    "2034", "2035", "2036", "2037",
+/// How many years after the current year are available on the year tabs
+pub const YEAR_MARGIN: i32 = 2;
+
+/// The max amount of years shown on a year tab at once
+const YEAR_WINDOW: usize = 16;
+
+/// The max amount of tags shown on the chart tag selection tab at once
+const TAG_WINDOW: usize = 8;
+
+/// Number of the highest spending tags that are selected when no chart tag is selected
+const DEFAULT_CHART_TAGS: usize = 3;
+
+pub const MODES: [&str; 6] = [
+    "Monthly",
+    "Yearly",
+    "All Time",
+    "Weekly",
+    "Quarterly",
+    "Custom",
 ];
 
-pub const MODES: [&str; 3] = ["Monthly", "Yearly", "All Time"];
+pub const HOME_MODES: [&str; 4] = ["Monthly", "Weekly", "Quarterly", "Custom"];
 
 /// The struct stores all transaction data for the Transaction widget
 /// and creates an index to keep track of which transactions row is selected
@@ -106,17 +124,21 @@ impl IndexedData {
         }
     }
 
-    pub fn new_yearly() -> Self {
-        let year_index = Local::now().year() as usize - 2022;
+    pub fn new_yearly(conn: &mut DbConn) -> Self {
+        let current_year = Local::now().year();
+        let (first_year, last_year) = year_range(conn);
+
         IndexedData {
-            titles: YEARS.into_iter().map(ToString::to_string).collect(),
-            index: year_index,
+            titles: (first_year..=last_year).map(|y| y.to_string()).collect(),
+            index: (current_year - first_year) as usize,
         }
     }
 
-    pub fn new_yearly_no_local() -> Self {
+    pub fn new_yearly_no_local(conn: &mut DbConn) -> Self {
+        let (first_year, last_year) = year_range(conn);
+
         IndexedData {
-            titles: YEARS.into_iter().map(ToString::to_string).collect(),
+            titles: (first_year..=last_year).map(|y| y.to_string()).collect(),
             index: 0,
         }
     }
@@ -128,6 +150,13 @@ impl IndexedData {
         }
     }
 
+    pub fn new_home_modes() -> Self {
+        IndexedData {
+            titles: HOME_MODES.into_iter().map(ToString::to_string).collect(),
+            index: 0,
+        }
+    }
+
     pub fn new_tx_methods_cumulative(conn: &mut DbConn) -> Self {
         IndexedData {
             titles: conn.get_tx_methods_cumulative(),
@@ -135,20 +164,27 @@ impl IndexedData {
         }
     }
 
+    /// Tags with any expense in the chart, highest spending first
+    pub fn new_chart_tags(chart_view: &ChartView) -> Self {
+        IndexedData {
+            titles: chart_view.expense_tags(),
+            index: 0,
+        }
+    }
+
     /// Increases the current index by 1 or goes to 0 if at the final value
     pub fn next(&mut self) {
         self.index = (self.index + 1) % self.titles.len();
     }
 
+    /// Goes to the next year. Adds a new year if at the final value
     pub fn next_yearly(&mut self) {
-        if self.index == 8 {
-            self.titles.remove(0);
-            let mut final_year: u64 = self.titles[self.titles.len() - 1].parse().unwrap();
-            final_year += 1;
-            self.titles.push(final_year.to_string());
-        } else {
-            self.next();
+        if self.index == self.titles.len() - 1 {
+            let final_year: i32 = self.titles[self.index].parse().unwrap();
+            self.titles.push((final_year + 1).to_string());
         }
+
+        self.index += 1;
     }
 
     /// Decreases the current index by 1 or goes to final index if at 0
@@ -160,21 +196,52 @@ impl IndexedData {
         }
     }
 
+    /// Goes to the previous year. Stays on the earliest year if already there
     pub fn previous_yearly(&mut self) {
-        if self.index == 8 {
-            let first_year: i64 = self.titles[0].parse().unwrap();
-
-            if first_year != 2022 {
-                self.titles.pop();
-                self.titles.insert(0, (first_year - 1).to_string());
-            } else if self.index > 0 {
-                self.index -= 1;
-            }
-        } else if self.index > 0 {
+        if self.index > 0 {
             self.index -= 1;
         }
     }
 
+    /// Extends the year list if txs now exist outside of it. The selected year stays the same.
+    pub fn reload_yearly(&mut self, conn: &mut DbConn) {
+        let selected_year: i32 = self.get_selected_value().parse().unwrap();
+        let current_first: i32 = self.titles[0].parse().unwrap();
+        let current_last: i32 = self.titles[self.titles.len() - 1].parse().unwrap();
+
+        let (first_year, last_year) = year_range(conn);
+        let first_year = first_year.min(current_first);
+        let last_year = last_year.max(current_last);
+
+        self.titles = (first_year..=last_year).map(|y| y.to_string()).collect();
+        self.index = (selected_year - first_year) as usize;
+    }
+
+    /// Returns the part of the year list that fits in a tab while keeping the selected year visible
+    #[must_use]
+    pub fn year_window(&self) -> IndexedData {
+        self.window(YEAR_WINDOW)
+    }
+
+    /// Returns the part of the tag list that fits in a tab while keeping the selected tag visible
+    #[must_use]
+    pub fn tag_window(&self) -> IndexedData {
+        self.window(TAG_WINDOW)
+    }
+
+    fn window(&self, size: usize) -> IndexedData {
+        let start = self
+            .index
+            .saturating_sub(size / 2)
+            .min(self.titles.len().saturating_sub(size));
+        let end = (start + size).min(self.titles.len());
+
+        IndexedData {
+            titles: self.titles[start..end].to_vec(),
+            index: self.index - start,
+        }
+    }
+
     pub fn set_index_zero(&mut self) {
         self.index = 0;
     }
@@ -183,6 +250,174 @@ impl IndexedData {
     pub fn get_selected_value(&self) -> &str {
         &self.titles[self.index]
     }
+
+    /// Selects the given title if it exists
+    pub fn select_title(&mut self, title: &str) {
+        if let Some(index) = self.titles.iter().position(|t| t == title) {
+            self.index = index;
+        }
+    }
+}
+
+/// Returns the first and the last year to show on the year tabs. Goes from the earliest
+/// tx year to the current year plus [`YEAR_MARGIN`] or the latest tx year, whichever is later.
+fn year_range(conn: &mut DbConn) -> (i32, i32) {
+    let current_year = Local::now().year();
+
+    let (first_year, last_year) = conn
+        .get_tx_year_range()
+        .ok()
+        .flatten()
+        .unwrap_or((current_year, current_year));
+
+    (
+        first_year.min(current_year),
+        last_year.max(current_year + YEAR_MARGIN),
+    )
+}
+
+/// The month by month report that replaces the Summary page content in the Yearly mode
+pub struct ReportData {
+    pub enabled: bool,
+    /// Number of value columns scrolled past. The row name column is always shown
+    pub column_offset: usize,
+    pub report: Option<YearlyReport>,
+}
+
+impl ReportData {
+    #[must_use]
+    pub fn new() -> Self {
+        ReportData {
+            enabled: false,
+            column_offset: 0,
+            report: None,
+        }
+    }
+
+    pub fn scroll_left(&mut self) {
+        self.column_offset = self.column_offset.saturating_sub(1);
+    }
+
+    /// Scrolls to the next column while keeping at least the last column visible
+    pub fn scroll_right(&mut self) {
+        let Some(report) = &self.report else {
+            return;
+        };
+
+        if self.column_offset + 2 < report.headers().len() {
+            self.column_offset += 1;
+        }
+    }
+}
+
+/// Side by side comparison of a pinned Summary period with the selected one
+pub struct CompareData {
+    /// Date, fetch nature and name of the pinned period. None if the comparison is off
+    pub base: Option<(NaiveDate, FetchNature, String)>,
+    /// Name of the selected period the pinned period is compared with
+    pub current_name: String,
+    pub comparison: Option<SummaryComparison>,
+}
+
+impl CompareData {
+    #[must_use]
+    pub fn new() -> Self {
+        CompareData {
+            base: None,
+            current_name: String::new(),
+            comparison: None,
+        }
+    }
+}
+
+/// Keeps track of the period shown in the Weekly, Quarterly and Custom modes.
+/// `date` can be any date within the current Weekly or Quarterly period.
+pub struct PeriodData {
+    pub date: NaiveDate,
+    pub custom_start: NaiveDate,
+    pub custom_end: NaiveDate,
+}
+
+impl PeriodData {
+    /// Starts on the current week and quarter. The custom range covers the current month so far.
+    #[must_use]
+    pub fn new() -> Self {
+        let today = Local::now().date_naive();
+
+        PeriodData {
+            date: today,
+            custom_start: today.with_day(1).unwrap(),
+            custom_end: today,
+        }
+    }
+
+    /// Whether the mode is navigated by period instead of the month and year tabs
+    #[must_use]
+    pub fn is_period_mode(mode: &str) -> bool {
+        matches!(mode, "Weekly" | "Quarterly" | "Custom")
+    }
+
+    /// The fetch nature of a mode name. An unknown mode falls back to Monthly
+    #[must_use]
+    pub fn fetch_nature(&self, mode: &str) -> FetchNature {
+        match mode {
+            "Monthly" => FetchNature::Monthly,
+            "Yearly" => FetchNature::Yearly,
+            "All Time" => FetchNature::All,
+            "Weekly" => FetchNature::Weekly,
+            "Quarterly" => FetchNature::Quarterly,
+            "Custom" => FetchNature::Custom(self.custom_start, self.custom_end),
+            _ => FetchNature::Monthly,
+        }
+    }
+
+    /// Moves to the period right after the current one
+    pub fn next(&mut self, mode: &str) {
+        if let Some((date, nature)) = self.fetch_nature(mode).next_period(self.date) {
+            self.set_period(date, nature);
+        }
+    }
+
+    /// Moves to the period right before the current one
+    pub fn previous(&mut self, mode: &str) {
+        if let Some((date, nature)) = self.fetch_nature(mode).previous_period(self.date) {
+            self.set_period(date, nature);
+        }
+    }
+
+    fn set_period(&mut self, date: NaiveDate, nature: FetchNature) {
+        if let FetchNature::Custom(start_date, end_date) = nature {
+            self.set_custom(start_date, end_date);
+        } else {
+            self.date = date;
+        }
+    }
+
+    pub fn set_custom(&mut self, start_date: NaiveDate, end_date: NaiveDate) {
+        self.custom_start = start_date.min(end_date);
+        self.custom_end = start_date.max(end_date);
+    }
+
+    /// Creates a single value tab that shows the current period of the mode
+    #[must_use]
+    pub fn get_tab(&self, mode: &str) -> IndexedData {
+        let title = match self.fetch_nature(mode).date_range(self.date) {
+            Some((start_date, end_date)) => match mode {
+                "Weekly" => format!(
+                    "Week {}: {start_date} to {end_date}",
+                    start_date.iso_week().week()
+                ),
+                "Quarterly" => format!("Q{} {}", start_date.month0() / 3 + 1, start_date.year()),
+                _ => format!("{start_date} to {end_date}"),
+            },
+            None => String::new(),
+        };
+
+        IndexedData {
+            titles: vec![title],
+            index: 0,
+        }
+    }
 }
 
 /// The enum is used to keep track of which tab is currently set at active
@@ -220,6 +455,7 @@ impl HomeTab {
 /// is currently being interacted with.
 pub enum TxTab {
     Date,
+    Time,
     Details,
     FromMethod,
     ToMethod,
@@ -377,6 +613,48 @@ impl SummaryTab {
     }
 }
 
+/// The type of chart drawn on the Chart page
+pub enum ChartType {
+    /// Balance lines of each method
+    Balance,
+    /// Income and expense bars with a net line
+    CashFlow,
+    /// Monthly expense lines of the selected tags
+    TagTrend,
+}
+
+impl ChartType {
+    pub fn next_type(&mut self) -> Self {
+        match self {
+            ChartType::Balance => ChartType::CashFlow,
+            ChartType::CashFlow => ChartType::TagTrend,
+            ChartType::TagTrend => ChartType::Balance,
+        }
+    }
+}
+
+/// Selection status of the chart tags. Tags that existed before keep their previous status.
+/// The highest spending tags get selected if nothing else is
+#[must_use]
+pub fn chart_tag_activation(
+    tags: &IndexedData,
+    previous: &HashMap<String, bool>,
+) -> HashMap<String, bool> {
+    let mut activation: HashMap<String, bool> = tags
+        .titles
+        .iter()
+        .map(|tag| (tag.clone(), previous.get(tag).copied().unwrap_or(false)))
+        .collect();
+
+    if !activation.values().any(|active| *active) {
+        for tag in tags.titles.iter().take(DEFAULT_CHART_TAGS) {
+            activation.insert(tag.clone(), true);
+        }
+    }
+
+    activation
+}
+
 pub enum SortingType {
     Tags,
     Income,
@@ -148,7 +148,8 @@ pub fn activity_ui(
         .block(styled_block("TX Details", theme));
 
     let mut month_tab = create_tab(months, "Months", theme);
-    let mut year_tab = create_tab(years, "Years", theme);
+    let year_window = years.year_window();
+    let mut year_tab = create_tab(&year_window, "Years", theme);
 
     match current_tab {
         ActivityTab::Months => month_tab = month_tab.highlight_style(tab_highlight_style(theme)),
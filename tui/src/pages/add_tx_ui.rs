@@ -39,7 +39,7 @@ pub fn add_tx_ui(
     }
 
     let status_data = add_tx_data.get_tx_status();
-    // Contains date, details, from method, to method, amount, tx type, tags.
+    // Contains date, details, from method, to method, amount, tx type, tags, autofill and time.
     // Except to method, rest will be used for the widgets
     let input_data = add_tx_data.get_all_texts();
     // The index of the cursor position
@@ -98,6 +98,12 @@ pub fn add_tx_ui(
         }
     };
 
+    // The details chunk is shared with the time input box on the left
+    let details_chunk = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(12), Constraint::Min(0)])
+        .split(chunks[2]);
+
     // Creates border around the entire terminal
     f.render_widget(main_block(theme), size);
 
@@ -199,6 +205,8 @@ pub fn add_tx_ui(
     // We already fetched the data for each of these. Assign them now and then use them to load the widget
     let date_text = Line::from(format!("{} ", input_data[0]));
 
+    let time_text = Line::from(format!("{} ", input_data[8]));
+
     let mut details_text = Line::from(format!("{} ", input_data[1]));
 
     let mut from_method_text = Line::from(format!("{} ", input_data[2]));
@@ -255,6 +263,11 @@ pub fn add_tx_ui(
         .block(styled_block("Date", theme))
         .alignment(Alignment::Left);
 
+    let time_sec = Paragraph::new(time_text)
+        .style(Style::default().bg(theme.background()).fg(theme.text()))
+        .block(styled_block("Time", theme))
+        .alignment(Alignment::Left);
+
     let from_method_sec = Paragraph::new(from_method_text)
         .style(Style::default().bg(theme.background()).fg(theme.text()))
         .block(styled_block(from_method_name, theme))
@@ -280,9 +293,19 @@ pub fn add_tx_ui(
         .block(styled_block("Details", theme))
         .alignment(Alignment::Left);
 
+    // Show the most likely tag along with the confidence next to the field title
+    let tags_title = match add_tx_data.tag_suggestion(conn) {
+        Some(suggestion) => format!(
+            "Tags · {} {:.0}%",
+            suggestion.name,
+            suggestion.confidence * 100.0
+        ),
+        None => "Tags".to_string(),
+    };
+
     let tags_sec = Paragraph::new(tags_text)
         .style(Style::default().bg(theme.background()).fg(theme.text()))
-        .block(styled_block("Tags", theme))
+        .block(styled_block(&tags_title, theme))
         .alignment(Alignment::Left);
 
     // We will be adding a cursor based on which tab is selected + the selected index.
@@ -292,9 +315,13 @@ pub fn add_tx_ui(
             x: input_chunk[0].x + current_index as u16 + 1,
             y: input_chunk[0].y + 1,
         }),
+        TxTab::Time => f.set_cursor_position(Position {
+            x: details_chunk[0].x + current_index as u16 + 1,
+            y: details_chunk[0].y + 1,
+        }),
         TxTab::Details => f.set_cursor_position(Position {
-            x: chunks[2].x + current_index as u16 + 1,
-            y: chunks[2].y + 1,
+            x: details_chunk[1].x + current_index as u16 + 1,
+            y: details_chunk[1].y + 1,
         }),
         TxTab::TxType => f.set_cursor_position(Position {
             x: input_chunk[1].x + current_index as u16 + 1,
@@ -339,7 +366,8 @@ pub fn add_tx_ui(
     }
 
     f.render_widget(balance_area, chunks[0]);
-    f.render_widget(details_sec, chunks[2]);
+    f.render_widget(time_sec, details_chunk[0]);
+    f.render_widget(details_sec, details_chunk[1]);
     f.render_widget(status_sec, chunks[3]);
     f.render_widget(date_sec, input_chunk[0]);
     f.render_widget(tx_type_sec, input_chunk[1]);
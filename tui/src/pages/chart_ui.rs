@@ -1,4 +1,4 @@
-use chrono::{Duration, naive::NaiveDate};
+use chrono::{Duration, Months, naive::NaiveDate};
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Direction, Layout};
 use ratatui::style::{Color, Style};
@@ -6,28 +6,337 @@ use ratatui::symbols::Marker;
 use ratatui::text::Span;
 use ratatui::widgets::{Axis, Block, Chart, Dataset, GraphType};
 use rex_app::conn::DbConn;
-use rex_app::views::ChartView;
+use rex_app::views::{ChartView, ForecastView};
 use std::collections::HashMap;
 
-use crate::page_handler::{ChartTab, IndexedData};
+use crate::page_handler::{ChartTab, ChartType, IndexedData, PeriodData};
 use crate::theme::Theme;
 use crate::utility::{
     LerpState, create_tab, create_tab_activation, main_block, tab_highlight_style,
 };
 
-/// Creates the balance chart from the transactions
+/// Number of dashes the forecast part of the chart is split into
+const FORECAST_DASHES: f64 = 40.0;
+
+/// Turns the forecast line points into short line segments with gaps between them
+/// so it can be drawn as a dashed line
+fn dashed_segments(points: &[(f64, f64)]) -> Vec<Vec<(f64, f64)>> {
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        return Vec::new();
+    };
+
+    let dash_length = ((last.0 - first.0) / FORECAST_DASHES / 2.0).max(0.5);
+
+    // The y value at the given x by following the line between the points
+    let value_at = |x: f64| {
+        for pair in points.windows(2) {
+            let ((x1, y1), (x2, y2)) = (pair[0], pair[1]);
+
+            if x <= x2 {
+                if x2 == x1 {
+                    return y2;
+                }
+                return y1 + (y2 - y1) * (x - x1) / (x2 - x1);
+            }
+        }
+        last.1
+    };
+
+    let mut segments = Vec::new();
+    let mut dash_start = first.0;
+
+    while dash_start < last.0 {
+        let dash_end = (dash_start + dash_length).min(last.0);
+
+        segments.push(vec![
+            (dash_start, value_at(dash_start)),
+            (dash_end, value_at(dash_end)),
+        ]);
+
+        dash_start += dash_length * 2.0;
+    }
+
+    segments
+}
+
+fn runway_text(runway: Option<u32>) -> String {
+    match runway {
+        Some(0) => "negative".to_string(),
+        Some(1) => "1 month".to_string(),
+        Some(months) => format!("{months} months"),
+        None => "never".to_string(),
+    }
+}
+
+/// Number of points each cash flow bar is drawn with to make it wider
+const BAR_POINTS: usize = 4;
+
+/// Points of the cash flow chart. Each group is placed on a whole number x position with the
+/// income bar on the left, the expense bar on the right and the net line going through the middle
+struct CashFlowPoints {
+    income: Vec<(f64, f64)>,
+    expense: Vec<(f64, f64)>,
+    net: Vec<(f64, f64)>,
+    labels: Vec<String>,
+    lowest: f64,
+    highest: f64,
+}
+
+impl CashFlowPoints {
+    fn new(chart_view: &ChartView) -> Self {
+        let cash_flow = chart_view.cash_flow();
+
+        let mut points = CashFlowPoints {
+            income: Vec::new(),
+            expense: Vec::new(),
+            net: Vec::new(),
+            labels: Vec::new(),
+            lowest: 0.0,
+            highest: 0.0,
+        };
+
+        for (index, group) in cash_flow.iter().enumerate() {
+            let x = index as f64;
+
+            let income = group.income().dollar().value();
+            let expense = group.expense().dollar().value();
+            let net = group.net().dollar().value();
+
+            for point in 0..BAR_POINTS {
+                let shift = 0.05 + 0.35 * point as f64 / (BAR_POINTS - 1) as f64;
+
+                points.income.push((x - shift, income));
+                points.expense.push((x + shift, expense));
+            }
+
+            points.net.push((x, net));
+
+            points.highest = points.highest.max(income).max(expense).max(net);
+            points.lowest = points.lowest.min(net);
+        }
+
+        if let (Some(first), Some(last)) = (cash_flow.first(), cash_flow.last()) {
+            points.labels.push(first.label().to_string());
+            points.labels.push(last.label().to_string());
+        }
+
+        points.highest += points.highest * 5.0 / 100.0;
+        points.lowest += points.lowest * 5.0 / 100.0;
+
+        points
+    }
+
+    fn chart(&self, chart_hidden_legends: bool, theme: &Theme) -> Chart<'_> {
+        let style = |color: Color| Style::default().fg(color).bg(theme.background());
+
+        let mut income = Dataset::default()
+            .marker(Marker::HalfBlock)
+            .graph_type(GraphType::Bar)
+            .style(style(Color::LightGreen))
+            .data(&self.income);
+
+        let mut expense = Dataset::default()
+            .marker(Marker::HalfBlock)
+            .graph_type(GraphType::Bar)
+            .style(style(Color::LightRed))
+            .data(&self.expense);
+
+        let mut net = Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(style(Color::LightBlue))
+            .data(&self.net);
+
+        if !chart_hidden_legends {
+            income = income.name("Income");
+            expense = expense.name("Expense");
+            net = net.name("Net");
+        }
+
+        let diff = (self.highest - self.lowest) / 10.0;
+
+        let y_labels: Vec<Span> = (0..=10)
+            .map(|i| Span::from(format!("{:.2}", self.lowest + diff * f64::from(i))))
+            .collect();
+
+        let x_end = (self.net.len().max(1) - 1) as f64 + 0.5;
+
+        Chart::new(vec![income, expense, net])
+            .block(
+                Block::default().style(Style::default().bg(theme.background()).fg(theme.border())),
+            )
+            .style(Style::default().bg(theme.background()).fg(theme.border()))
+            .x_axis(
+                Axis::default()
+                    .style(Style::default().bg(theme.background()).fg(theme.border()))
+                    .bounds([-0.5, x_end])
+                    .labels(
+                        self.labels
+                            .iter()
+                            .cloned()
+                            .map(Span::from)
+                            .collect::<Vec<_>>(),
+                    ),
+            )
+            .y_axis(
+                Axis::default()
+                    .style(Style::default().bg(theme.background()).fg(theme.border()))
+                    .bounds([self.lowest, self.highest])
+                    .labels(y_labels),
+            )
+    }
+}
+
+/// Colors of the tag trend lines in the order of the selected tags. Cyan once it runs out
+const TAG_COLORS: [Color; 8] = [
+    Color::LightRed,
+    Color::LightBlue,
+    Color::LightYellow,
+    Color::LightGreen,
+    Color::LightMagenta,
+    Color::Gray,
+    Color::Yellow,
+    Color::Green,
+];
+
+/// Points of the tag trend chart. Each month is placed on a whole number x position
+/// with one line for every selected tag
+struct TagTrendPoints {
+    lines: Vec<(String, Vec<(f64, f64)>)>,
+    labels: Vec<String>,
+    highest: f64,
+}
+
+impl TagTrendPoints {
+    fn new(
+        chart_view: &ChartView,
+        chart_tags: &IndexedData,
+        chart_activated_tags: &HashMap<String, bool>,
+    ) -> Self {
+        let mut tag_months = chart_view.tag_monthly_expense();
+
+        let mut points = TagTrendPoints {
+            lines: Vec::new(),
+            labels: Vec::new(),
+            highest: 0.0,
+        };
+
+        // Follow the tag selection order so the colors stay the same between reloads
+        for tag in &chart_tags.titles {
+            if !chart_activated_tags.get(tag).copied().unwrap_or(false) {
+                continue;
+            }
+
+            let Some(months) = tag_months.remove(tag) else {
+                continue;
+            };
+
+            if points.labels.is_empty()
+                && let (Some(first), Some(last)) = (months.keys().next(), months.keys().last())
+            {
+                points.labels.push(first.format("%Y-%m").to_string());
+                points.labels.push(last.format("%Y-%m").to_string());
+            }
+
+            let line: Vec<(f64, f64)> = months
+                .values()
+                .enumerate()
+                .map(|(index, expense)| (index as f64, expense.dollar().value()))
+                .collect();
+
+            for (_, expense) in &line {
+                points.highest = points.highest.max(*expense);
+            }
+
+            points.lines.push((tag.clone(), line));
+        }
+
+        points.highest += points.highest * 5.0 / 100.0;
+
+        points
+    }
+
+    fn chart(&self, chart_hidden_legends: bool, theme: &Theme) -> Chart<'_> {
+        let mut datasets = Vec::new();
+
+        for (index, (tag, line)) in self.lines.iter().enumerate() {
+            let color = TAG_COLORS.get(index).copied().unwrap_or(Color::Cyan);
+
+            // A line needs at least 2 points to be visible
+            let graph_type = if line.len() > 1 {
+                GraphType::Line
+            } else {
+                GraphType::Scatter
+            };
+
+            let mut dataset = Dataset::default()
+                .marker(Marker::Braille)
+                .graph_type(graph_type)
+                .style(Style::default().fg(color).bg(theme.background()))
+                .data(line);
+
+            if !chart_hidden_legends {
+                dataset = dataset.name(tag.as_str());
+            }
+
+            datasets.push(dataset);
+        }
+
+        let diff = self.highest / 10.0;
+
+        let y_labels: Vec<Span> = (0..=10)
+            .map(|i| Span::from(format!("{:.2}", diff * f64::from(i))))
+            .collect();
+
+        let x_end = self
+            .lines
+            .first()
+            .map_or(0.0, |(_, line)| line.len().saturating_sub(1) as f64);
+
+        Chart::new(datasets)
+            .block(
+                Block::default().style(Style::default().bg(theme.background()).fg(theme.border())),
+            )
+            .style(Style::default().bg(theme.background()).fg(theme.border()))
+            .x_axis(
+                Axis::default()
+                    .style(Style::default().bg(theme.background()).fg(theme.border()))
+                    .bounds([0.0, x_end])
+                    .labels(
+                        self.labels
+                            .iter()
+                            .cloned()
+                            .map(Span::from)
+                            .collect::<Vec<_>>(),
+                    ),
+            )
+            .y_axis(
+                Axis::default()
+                    .style(Style::default().bg(theme.background()).fg(theme.border()))
+                    .bounds([0.0, self.highest])
+                    .labels(y_labels),
+            )
+    }
+}
+
+/// Creates the balance, the cash flow or the tag trend chart from the transactions
 pub fn chart_ui(
     f: &mut Frame,
     months: &IndexedData,
     years: &IndexedData,
     mode_selection: &IndexedData,
+    period: &PeriodData,
     chart_tx_methods: &IndexedData,
     current_page: &ChartTab,
     chart_hidden_mode: bool,
     chart_hidden_legends: bool,
+    chart_type: &ChartType,
+    chart_tags: &IndexedData,
+    chart_activated_tags: &HashMap<String, bool>,
     chart_activated_methods: &HashMap<String, bool>,
     lerp_state: &mut LerpState,
     chart_view: &ChartView,
+    forecast: Option<(&ForecastView, u32)>,
     theme: &Theme,
     conn: &mut DbConn,
 ) {
@@ -55,11 +364,11 @@ pub fn chart_ui(
                     Constraint::Min(0),
                 ]);
             }
-            1 => {
+            1 | 3..=5 => {
                 main_layout = main_layout.constraints([
                     // Modes
                     Constraint::Length(3),
-                    // Years
+                    // Years or Period
                     Constraint::Length(3),
                     // Tx method
                     Constraint::Length(3),
@@ -88,16 +397,31 @@ pub fn chart_ui(
 
     let mut month_tab = create_tab(months, "Months", theme);
 
-    let mut year_tab = create_tab(years, "Years", theme);
+    let mode = mode_selection.get_selected_value();
+    let period_tab = period.get_tab(mode);
+    let year_window = years.year_window();
+
+    let mut year_tab = if PeriodData::is_period_mode(mode) {
+        create_tab(&period_tab, "Period", theme)
+    } else {
+        create_tab(&year_window, "Years", theme)
+    };
 
     let mut mode_selection_tab = create_tab(mode_selection, "Modes", theme);
 
-    let mut tx_method_selection_tab = create_tab_activation(
-        chart_tx_methods,
-        "Tx Method Selection",
-        chart_activated_methods,
-        theme,
-    );
+    let tag_window = chart_tags.tag_window();
+
+    // The tag trend chart selects tags instead of methods
+    let mut tx_method_selection_tab = if let ChartType::TagTrend = chart_type {
+        create_tab_activation(&tag_window, "Tag Selection", chart_activated_tags, theme)
+    } else {
+        create_tab_activation(
+            chart_tx_methods,
+            "Tx Method Selection",
+            chart_activated_methods,
+            theme,
+        )
+    };
 
     let tx_methods = conn.get_tx_methods_sorted();
 
@@ -124,6 +448,9 @@ pub fn chart_ui(
 
     let mut current_axis = 0.0;
 
+    // Whether the chart animation has reached the last date
+    let mut chart_completed = false;
+
     // If there are no transactions, we will create an empty chart
     if !chart_view.is_empty() {
         let mut checking_date = chart_view.start_date();
@@ -235,6 +562,7 @@ pub fn chart_ui(
                 if to_loop <= 0.0 {
                     date_labels.pop().unwrap();
                     date_labels.push(checking_date.to_string());
+                    chart_completed = checking_date > final_date;
                     break;
                 }
                 to_loop += -1.0;
@@ -245,6 +573,82 @@ pub fn chart_ui(
             }
         }
     }
+    let mut last_axis = current_axis - 1.0;
+
+    // The dashed forecast lines of each method index
+    let mut forecast_datasets: Vec<Vec<Vec<(f64, f64)>>> = Vec::new();
+    let mut runway_title = String::new();
+
+    if let Some((forecast, months)) = forecast
+        && chart_completed
+    {
+        let chart_end = forecast.date();
+        let mut runways = Vec::new();
+
+        for method_index in 0..all_tx_methods.len() {
+            let is_cumulative = method_index == all_tx_methods.len() - 1;
+
+            let Some(start_point) = datasets[method_index].last().copied() else {
+                forecast_datasets.push(Vec::new());
+                continue;
+            };
+
+            let mut points = vec![start_point];
+
+            for month in 1..=months {
+                let projected_balance = if is_cumulative {
+                    tx_methods
+                        .iter()
+                        .map(|method| forecast.projected_balance(method.id, month).value())
+                        .sum()
+                } else {
+                    forecast
+                        .projected_balance(tx_methods[method_index].id, month)
+                        .value()
+                };
+
+                let point_date = chart_end + Months::new(month);
+                let x =
+                    start_point.0 + point_date.signed_duration_since(chart_end).num_days() as f64;
+
+                points.push((x, projected_balance as f64 / 100.0));
+            }
+
+            if chart_activated_methods[all_tx_methods[method_index]] {
+                for (_, balance) in &points {
+                    if *balance > highest_balance {
+                        highest_balance = *balance;
+                    } else if *balance < lowest_balance {
+                        lowest_balance = *balance;
+                    }
+                }
+
+                let runway = if is_cumulative {
+                    forecast.total_runway()
+                } else {
+                    forecast.runway(tx_methods[method_index].id)
+                };
+
+                runways.push(format!(
+                    "{}: {}",
+                    all_tx_methods[method_index],
+                    runway_text(runway)
+                ));
+            }
+
+            if let Some((x, _)) = points.last() {
+                last_axis = last_axis.max(*x);
+            }
+
+            forecast_datasets.push(dashed_segments(&points));
+        }
+
+        date_labels.pop();
+        date_labels.push((chart_end + Months::new(months)).to_string());
+
+        runway_title = format!(" Runway - {} ", runways.join(" | "));
+    }
+
     // Add a few % extra value to the highest and the lowest balance
     // so the chart can properly render
     highest_balance += highest_balance * 5.0 / 100.0;
@@ -288,14 +692,14 @@ pub fn chart_ui(
             continue;
         }
 
+        let line_style = Style::default()
+            .fg(color_list.pop().unwrap())
+            .bg(theme.background());
+
         let mut dataset = Dataset::default()
             .marker(Marker::Braille)
             .graph_type(GraphType::Line)
-            .style(
-                Style::default()
-                    .fg(color_list.pop().unwrap())
-                    .bg(theme.background()),
-            )
+            .style(line_style)
             .data(&datasets[i]);
 
         if !chart_hidden_legends {
@@ -303,37 +707,65 @@ pub fn chart_ui(
         }
 
         final_dataset.push(dataset);
+
+        // Forecast dashes use the same color as the method without any legend
+        if let Some(segments) = forecast_datasets.get(i) {
+            for segment in segments {
+                final_dataset.push(
+                    Dataset::default()
+                        .marker(Marker::Braille)
+                        .graph_type(GraphType::Line)
+                        .style(line_style)
+                        .data(segment),
+                );
+            }
+        }
     }
 
-    let chart = Chart::new(final_dataset)
-        .block(Block::default().style(Style::default().bg(theme.background()).fg(theme.border())))
-        .style(Style::default().bg(theme.background()).fg(theme.border()))
-        .x_axis(
-            Axis::default()
-                .title(Span::styled(
-                    "",
-                    Style::default().bg(theme.background()).fg(theme.border()),
-                ))
-                .style(Style::default().bg(theme.background()).fg(theme.border()))
-                .bounds([0.0, current_axis - 1.0])
-                .labels(
-                    date_labels
-                        .iter()
-                        .cloned()
-                        .map(Span::from)
-                        .collect::<Vec<_>>(),
-                ),
-        )
-        .y_axis(
-            Axis::default()
-                .title(Span::styled(
-                    "",
-                    Style::default().bg(theme.background()).fg(theme.border()),
-                ))
-                .style(Style::default().bg(theme.background()).fg(theme.border()))
-                .bounds([lowest_balance, highest_balance])
-                .labels(labels.iter().cloned().map(Span::from).collect::<Vec<_>>()),
-        );
+    let cash_flow_points;
+    let tag_trend_points;
+
+    let chart = if let ChartType::CashFlow = chart_type {
+        cash_flow_points = CashFlowPoints::new(chart_view);
+        cash_flow_points.chart(chart_hidden_legends, theme)
+    } else if let ChartType::TagTrend = chart_type {
+        tag_trend_points = TagTrendPoints::new(chart_view, chart_tags, chart_activated_tags);
+        tag_trend_points.chart(chart_hidden_legends, theme)
+    } else {
+        Chart::new(final_dataset)
+            .block(
+                Block::default()
+                    .title(runway_title)
+                    .style(Style::default().bg(theme.background()).fg(theme.border())),
+            )
+            .style(Style::default().bg(theme.background()).fg(theme.border()))
+            .x_axis(
+                Axis::default()
+                    .title(Span::styled(
+                        "",
+                        Style::default().bg(theme.background()).fg(theme.border()),
+                    ))
+                    .style(Style::default().bg(theme.background()).fg(theme.border()))
+                    .bounds([0.0, last_axis])
+                    .labels(
+                        date_labels
+                            .iter()
+                            .cloned()
+                            .map(Span::from)
+                            .collect::<Vec<_>>(),
+                    ),
+            )
+            .y_axis(
+                Axis::default()
+                    .title(Span::styled(
+                        "",
+                        Style::default().bg(theme.background()).fg(theme.border()),
+                    ))
+                    .style(Style::default().bg(theme.background()).fg(theme.border()))
+                    .bounds([lowest_balance, highest_balance])
+                    .labels(labels.iter().cloned().map(Span::from).collect::<Vec<_>>()),
+            )
+    };
 
     match current_page {
         ChartTab::Months => month_tab = month_tab.highlight_style(tab_highlight_style(theme)),
@@ -359,7 +791,7 @@ pub fn chart_ui(
                 f.render_widget(tx_method_selection_tab, chunks[3]);
                 f.render_widget(chart, chunks[4]);
             }
-            1 => {
+            1 | 3..=5 => {
                 f.render_widget(year_tab, chunks[1]);
                 f.render_widget(tx_method_selection_tab, chunks[2]);
                 f.render_widget(chart, chunks[3]);
@@ -0,0 +1,31 @@
+use ratatui::Frame;
+use ratatui::layout::{Alignment, Constraint, Direction, Layout};
+use ratatui::widgets::{Paragraph, Wrap};
+
+use crate::theme::Theme;
+use crate::utility::{create_bolded_text, main_block, styled_block};
+
+/// Draws a full screen error for failures that stop the app from starting or running
+pub fn error_ui(f: &mut Frame, message: &str, theme: &Theme) {
+    let size = f.area();
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([Constraint::Min(5), Constraint::Length(3)])
+        .split(size);
+
+    f.render_widget(main_block(theme), size);
+
+    let error_text = Paragraph::new(create_bolded_text(message))
+        .block(styled_block("Error", theme))
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: true });
+
+    let help_text = Paragraph::new(create_bolded_text("R: Retry. Q: Quit."))
+        .block(styled_block("", theme))
+        .alignment(Alignment::Center);
+
+    f.render_widget(error_text, chunks[0]);
+    f.render_widget(help_text, chunks[1]);
+}
@@ -6,7 +6,7 @@ use rex_app::conn::DbConn;
 use rex_app::views::TxViewGroup;
 use thousands::Separable;
 
-use crate::page_handler::{HomeRow, HomeTab, IndexedData, TableData};
+use crate::page_handler::{HomeRow, HomeTab, IndexedData, PeriodData, TableData};
 use crate::theme::Theme;
 use crate::utility::{LerpState, create_tab, main_block, styled_block, tab_highlight_style};
 
@@ -27,6 +27,8 @@ pub fn home_ui(
     f: &mut Frame,
     months: &IndexedData,
     years: &IndexedData,
+    modes: &IndexedData,
+    period: &PeriodData,
     home_table: &mut TableData,
     current_tab: &HomeTab,
     lerp_state: &mut LerpState,
@@ -106,8 +108,8 @@ pub fn home_ui(
 
     // Chunks are used in this format respectively
     // - The Balance tab
-    // - The year tab
-    // - The month tab
+    // - The year tab or the period tab on Weekly, Quarterly and Custom mode
+    // - The month tab or the mode tab on Weekly, Quarterly and Custom mode
     // - The transaction list/Table
 
     let chunks = Layout::default()
@@ -123,9 +125,21 @@ pub fn home_ui(
 
     f.render_widget(main_block(theme), size);
 
-    let mut month_tab = create_tab(months, "Months", theme);
-
-    let mut year_tab = create_tab(years, "Years", theme);
+    let mode = modes.get_selected_value();
+    let period_tab = period.get_tab(mode);
+    let year_window = years.year_window();
+
+    let (mut month_tab, mut year_tab) = if PeriodData::is_period_mode(mode) {
+        (
+            create_tab(modes, "Modes", theme),
+            create_tab(&period_tab, "Period", theme),
+        )
+    } else {
+        (
+            create_tab(months, "Months", theme),
+            create_tab(&year_window, "Years", theme),
+        )
+    };
 
     // set up the table columns and their size
     // resizing the table headers to match an % of the
@@ -1,17 +1,21 @@
 mod activity_ui;
 mod add_tx_ui;
 mod chart_ui;
+mod error_ui;
 mod home_ui;
 mod initial_ui;
 mod popups;
+mod profile_ui;
 mod search_ui;
 mod summary_ui;
 
 pub use activity_ui::*;
 pub use add_tx_ui::*;
 pub use chart_ui::*;
+pub use error_ui::*;
 pub use home_ui::*;
 pub use initial_ui::*;
 pub use popups::*;
+pub use profile_ui::*;
 pub use search_ui::*;
 pub use summary_ui::*;
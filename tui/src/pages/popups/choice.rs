@@ -19,7 +19,7 @@ impl ChoicePopup {
 
         let constraints;
 
-        match self.showing {
+        match &self.showing {
             ChoicePopupState::Delete => {
                 title = "Transaction Deletion";
                 message = "Are you sure you want to delete this transaction?";
@@ -34,25 +34,25 @@ impl ChoicePopup {
                 title = "Configuration";
                 message = "Select an option to configure";
 
-                y_value = 12;
+                y_value = 15;
 
                 constraints = vec![
                     Constraint::Length(4),
                     Constraint::Min(1),
-                    Constraint::Length(7),
+                    Constraint::Length(10),
                 ];
             }
             ChoicePopupState::ConfigForced => {
                 title = "Configuration";
                 message = "Please add at least 1 Transaction Method to get started. Example Transaction Method: Bank, Cash, Paypal";
 
-                y_value = 10;
+                y_value = 11;
                 x_value = 60;
 
                 constraints = vec![
                     Constraint::Length(4),
                     Constraint::Min(1),
-                    Constraint::Length(3),
+                    Constraint::Length(4),
                 ];
             }
             ChoicePopupState::TxMethods => {
@@ -71,6 +71,32 @@ impl ChoicePopup {
                     Constraint::Length((self.table.items.len() + 2) as u16),
                 ];
             }
+            ChoicePopupState::Backups(_) => {
+                title = "Restore Backup";
+                message = "Select a backup to preview the changes before restoring";
+
+                x_value = 100;
+                y_value = (5 + self.table.items.len() as u16 + 2).min(20);
+
+                constraints = vec![
+                    Constraint::Length(4),
+                    Constraint::Length(1),
+                    Constraint::Min(3),
+                ];
+            }
+            ChoicePopupState::Profiles(_) => {
+                title = "Switch Profile";
+                message = "Select a profile to open. Changes are saved before switching";
+
+                x_value = 50;
+                y_value = (5 + self.table.items.len() as u16 + 2).min(20);
+
+                constraints = vec![
+                    Constraint::Length(4),
+                    Constraint::Length(1),
+                    Constraint::Min(3),
+                ];
+            }
         }
 
         let title = Span::styled(title, Style::default().add_modifier(Modifier::BOLD));
@@ -1,3 +1,9 @@
+use rex_app::backup::{BackupFile, BackupSummary, TxDiff};
+use std::fs;
+
+use crate::config::Config;
+use crate::page_handler::BackupStatus;
+
 pub const F: &str = "F: Home Page";
 pub const A: &str = "A: Add Transaction Page";
 pub const R: &str = "R: Chart Page";
@@ -9,6 +15,154 @@ pub const H: &str = "H: Show help";
 pub const V: &str = "V: Show selected transaction details";
 pub const J: &str = "J: Configuration";
 pub const T: &str = "T: Cycle through themes";
+pub const C: &str = "C: Select a custom date range. Example: 2024-01-01 2024-03-31";
+
+/// Lists the timestamped backups of every backup path with the retention in use and the
+/// outcome of the last backup made during the session
+pub fn backups_text(config: &Config, status: Option<BackupStatus>) -> String {
+    let backups = config.backups();
+
+    let retention = config.backup_retention.unwrap_or_default();
+
+    let mut text = if let Some(paths) = &config.backup_db_path {
+        format!(
+            "Retention: Last {}, {} daily, {} weekly and {} monthly backups per path
+Backup Paths: {}\n",
+            retention.keep_last,
+            retention.daily,
+            retention.weekly,
+            retention.monthly,
+            paths
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    } else if backups.is_empty() {
+        return "No backup path is set. Set one from the configuration first".to_string();
+    } else {
+        "No backup path is set. Only the copies saved before a restore are available\n".to_string()
+    };
+
+    match status {
+        Some(BackupStatus::Saved(time)) => text.push_str(&format!(
+            "Last automatic backup: {}\n",
+            time.format("%Y-%m-%d %H:%M:%S")
+        )),
+        Some(BackupStatus::Failed(time, e)) => text.push_str(&format!(
+            "Last automatic backup failed at {}. Error: {e}\n",
+            time.format("%Y-%m-%d %H:%M:%S")
+        )),
+        None => {}
+    }
+
+    if backups.is_empty() {
+        text.push_str("\nNo backup has been made yet. A backup is made on every exit and during long sessions");
+    }
+
+    for (index, backup) in backups.iter().enumerate() {
+        let size = fs::metadata(&backup.path).map_or(0, |m| m.len());
+        let encrypted = if backup.encrypted { ", Encrypted" } else { "" };
+        let safety = if backup.safety { ", Safety copy" } else { "" };
+
+        text.push_str(&format!(
+            "\nBackup {}: {}, {} KB{encrypted}{safety}\nPath: {}",
+            index + 1,
+            backup.created.format("%Y-%m-%d %H:%M:%S"),
+            size.div_ceil(1024),
+            backup.path.display(),
+        ));
+    }
+
+    text
+}
+
+/// Balances of a backup in a single line
+pub fn balances_line(summary: &BackupSummary) -> String {
+    let mut balances: Vec<String> = summary
+        .balances
+        .methods
+        .iter()
+        .map(|method| format!("{} {:.2}", method.method, method.balance))
+        .collect();
+
+    balances.push(format!("Total {:.2}", summary.balances.total));
+
+    balances.join(", ")
+}
+
+fn tx_line(fields: Vec<String>) -> String {
+    fields
+        .into_iter()
+        .filter(|field| !field.is_empty())
+        .collect::<Vec<String>>()
+        .join(" | ")
+}
+
+/// Shows the backup along with the txs that change once it is restored
+pub fn restore_preview_text(
+    backup: &BackupFile,
+    summary: &BackupSummary,
+    diff: &TxDiff,
+    safety_copy: bool,
+) -> String {
+    let encrypted = if backup.encrypted { ", Encrypted" } else { "" };
+
+    let mut text = format!(
+        "Backup: {}{encrypted}
+Path: {}
+Transactions: {}
+Balances: {}
+",
+        backup.created.format("%Y-%m-%d %H:%M:%S"),
+        backup.path.display(),
+        summary.tx_count,
+        balances_line(summary),
+    );
+
+    if diff.is_empty() {
+        text.push_str("\nThe transactions are the same as the current database\n");
+    }
+
+    if !diff.added.is_empty() {
+        text.push_str(&format!(
+            "\nTransactions added back: {}\n",
+            diff.added.len()
+        ));
+
+        for tx in &diff.added {
+            text.push_str(&format!("+ {}\n", tx_line(tx.to_array(true))));
+        }
+    }
+
+    if !diff.removed.is_empty() {
+        text.push_str(&format!("\nTransactions removed: {}\n", diff.removed.len()));
+
+        for tx in &diff.removed {
+            text.push_str(&format!("- {}\n", tx_line(tx.to_array(true))));
+        }
+    }
+
+    if !diff.changed.is_empty() {
+        text.push_str(&format!("\nTransactions changed: {}\n", diff.changed.len()));
+
+        for (current, restored) in &diff.changed {
+            text.push_str(&format!(
+                "~ {}\n  → {}\n",
+                tx_line(current.to_array(true)),
+                tx_line(restored.to_array(true))
+            ));
+        }
+    }
+
+    if safety_copy {
+        text.push_str("\nA copy of the current database is saved before restoring");
+    }
+
+    text.push_str("\nEnter: Restore this backup");
+
+    text
+}
 
 pub fn new_update_text(data: &[String]) -> String {
     format!(
@@ -23,15 +177,16 @@ Enter: Redirect to the new version",
 pub fn add_tx_help_text() -> String {
     format!(
         "This page is for adding new transactions. Following are the supported keys here. \
-On Transfer transaction there will be one additional field pushing Tags to the key 7.
+On Transfer transaction there will be one additional field pushing Tags to the key 7 and Time to the key 8.
 
-1: Date         Example: 2022-05-12, YYYY-MM-DD
+1: Date         Example: 2022-05-12, today, yesterday, -3d, +1w, last friday, 15, 03-15
 2: TX details   Example: For Grocery, Salary
 5: TX Type      Example: Income/Expense/Transfer/I/E/T
 5: New TX Type  Example: Borrow/Borrow Repay/Lend/Lend Repay/b/br/l/lr
 3: TX Method    Example: Cash, Bank, Card
 4: Amount       Example: 1000, 100+50, b - 100
 6: Tags         Example: Food, Car. Add a Comma for a new tag
+7: Time         Example: 14:30, 930, 2:15pm. Optional, orders txs within the same day
 
 S: Save the inputted data as a Transaction
 Enter: Submit field and continue. Also selects the first field if nothing is selected
@@ -39,17 +194,20 @@ Esc: Stop editing field
 Tab: Accept Autocompletion. Pressing again will remove the autocompleted value
 
 Arrow Up/Down: Steps value up/down by 1 when available
+Shift/Ctrl + Arrow Up/Down: Steps the date by a week/month
 Arrow Left/Right: Move cursor on input fields
 
 C: Clear all fields/Reset all changes, including discarding a tx if was being edited
 b: On amount field 'b' gets replaced with the current balance of Tx Method field
 k: On amount field 'k' is considered as 1000 or a thousand
 m: On amount field 'm' is considered as 1,000,000 or a million
-Calculation: Amount field supports simple calculation with +, -, *, /
+Calculation: Amount field supports +, -, *, /, parentheses and percentages like 200 - 15%
 Tags: This field can be treated as the category of this transaction.
 Empty tags field gets replaced with Unknown. Separate more than 1 tags with a comma
+Tag suggestion: The most likely tag based on past txs is shown next to the Tags title.
+Press Tab on an empty Tags field to accept it
 
-Example amount: 100 + b, b + b, 5 * b, 1.2k + 1m
+Example amount: 100 + b, b + b, 5 * b, 1.2k + 1m, (b - 50) / 2
 
 {F}
 {T}
@@ -72,11 +230,25 @@ Following are the supported keys here
 
 r (Lower case): Shows/Hides the top widgets for full chart view
 R (Upper case): Shows/Hides the chart legends
-Space: Enable/Disable tx method from the chart
+P: Cycle the balance forecast through 1, 3 and 6 months and off
+B: Cycle between the balance, cash flow and tag trend chart
+Space: Enable/Disable tx method or tag from the chart
+{C}
 
 Arrow Up/Down: Cycle widgets
 Arrow Left/Right: Move value of the widget
 
+Weekly, Quarterly and Custom modes show a Period widget. Arrow Left/Right on it moves to the previous/next period
+
+The forecast continues each balance with a dashed line using the average monthly movement
+of the 6 months before the last chart date. Runway shows the months until a balance goes negative
+
+The cash flow chart shows the income and expense bars of all methods with the net as a line.
+The bars are per day in Monthly and Weekly, per month in Yearly and Quarterly and per year in All Time
+
+The tag trend chart shows the monthly expense of each selected tag. The Tx Method widget turns
+into a Tag Selection widget where the highest spending tags are selected by default
+
 {F}
 {T}
 {A}
@@ -99,10 +271,25 @@ Following are the supported keys here
 
 X: Sorts table by Tag, Total Income or Total Expense
 Z: Hides the top widgets for full view
+M: Shows/Hides the month by month report of the selected year
+E: Exports the summary as Markdown or HTML or the month by month report as CSV
+P: Pins the selected period to compare with other periods. Press again to stop comparing
+{C}
 
 Arrow Up/Down: Cycle widgets/table value
 Arrow Left/Right: Move value of the widget
 
+Weekly, Quarterly and Custom modes show a Period widget. Arrow Left/Right on it moves to the previous/next period
+
+The month by month report has the income, expense, net and savings rate along with the net of each tag.
+Arrow Left/Right on the report table scrolls the month columns
+
+The exported summary is a single file with every summary table and tag distribution bars.
+Save it with a .html extension for HTML, any other extension saves it as Markdown
+
+While a period is pinned, select any other period with any mode to see it side by side with the pinned one.
+Each tag and method shows the income and expense of both periods with the change and the % change
+
 {F}
 {T}
 {A}
@@ -125,11 +312,15 @@ E: Edit the selected transaction on the table
 D: Delete the selected transaction on the table
 ,: Swaps the location of the selected transaction with the transaction above it
 .: Swaps the location of the selected transaction with the transaction below it
+P: Cycle through Monthly, Weekly, Quarterly and Custom modes
+{C}
 {V}
 
 Arrow Up/Down: Cycle widgets/table value
 Arrow Left/Right: Move value of the widget
 
+On Weekly, Quarterly and Custom modes the Years widget turns into the Period widget and the Months widget into the Modes widget
+
 Swapping transaction location will only work if they are on the same date. 
 
 {A}
@@ -150,7 +341,7 @@ pub fn search_help_text() -> String {
         "This page is for searching transactions. \
             On Transfer transaction there will be one additional field pushing Tags to the key 7.
 
-1: Date         Example: 2022-05-12, YYYY-MM-DD
+1: Date         Example: 2022-05-12, today, yesterday, -3d, +1w, last friday, 15, 03-15
 2: TX details   Example: For Grocery, Salary
 5: TX Type      Example: Income/Expense/Transfer/I/E/T
 5: New TX Type  Example: Borrow/Borrow Repay/Lend/Lend Repay/b/br/l/lr
@@ -168,12 +359,13 @@ Esc: Stop editing field
 Tab: Accept Autocompletion. Pressing again will remove the autocompleted value
 
 Arrow Up/Down: Steps value up/down by 1
+Shift/Ctrl + Arrow Up/Down: Steps an exact date by a week/month
 Arrow Left/Right: Move cursor on input fields
 C: Clear all fields
 b: On amount field 'b' gets replaced with the current balance of Tx Method field
 k: On amount field 'k' is considered as 1000 or a thousand
 m: On amount field 'm' is considered as 1,000,000 or a million
-Calculation: Amount field supports simple calculation with +, -, *, /
+Calculation: Amount field supports +, -, *, /, parentheses and percentages like 200 - 15%
 
 Example amount: 100 + b, b + b, 5 * b, 1.2k + 5m
 
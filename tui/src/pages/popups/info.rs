@@ -49,6 +49,12 @@ impl InfoPopup {
                 title = "Error";
                 message = err.clone();
             }
+            InfoPopupState::Info(info) => {
+                title = "Info";
+                message = info.clone();
+
+                y_value = 10;
+            }
             InfoPopupState::ShowDetails(details) => {
                 title = "Transaction Details";
                 message = details.clone();
@@ -56,6 +62,14 @@ impl InfoPopup {
                 x_value = 40;
                 y_value = 20;
             }
+            InfoPopupState::Backups(backups) => {
+                title = "Backups";
+                message = backups.clone();
+            }
+            InfoPopupState::RestorePreview(_, preview) => {
+                title = "Restore Backup";
+                message = preview.clone();
+            }
             InfoPopupState::ChoiceHelp => {
                 message = choice_help();
 
@@ -4,7 +4,7 @@ use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{BorderType, Borders, Clear, Paragraph};
 
-use crate::pages::InputPopup;
+use crate::pages::{InputPopup, InputPopupState};
 use crate::theme::Theme;
 use crate::utility::{centered_rect_exact, create_bolded_text, main_block, styled_block};
 
@@ -14,10 +14,11 @@ impl InputPopup {
         let x_value = 50;
         let y_value = 7;
 
-        let title = if self.modifying_method.is_none() {
-            "New Method"
-        } else {
-            "Rename to"
+        let (title, input_title) = match &self.showing {
+            InputPopupState::NewMethod => ("New Method", "Method name"),
+            InputPopupState::RenameMethod(_) => ("Rename to", "Method name"),
+            InputPopupState::CustomRange => ("Custom Range", "Start and end date"),
+            InputPopupState::NewProfile(_) => ("New Profile", "Profile name"),
         };
 
         let title = Span::styled(title, Style::default().add_modifier(Modifier::BOLD));
@@ -46,7 +47,7 @@ impl InputPopup {
 
         let input_section = Paragraph::new(input_text)
             .style(Style::default().bg(theme.background()).fg(theme.text()))
-            .block(styled_block("Method name", theme))
+            .block(styled_block(input_title, theme))
             .alignment(Alignment::Left);
 
         let status_section = Paragraph::new(status_text)
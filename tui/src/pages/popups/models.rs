@@ -1,14 +1,18 @@
 use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
 use ratatui::Frame;
 use ratatui::style::Color;
+use rex_app::backup::{BackupFile, OpenedBackup};
 use rex_app::conn::DbConn;
+use rex_app::crypto::EncryptedDb;
 use rfd::FileDialog;
 use std::path::PathBuf;
 use strum::IntoEnumIterator;
 use strum_macros::{Display, EnumIter, FromRepr};
 
-use crate::config::Config;
+use crate::config::{Config, invalid_profile_name};
 use crate::page_handler::TableData;
+use crate::pages::balances_line;
 use crate::theme::Theme;
 use crate::utility::{RESTRICTED, add_char_to};
 
@@ -33,7 +37,11 @@ pub enum InfoPopupState {
     ChoiceHelp,
     RepositionHelp,
     Error(String),
+    Info(String),
     ShowDetails(String),
+    Backups(String),
+    /// The backup to restore on Enter and what changes once it is restored
+    RestorePreview(BackupFile, String),
 }
 
 pub struct InfoPopup {
@@ -65,7 +73,16 @@ pub struct InputPopup {
     pub text: String,
     pub cursor_position: usize,
     pub status: String,
-    pub modifying_method: Option<String>,
+    pub showing: InputPopupState,
+}
+
+#[derive(Clone)]
+pub enum InputPopupState {
+    NewMethod,
+    RenameMethod(String),
+    CustomRange,
+    /// Existing profile names that cannot be used again
+    NewProfile(Vec<String>),
 }
 
 pub struct ChoiceDetails {
@@ -86,12 +103,16 @@ pub enum DeletionChoices {
     No,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 pub enum ChoicePopupState {
     Delete,
     Config,
     TxMethods,
     ConfigForced,
+    /// Backups in the same order as the table rows
+    Backups(Vec<BackupFile>),
+    /// Profile names in the same order as the table rows. The last row adds a new profile
+    Profiles(Vec<String>),
 }
 
 #[derive(EnumIter, Display, FromRepr, Copy, Clone)]
@@ -116,13 +137,28 @@ pub enum ConfigChoices {
     NewLocation,
     #[strum(to_string = "Set backup paths for app data")]
     BackupPaths,
+    #[strum(to_string = "List backups")]
+    ListBackups,
+    #[strum(to_string = "Restore from a backup")]
+    RestoreBackup,
+    #[strum(to_string = "Switch profile")]
+    SwitchProfile,
 }
 
+/// Shown while there is no transaction method. A new profile starts empty so switching back must
+/// stay possible
+const FORCED_CONFIG_CHOICES: [ConfigChoices; 2] =
+    [ConfigChoices::AddNewTxMethod, ConfigChoices::SwitchProfile];
+
 impl InfoPopup {
     pub fn is_new_update(&self) -> bool {
         let new_update = matches!(self.showing, InfoPopupState::NewUpdate(_));
         new_update
     }
+
+    pub fn is_restore_preview(&self) -> bool {
+        matches!(self.showing, InfoPopupState::RestorePreview(..))
+    }
 }
 
 impl DeletionChoices {
@@ -365,7 +401,10 @@ impl PopupType {
     }
 
     pub fn new_choice_config_forced(theme: &Theme) -> Self {
-        let choices = vec![ConfigChoices::AddNewTxMethod.to_choice(theme)];
+        let choices = FORCED_CONFIG_CHOICES
+            .iter()
+            .map(|c| c.to_choice(theme))
+            .collect::<Vec<ChoiceDetails>>();
 
         let table_items = choices.iter().map(|c| vec![c.text.clone()]).collect();
         let mut table_data = TableData::new(table_items);
@@ -380,6 +419,11 @@ impl PopupType {
 
     pub fn get_config_choice(&self) -> Option<ConfigChoices> {
         match self {
+            PopupType::Choice(ChoicePopup {
+                table,
+                showing: ChoicePopupState::ConfigForced,
+                ..
+            }) => FORCED_CONFIG_CHOICES.get(table.state.selected()?).copied(),
             PopupType::Choice(choice) => {
                 ConfigChoices::from_repr(choice.table.state.selected().unwrap())
             }
@@ -463,8 +507,7 @@ impl PopupType {
             return;
         };
 
-        let mut default_path = config.location.clone();
-        default_path.pop();
+        let default_path = config.data_dir.clone();
 
         if path == default_path {
             return;
@@ -498,6 +541,122 @@ impl PopupType {
         }
     }
 
+    /// Lists every backup with its tx count and balances. Backups that cannot be read are still
+    /// listed with the error
+    pub fn new_choice_backups(
+        config: &Config,
+        encrypted_db: Option<&EncryptedDb>,
+        theme: &Theme,
+    ) -> Result<Self> {
+        let backups = config.backups();
+
+        if backups.is_empty() {
+            return Err(anyhow!(
+                "No backup has been made yet. Set a backup path to get a backup on every exit"
+            ));
+        }
+
+        let choices: Vec<ChoiceDetails> = backups
+            .iter()
+            .map(|backup| {
+                let summary =
+                    OpenedBackup::open(backup, encrypted_db).and_then(|opened| opened.summary());
+                let date = backup.created.format("%Y-%m-%d %H:%M:%S");
+                let date = if backup.safety {
+                    format!("{date} Safety copy")
+                } else {
+                    date.to_string()
+                };
+
+                match summary {
+                    Ok(summary) => ChoiceDetails {
+                        text: format!(
+                            "{date} | {} txs | {}",
+                            summary.tx_count,
+                            balances_line(&summary)
+                        ),
+                        color: theme.positive(),
+                    },
+                    Err(e) => ChoiceDetails {
+                        text: format!("{date} | Could not read the backup. {e}"),
+                        color: theme.negative(),
+                    },
+                }
+            })
+            .collect();
+
+        let table = choices.iter().map(|c| vec![c.text.clone()]).collect();
+        let mut table_data = TableData::new(table);
+        table_data.state.select(Some(0));
+
+        Ok(PopupType::Choice(ChoicePopup {
+            table: table_data,
+            choices,
+            showing: ChoicePopupState::Backups(backups),
+        }))
+    }
+
+    pub fn get_choice_backup(&self) -> Option<BackupFile> {
+        match self {
+            PopupType::Choice(ChoicePopup {
+                table,
+                showing: ChoicePopupState::Backups(backups),
+                ..
+            }) => backups.get(table.state.selected()?).cloned(),
+            _ => None,
+        }
+    }
+
+    /// Lists every profile of the root config. The profile in use is marked and can't be picked
+    pub fn new_choice_profiles(root_config: &Config, theme: &Theme) -> Self {
+        let profiles = root_config.profile_names();
+        let active = root_config.active_profile();
+
+        let mut choices: Vec<ChoiceDetails> = profiles
+            .iter()
+            .map(|name| {
+                if name == active {
+                    ChoiceDetails {
+                        text: format!("{name} (in use)"),
+                        color: theme.negative(),
+                    }
+                } else {
+                    ChoiceDetails {
+                        text: name.clone(),
+                        color: theme.positive(),
+                    }
+                }
+            })
+            .collect();
+
+        choices.push(ChoiceDetails {
+            text: String::from("Add a new profile"),
+            color: theme.positive(),
+        });
+
+        let table = choices.iter().map(|c| vec![c.text.clone()]).collect();
+        let mut table_data = TableData::new(table);
+        table_data.state.select(Some(0));
+
+        PopupType::Choice(ChoicePopup {
+            table: table_data,
+            choices,
+            showing: ChoicePopupState::Profiles(profiles),
+        })
+    }
+
+    /// The selected profile name. None if the row to add a new profile is selected
+    pub fn get_choice_profile(&self) -> Option<String> {
+        match self {
+            PopupType::Choice(ChoicePopup {
+                table,
+                showing: ChoicePopupState::Profiles(profiles),
+                ..
+            }) => profiles.get(table.state.selected()?).cloned(),
+            _ => None,
+        }
+    }
+
     pub fn new_choice_methods(conn: &mut DbConn, theme: &Theme) -> Result<Self> {
         let tx_methods = conn.get_tx_methods_sorted();
 
@@ -536,12 +695,12 @@ impl PopupType {
         }
     }
 
-    pub fn new_input(modifying: Option<String>) -> Self {
+    pub fn new_input(showing: InputPopupState) -> Self {
         PopupType::Input(InputPopup {
             text: String::new(),
             cursor_position: 0,
             status: String::from("All good"),
-            modifying_method: modifying,
+            showing,
         })
     }
 
@@ -549,6 +708,21 @@ impl PopupType {
         if let PopupType::Input(input) = self {
             add_char_to(char, &mut input.cursor_position, &mut input.text);
 
+            if let InputPopupState::CustomRange = input.showing {
+                input.status = match parse_custom_range(&input.text) {
+                    Some(_) => String::from("All good"),
+                    None => String::from("Invalid. Example: 2024-01-01 2024-03-31"),
+                };
+                return;
+            }
+
+            if let InputPopupState::NewProfile(existing) = &input.showing {
+                input.status = invalid_profile_name(&input.text, existing)
+                    .unwrap_or("All good")
+                    .to_string();
+                return;
+            }
+
             let tx_methods = conn.get_tx_methods_sorted();
 
             if tx_methods.iter().any(|m| m.name == input.text) {
@@ -575,10 +749,14 @@ impl PopupType {
                 return Ok(false);
             }
 
-            if let Some(modifying) = &input.modifying_method {
-                conn.rename_tx_method(modifying, &input.text)?;
-            } else {
-                conn.add_new_methods(std::slice::from_ref(&input.text))?;
+            match &input.showing {
+                InputPopupState::RenameMethod(modifying) => {
+                    conn.rename_tx_method(modifying, &input.text)?;
+                }
+                InputPopupState::NewMethod => {
+                    conn.add_new_methods(std::slice::from_ref(&input.text))?;
+                }
+                InputPopupState::CustomRange | InputPopupState::NewProfile(_) => return Ok(false),
             }
 
             Ok(true)
@@ -587,6 +765,61 @@ impl PopupType {
         }
     }
 
+    pub fn is_new_profile_input(&self) -> bool {
+        matches!(
+            self,
+            PopupType::Input(InputPopup {
+                showing: InputPopupState::NewProfile(_),
+                ..
+            })
+        )
+    }
+
+    /// Returns the entered profile name, if it can be used
+    pub fn get_new_profile(&mut self) -> Option<String> {
+        let PopupType::Input(InputPopup {
+            text,
+            status,
+            showing: InputPopupState::NewProfile(existing),
+            ..
+        }) = self
+        else {
+            return None;
+        };
+
+        if let Some(reason) = invalid_profile_name(text, existing) {
+            *status = reason.to_string();
+            return None;
+        }
+
+        Some(text.clone())
+    }
+
+    pub fn is_custom_range_input(&self) -> bool {
+        matches!(
+            self,
+            PopupType::Input(InputPopup {
+                showing: InputPopupState::CustomRange,
+                ..
+            })
+        )
+    }
+
+    /// Returns the start and end date entered in the custom range input, if valid
+    pub fn get_custom_range(&mut self) -> Option<(NaiveDate, NaiveDate)> {
+        let PopupType::Input(input) = self else {
+            return None;
+        };
+
+        let range = parse_custom_range(&input.text);
+
+        if range.is_none() {
+            input.status = String::from("Invalid. Example: 2024-01-01 2024-03-31");
+        }
+
+        range
+    }
+
     pub fn move_cursor(&mut self, direction: MovementDirection) {
         if let PopupType::Input(input) = self {
             match direction {
@@ -604,3 +837,18 @@ impl PopupType {
         }
     }
 }
+
+/// Parses two dates separated by a space or `to`, like `2024-01-01 to 2024-03-31`
+fn parse_custom_range(text: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let dates = text
+        .split_whitespace()
+        .filter(|s| *s != "to")
+        .map(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+        .collect::<Option<Vec<NaiveDate>>>()?;
+
+    let [start_date, end_date] = dates[..] else {
+        return None;
+    };
+
+    Some((start_date.min(end_date), start_date.max(end_date)))
+}
@@ -0,0 +1,45 @@
+use ratatui::Frame;
+use ratatui::layout::{Alignment, Constraint, Direction, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Paragraph, Row, Table};
+
+use crate::page_handler::TableData;
+use crate::theme::Theme;
+use crate::utility::{create_bolded_text, main_block, styled_block};
+
+/// Draws the profile list that is shown on start once there is more than one profile
+pub fn profile_ui(f: &mut Frame, profiles: &mut TableData, theme: &Theme) {
+    let size = f.area();
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([Constraint::Min(5), Constraint::Length(3)])
+        .split(size);
+
+    f.render_widget(main_block(theme), size);
+
+    let rows = profiles
+        .items
+        .iter()
+        .map(|r| Row::new(r.clone()).style(Style::default().fg(theme.text())));
+
+    let table = Table::new(rows, [Constraint::Percentage(100)])
+        .highlight_symbol(">> ")
+        .block(styled_block("Profiles", theme))
+        .style(Style::default().fg(theme.border()))
+        .row_highlight_style(
+            Style::default()
+                .fg(theme.positive())
+                .add_modifier(Modifier::REVERSED),
+        );
+
+    let help_text = Paragraph::new(create_bolded_text(
+        "Arrow Up/Down: Select. Enter: Open. Q: Quit.",
+    ))
+    .block(styled_block("", theme))
+    .alignment(Alignment::Center);
+
+    f.render_stateful_widget(table, chunks[0], &mut profiles.state);
+    f.render_widget(help_text, chunks[1]);
+}
@@ -3,10 +3,12 @@ use ratatui::layout::{Constraint, Direction, Layout};
 use ratatui::style::{Modifier, Style};
 use ratatui::widgets::{Cell, Row, Table};
 use rex_app::conn::DbConn;
-use rex_app::views::FullSummary;
+use rex_app::views::{FullSummary, SummaryComparison, YearlyReport};
 use thousands::Separable;
 
-use crate::page_handler::{IndexedData, SortingType, SummaryTab, TableData};
+use crate::page_handler::{
+    CompareData, IndexedData, PeriodData, ReportData, SortingType, SummaryTab, TableData,
+};
 use crate::theme::Theme;
 use crate::utility::{
     LerpState, create_tab, main_block, styled_block, styled_block_no_bottom, styled_block_no_top,
@@ -15,21 +17,68 @@ use crate::utility::{
 
 pub const SUMMARY_TABLE_ID: &str = "summary_table_row";
 
+/// Width of the row name column of the yearly report
+const REPORT_NAME_WIDTH: u16 = 18;
+/// Width of each value column of the yearly report
+const REPORT_VALUE_WIDTH: u16 = 13;
+
 /// The function draws the Summary page of the interface.
 pub fn summary_ui(
     f: &mut Frame,
     months: &IndexedData,
     years: &IndexedData,
     mode_selection: &IndexedData,
+    period: &PeriodData,
     table_data: &mut TableData,
     current_page: &SummaryTab,
     summary_hidden_mode: bool,
     summary_sort: &SortingType,
+    summary_report: &ReportData,
+    summary_compare: &CompareData,
     lerp_state: &mut LerpState,
     full_summary: &FullSummary,
     theme: &Theme,
     conn: &mut DbConn,
 ) {
+    if let Some((_, _, base_name)) = &summary_compare.base
+        && let Some(comparison) = &summary_compare.comparison
+    {
+        let title = format!("Comparing {base_name} vs {}", summary_compare.current_name);
+
+        summary_compare_ui(
+            f,
+            months,
+            years,
+            mode_selection,
+            period,
+            table_data,
+            current_page,
+            summary_hidden_mode,
+            comparison,
+            &title,
+            theme,
+        );
+        return;
+    }
+
+    if summary_report.enabled
+        && mode_selection.index == 1
+        && let Some(report) = &summary_report.report
+    {
+        yearly_report_ui(
+            f,
+            years,
+            mode_selection,
+            table_data,
+            current_page,
+            summary_hidden_mode,
+            report,
+            summary_report.column_offset,
+            theme,
+        );
+        return;
+    }
+
     let size = f.area();
 
     let tags_count = table_data.items.len();
@@ -66,18 +115,31 @@ pub fn summary_ui(
 
     let mut lend_borrow_headers = vec!["Borrow", "Lend"];
 
-    if mode_selection.index == 0 {
-        tag_table_headers.push("MoM Income %");
-        tag_table_headers.push("MoM Expense %");
+    // Period over period comparison. Nothing to compare with on All Time
+    let comparison = match mode_selection.index {
+        0 => Some("MoM"),
+        1 => Some("YoY"),
+        3 => Some("WoW"),
+        4 => Some("QoQ"),
+        5 => Some("PoP"),
+        _ => None,
+    };
 
-        lend_borrow_headers.push("MoM Borrow %");
-        lend_borrow_headers.push("MoM Lend %");
-    } else if mode_selection.index == 1 {
-        tag_table_headers.push("YoY Income %");
-        tag_table_headers.push("YoY Expense %");
+    let comparison_headers = comparison.map(|c| {
+        [
+            format!("{c} Income %"),
+            format!("{c} Expense %"),
+            format!("{c} Borrow %"),
+            format!("{c} Lend %"),
+        ]
+    });
 
-        lend_borrow_headers.push("YoY Borrow %");
-        lend_borrow_headers.push("YoY Lend %");
+    if let Some(headers) = &comparison_headers {
+        tag_table_headers.push(&headers[0]);
+        tag_table_headers.push(&headers[1]);
+
+        lend_borrow_headers.push(&headers[2]);
+        lend_borrow_headers.push(&headers[3]);
     }
 
     tag_table_headers.push("Borrow");
@@ -85,7 +147,10 @@ pub fn summary_ui(
 
     let mut method_headers = vec!["Method", "Total Income", "Total Expense"];
 
-    if mode_selection.index != 0 {
+    // Monthly and Weekly modes have no monthly averages
+    let has_average = !matches!(mode_selection.index, 0 | 3);
+
+    if has_average {
         method_headers.push("Average Income");
         method_headers.push("Average Expense");
     }
@@ -93,12 +158,9 @@ pub fn summary_ui(
     method_headers.push("Income %");
     method_headers.push("Expense %");
 
-    if mode_selection.index == 0 {
-        method_headers.push("MoM Income %");
-        method_headers.push("MoM Expense %");
-    } else if mode_selection.index == 1 {
-        method_headers.push("YoY Income %");
-        method_headers.push("YoY Expense %");
+    if let Some(headers) = &comparison_headers {
+        method_headers.push(&headers[0]);
+        method_headers.push(&headers[1]);
     }
 
     let method_header_cells = method_headers
@@ -130,6 +192,13 @@ pub fn summary_ui(
 
     let method_len = conn.get_tx_methods().len() as u16;
 
+    // The period modes share the layout of the Yearly mode with the period tab in place of years
+    let layout_index = if mode_selection.index > 2 {
+        1
+    } else {
+        mode_selection.index
+    };
+
     let mut main_layout = Layout::default().direction(Direction::Vertical).margin(2);
     let mut summary_layout = Layout::default().direction(Direction::Horizontal);
 
@@ -144,7 +213,7 @@ pub fn summary_ui(
         summary_layout =
             summary_layout.constraints([Constraint::Percentage(50), Constraint::Percentage(50)]);
     } else {
-        match mode_selection.index {
+        match layout_index {
             0 => {
                 main_layout = main_layout.constraints([
                     Constraint::Length(3),
@@ -195,14 +264,22 @@ pub fn summary_ui(
     let summary_chunk = if summary_hidden_mode {
         summary_layout.split(chunks[3])
     } else {
-        summary_layout.split(chunks[6 - mode_selection.index])
+        summary_layout.split(chunks[6 - layout_index])
     };
 
     f.render_widget(main_block(theme), size);
 
     let mut month_tab = create_tab(months, "Months", theme);
 
-    let mut year_tab = create_tab(years, "Years", theme);
+    let mode = mode_selection.get_selected_value();
+    let period_tab = period.get_tab(mode);
+    let year_window = years.year_window();
+
+    let mut year_tab = if PeriodData::is_period_mode(mode) {
+        create_tab(&period_tab, "Period", theme)
+    } else {
+        create_tab(&year_window, "Years", theme)
+    };
 
     let mut mode_selection_tab = create_tab(mode_selection, "Modes", theme);
 
@@ -441,7 +518,7 @@ pub fn summary_ui(
             Constraint::Percentage(16),
             Constraint::Percentage(16),
         ]
-    } else if mode_selection.index == 1 {
+    } else if has_average {
         vec![
             Constraint::Percentage(10),
             Constraint::Percentage(12),
@@ -623,7 +700,7 @@ pub fn summary_ui(
     } else {
         f.render_widget(mode_selection_tab, chunks[0]);
 
-        match mode_selection.index {
+        match layout_index {
             0 => {
                 f.render_widget(year_tab, chunks[1]);
                 f.render_widget(month_tab, chunks[2]);
@@ -649,3 +726,283 @@ pub fn summary_ui(
         }
     }
 }
+
+/// Draws the month by month yearly report in place of the summary. Only the value columns
+/// that fit the terminal width are drawn starting from the scrolled column offset.
+fn yearly_report_ui(
+    f: &mut Frame,
+    years: &IndexedData,
+    mode_selection: &IndexedData,
+    table_data: &mut TableData,
+    current_page: &SummaryTab,
+    summary_hidden_mode: bool,
+    report: &YearlyReport,
+    column_offset: usize,
+    theme: &Theme,
+) {
+    let size = f.area();
+
+    let mut main_layout = Layout::default().direction(Direction::Vertical).margin(2);
+
+    main_layout = if summary_hidden_mode {
+        main_layout.constraints([Constraint::Min(0)])
+    } else {
+        main_layout.constraints([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Min(0),
+        ])
+    };
+
+    let chunks = main_layout.split(size);
+
+    f.render_widget(main_block(theme), size);
+
+    let table_chunk = chunks[chunks.len() - 1];
+
+    // Borders and the highlight symbol takes some space
+    let available_width = table_chunk
+        .width
+        .saturating_sub(REPORT_NAME_WIDTH + 5)
+        .max(REPORT_VALUE_WIDTH);
+    let visible_columns = (available_width / (REPORT_VALUE_WIDTH + 1)).max(1) as usize;
+
+    let shown_columns = |row: &[String]| -> Vec<String> {
+        row.iter()
+            .take(1)
+            .chain(row.iter().skip(1 + column_offset).take(visible_columns))
+            .cloned()
+            .collect()
+    };
+
+    let header_cells = shown_columns(&report.headers())
+        .into_iter()
+        .map(|h| Cell::from(h).style(Style::default().fg(theme.background())));
+
+    let header = Row::new(header_cells)
+        .style(Style::default().bg(theme.header()))
+        .height(1)
+        .bottom_margin(0);
+
+    let rows = table_data.items.iter().map(|item| {
+        let cells = shown_columns(item)
+            .into_iter()
+            .enumerate()
+            .map(|(index, c)| {
+                if index == 0 {
+                    Cell::from(c).style(
+                        Style::default()
+                            .fg(theme.text())
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    Cell::from(c.separate_with_commas())
+                }
+            });
+
+        Row::new(cells)
+            .height(1)
+            .bottom_margin(0)
+            .style(Style::default().fg(theme.text()))
+    });
+
+    let mut widths = vec![Constraint::Length(REPORT_NAME_WIDTH)];
+    widths.extend(vec![
+        Constraint::Length(REPORT_VALUE_WIDTH);
+        visible_columns
+    ]);
+
+    let total_columns = report.headers().len() - 1;
+    let last_shown = (column_offset + visible_columns).min(total_columns);
+
+    let title = format!(
+        "Yearly Report: Columns {}-{last_shown} of {total_columns}",
+        column_offset + 1
+    );
+
+    let mut table_area = Table::new(rows, widths)
+        .header(header)
+        .block(styled_block(&title, theme))
+        .style(Style::default().fg(theme.border()));
+
+    let year_window = years.year_window();
+
+    let mut year_tab = create_tab(&year_window, "Years", theme);
+    let mut mode_selection_tab = create_tab(mode_selection, "Modes", theme);
+
+    match current_page {
+        SummaryTab::Years => year_tab = year_tab.highlight_style(tab_highlight_style(theme)),
+        SummaryTab::ModeSelection => {
+            mode_selection_tab = mode_selection_tab.highlight_style(tab_highlight_style(theme));
+        }
+        SummaryTab::Table => {
+            let mut style = Style::default();
+
+            if theme.add_reverse_modifier() {
+                style = style.fg(theme.selected()).add_modifier(Modifier::REVERSED);
+            } else {
+                style = style.bg(theme.selected());
+            }
+
+            table_area = table_area
+                .row_highlight_style(style)
+                .highlight_symbol(">> ");
+        }
+        SummaryTab::Months => {}
+    }
+
+    if !summary_hidden_mode {
+        f.render_widget(mode_selection_tab, chunks[0]);
+        f.render_widget(year_tab, chunks[1]);
+    }
+
+    f.render_stateful_widget(table_area, table_chunk, &mut table_data.state);
+}
+
+/// Draws the pinned period comparison in place of the summary. The method table with the totals
+/// is on top of the tag table
+fn summary_compare_ui(
+    f: &mut Frame,
+    months: &IndexedData,
+    years: &IndexedData,
+    mode_selection: &IndexedData,
+    period: &PeriodData,
+    table_data: &mut TableData,
+    current_page: &SummaryTab,
+    summary_hidden_mode: bool,
+    comparison: &SummaryComparison,
+    title: &str,
+    theme: &Theme,
+) {
+    let size = f.area();
+
+    let method_rows = comparison.methods_array();
+
+    // The period modes share the layout of the Yearly mode with the period tab in place of years
+    let tab_count = if summary_hidden_mode {
+        0
+    } else {
+        match mode_selection.index {
+            0 => 3,
+            2 => 1,
+            _ => 2,
+        }
+    };
+
+    let mut constraints = vec![Constraint::Length(3); tab_count];
+    // Method rows, the header and the borders
+    constraints.push(Constraint::Length(method_rows.len() as u16 + 3));
+    constraints.push(Constraint::Min(0));
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(constraints)
+        .split(size);
+
+    f.render_widget(main_block(theme), size);
+
+    let widths = [
+        Constraint::Percentage(12),
+        Constraint::Percentage(11),
+        Constraint::Percentage(11),
+        Constraint::Percentage(11),
+        Constraint::Percentage(11),
+        Constraint::Percentage(11),
+        Constraint::Percentage(11),
+        Constraint::Percentage(11),
+        Constraint::Percentage(11),
+    ];
+
+    let header = |name: &str| {
+        let cells = SummaryComparison::headers(name)
+            .into_iter()
+            .map(|h| Cell::from(h).style(Style::default().fg(theme.background())));
+
+        Row::new(cells)
+            .style(Style::default().bg(theme.header()))
+            .height(1)
+            .bottom_margin(0)
+    };
+
+    let to_row = |item: &Vec<String>| {
+        let cells = item.iter().enumerate().map(|(index, c)| {
+            if index == 0 {
+                Cell::from(c.clone()).style(
+                    Style::default()
+                        .fg(theme.text())
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                Cell::from(c.separate_with_commas())
+            }
+        });
+
+        Row::new(cells)
+            .height(1)
+            .bottom_margin(0)
+            .style(Style::default().fg(theme.text()))
+    };
+
+    let method_area = Table::new(method_rows.iter().map(to_row), widths)
+        .header(header("Method"))
+        .block(styled_block(title, theme))
+        .style(Style::default().fg(theme.border()));
+
+    let tags_text = format!("Tags: {}", table_data.items.len());
+
+    let mut table_area = Table::new(table_data.items.iter().map(to_row), widths)
+        .header(header("Tags"))
+        .block(styled_block(&tags_text, theme))
+        .style(Style::default().fg(theme.border()));
+
+    let mut month_tab = create_tab(months, "Months", theme);
+
+    let mode = mode_selection.get_selected_value();
+    let period_tab = period.get_tab(mode);
+    let year_window = years.year_window();
+
+    let mut year_tab = if PeriodData::is_period_mode(mode) {
+        create_tab(&period_tab, "Period", theme)
+    } else {
+        create_tab(&year_window, "Years", theme)
+    };
+
+    let mut mode_selection_tab = create_tab(mode_selection, "Modes", theme);
+
+    match current_page {
+        SummaryTab::Months => month_tab = month_tab.highlight_style(tab_highlight_style(theme)),
+        SummaryTab::Years => year_tab = year_tab.highlight_style(tab_highlight_style(theme)),
+        SummaryTab::ModeSelection => {
+            mode_selection_tab = mode_selection_tab.highlight_style(tab_highlight_style(theme));
+        }
+        SummaryTab::Table => {
+            let mut style = Style::default();
+
+            if theme.add_reverse_modifier() {
+                style = style.fg(theme.selected()).add_modifier(Modifier::REVERSED);
+            } else {
+                style = style.bg(theme.selected());
+            }
+
+            table_area = table_area
+                .row_highlight_style(style)
+                .highlight_symbol(">> ");
+        }
+    }
+
+    if tab_count > 0 {
+        f.render_widget(mode_selection_tab, chunks[0]);
+    }
+
+    if tab_count > 1 {
+        f.render_widget(year_tab, chunks[1]);
+    }
+
+    if tab_count > 2 {
+        f.render_widget(month_tab, chunks[2]);
+    }
+
+    f.render_widget(method_area, chunks[tab_count]);
+    f.render_stateful_widget(table_area, chunks[tab_count + 1], &mut table_data.state);
+}
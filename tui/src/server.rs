@@ -0,0 +1,356 @@
+use anyhow::{Result, anyhow};
+use rex_app::conn::DbConn;
+use rex_app::error::RexError;
+use rex_app::modifier::parse_search_fields;
+use rex_app::views::{FullTx, TxJson};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::io::Read;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::cli::{PeriodArgs, TxFields, summary_output};
+use crate::config::Config;
+
+/// Largest request body the server reads
+const MAX_BODY_SIZE: u64 = 64 * 1024;
+
+/// Amounts can be sent as a number or as a string with an expression such as 100+20
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AmountInput {
+    Number(f64),
+    Text(String),
+}
+
+impl AmountInput {
+    fn into_string(self) -> String {
+        match self {
+            AmountInput::Number(amount) => amount.to_string(),
+            AmountInput::Text(amount) => amount,
+        }
+    }
+}
+
+/// Body of the add and edit tx requests. Uses the same keys as the tx JSON output
+#[derive(Deserialize)]
+struct TxBody {
+    date: Option<String>,
+    time: Option<String>,
+    #[serde(default)]
+    details: String,
+    from_method: String,
+    #[serde(default)]
+    to_method: String,
+    amount: AmountInput,
+    tx_type: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+impl From<TxBody> for TxFields {
+    fn from(body: TxBody) -> Self {
+        Self {
+            date: body.date,
+            time: body.time,
+            details: body.details,
+            method: body.from_method,
+            to: body.to_method,
+            amount: body.amount.into_string(),
+            tx_type: body.tx_type,
+            tags: body.tags.join(", "),
+        }
+    }
+}
+
+/// Body of the search request. Every field is optional and works the same as the Search page
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct SearchBody {
+    date: String,
+    details: String,
+    from_method: String,
+    to_method: String,
+    amount: String,
+    tx_type: String,
+    tags: Vec<String>,
+}
+
+/// An error with the HTTP status to respond with
+struct ApiError {
+    status: u16,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        RexError::from(error).into()
+    }
+}
+
+impl From<RexError> for ApiError {
+    fn from(error: RexError) -> Self {
+        let status = match error {
+            RexError::NotFound(_) => 404,
+            RexError::Validation(_) | RexError::Other(_) => 400,
+            RexError::Integrity(_) => 409,
+            RexError::Locked => 503,
+            RexError::Open(_)
+            | RexError::Migration(_)
+            | RexError::OldDbMigration(_)
+            | RexError::SchemaMismatch(_)
+            | RexError::Backup(_)
+            | RexError::Crypto(_)
+            | RexError::Io(_)
+            | RexError::Database(_) => 500,
+        };
+
+        Self::new(status, error.to_string())
+    }
+}
+
+type ApiResult = Result<(u16, String), ApiError>;
+
+fn to_json(value: &impl Serialize) -> Result<String, ApiError> {
+    serde_json::to_string(value).map_err(|e| ApiError::new(500, e.to_string()))
+}
+
+fn read_body<T: DeserializeOwned>(request: &mut Request) -> Result<T, ApiError> {
+    let too_large = || ApiError::new(413, format!("Body cannot be over {MAX_BODY_SIZE} bytes"));
+
+    if request
+        .body_length()
+        .is_some_and(|length| length as u64 > MAX_BODY_SIZE)
+    {
+        return Err(too_large());
+    }
+
+    let mut body = Vec::new();
+
+    // One byte over the limit is read to tell a body that was cut apart from one that fits
+    request
+        .as_reader()
+        .take(MAX_BODY_SIZE + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| ApiError::new(400, e.to_string()))?;
+
+    if body.len() as u64 > MAX_BODY_SIZE {
+        return Err(too_large());
+    }
+
+    serde_json::from_slice(&body).map_err(|e| ApiError::new(400, format!("Invalid body: {e}")))
+}
+
+/// Reads the month and year query parameters of the url
+fn query_period(query: &str) -> Result<PeriodArgs, ApiError> {
+    let mut period = PeriodArgs::default();
+
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+        match key {
+            "month" => period.month = Some(value.to_string()),
+            "year" => {
+                let year = value
+                    .parse()
+                    .map_err(|_| ApiError::new(400, format!("Year {value} is invalid")))?;
+                period.year = Some(year);
+            }
+            _ => return Err(ApiError::new(400, format!("Unknown query parameter {key}"))),
+        }
+    }
+
+    if period.month.is_some() && period.year.is_some() {
+        return Err(ApiError::new(400, "Only one of month and year can be used"));
+    }
+
+    Ok(period)
+}
+
+fn tx_id(id: &str) -> Result<i32, ApiError> {
+    id.parse()
+        .map_err(|_| ApiError::new(404, format!("Tx {id} not found")))
+}
+
+fn fetch_tx(id: &str, conn: &mut DbConn) -> Result<FullTx, ApiError> {
+    let id = tx_id(id)?;
+
+    conn.fetch_tx_with_id(id)
+        .map_err(|_| ApiError::new(404, format!("Tx {id} not found")))
+}
+
+fn list_txs(query: &str, conn: &mut DbConn) -> ApiResult {
+    let (date, nature, _) = query_period(query)?.period()?;
+
+    let txs = conn.fetch_txs_with_date(date, nature)?;
+
+    Ok((200, to_json(&txs.json(conn))?))
+}
+
+fn search_txs(request: &mut Request, conn: &mut DbConn) -> ApiResult {
+    let body: SearchBody = read_body(request)?;
+    let tags = body.tags.join(", ");
+
+    let search = parse_search_fields(
+        &body.date,
+        &body.details,
+        &body.from_method,
+        &body.to_method,
+        &body.amount,
+        &body.tx_type,
+        &tags,
+        conn,
+    )?;
+
+    let txs = conn.search_txs(search)?;
+
+    Ok((200, to_json(&txs.json())?))
+}
+
+fn handle(request: &mut Request, conn: &mut DbConn) -> ApiResult {
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    let method = request.method().clone();
+
+    match (method, segments.as_slice()) {
+        (Method::Get, ["methods"]) => {
+            let methods: Vec<&str> = conn
+                .get_tx_methods_sorted()
+                .iter()
+                .map(|method| method.name.as_str())
+                .collect();
+
+            Ok((200, to_json(&methods)?))
+        }
+        (Method::Get, ["balances"]) => Ok((200, to_json(&conn.get_final_balances_json()?)?)),
+        (Method::Get, ["summary"]) => {
+            let summary = summary_output(&query_period(query)?, conn)?;
+
+            Ok((200, to_json(&summary)?))
+        }
+        (Method::Get, ["txs"]) => list_txs(query, conn),
+        (Method::Post, ["txs"]) => {
+            let body: TxBody = read_body(request)?;
+            let saved = TxFields::from(body).save(None, conn)?;
+
+            Ok((201, json!({ "added": saved }).to_string()))
+        }
+        (Method::Post, ["search"]) => search_txs(request, conn),
+        (Method::Get, ["txs", id]) => {
+            let tx = fetch_tx(id, conn)?;
+
+            Ok((200, to_json(&TxJson::from(&tx))?))
+        }
+        (Method::Put, ["txs", id]) => {
+            let old_tx = fetch_tx(id, conn)?;
+            let body: TxBody = read_body(request)?;
+            let saved = TxFields::from(body).save(Some(&old_tx), conn)?;
+
+            Ok((200, json!({ "edited": saved }).to_string()))
+        }
+        (Method::Delete, ["txs", id]) => {
+            let tx = fetch_tx(id, conn)?;
+            conn.delete_tx(&tx)?;
+
+            Ok((200, json!({ "deleted": tx.id }).to_string()))
+        }
+        (_, ["methods" | "balances" | "summary" | "txs" | "search"] | ["txs", _]) => {
+            Err(ApiError::new(405, "Method not allowed"))
+        }
+        _ => Err(ApiError::new(404, format!("No route for {path}"))),
+    }
+}
+
+/// A new random token of 64 hex characters made from 32 bytes of OS randomness
+pub fn random_token() -> Result<String> {
+    let mut bytes = [0; 32];
+    getrandom::fill(&mut bytes).map_err(|e| anyhow!("Failed to generate the API token. {e}"))?;
+
+    Ok(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+/// Compares two tokens in constant time so the time taken does not tell how much of the token
+/// matched. Empty or blank tokens never match
+fn tokens_match(given: &str, expected: &str) -> bool {
+    if expected.trim().is_empty() {
+        return false;
+    }
+
+    let (given, expected) = (given.as_bytes(), expected.as_bytes());
+
+    if given.len() != expected.len() {
+        return false;
+    }
+
+    given
+        .iter()
+        .zip(expected)
+        .fold(0, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+/// Whether the request has the API token as a bearer token
+fn is_authorized(request: &Request, token: &str) -> bool {
+    request.headers().iter().any(|header| {
+        header.field.equiv("Authorization")
+            && header
+                .value
+                .as_str()
+                .strip_prefix("Bearer ")
+                .is_some_and(|value| tokens_match(value.trim(), token))
+    })
+}
+
+/// Serves the DB over a local HTTP JSON API until the process is stopped.
+/// Requests are handled one at a time so the single DB connection is never shared
+pub fn serve(bind: &str, config: &mut Config, conn: &mut DbConn) -> Result<()> {
+    let token = config.get_api_token()?;
+
+    let server = Server::http(bind).map_err(|e| anyhow!("Failed to bind to {bind}: {e}"))?;
+
+    println!("Serving on http://{bind}");
+    println!(
+        "Requests need the api_token from {} as a Bearer token",
+        config.location.display()
+    );
+
+    for mut request in server.incoming_requests() {
+        let result = if is_authorized(&request, &token) {
+            handle(&mut request, conn)
+        } else {
+            Err(ApiError::new(401, "Missing or invalid token"))
+        };
+
+        let (status, body) = match result {
+            Ok(response) => response,
+            Err(error) => (error.status, json!({ "error": error.message }).to_string()),
+        };
+
+        println!("{} {} {status}", request.method(), request.url());
+
+        let header = Header::from_bytes("Content-Type", "application/json")
+            .expect("Content-Type header should be valid");
+
+        let response = Response::from_string(body)
+            .with_status_code(status)
+            .with_header(header);
+
+        if let Err(e) = request.respond(response) {
+            eprintln!("Failed to respond: {e}");
+        }
+    }
+
+    Ok(())
+}
@@ -2,7 +2,9 @@ use anyhow::Result as AResult;
 use chrono::prelude::Local;
 use rex_app::conn::DbConn;
 use rex_app::modifier::{parse_search_fields, parse_tx_fields};
-use rex_app::ui_helper::{DateType, Output, StepType, SteppingError, VerifierError};
+use rex_app::ui_helper::{
+    DateStep, DateType, Output, StepType, SteppingError, TagSuggestion, VerifierError,
+};
 use rex_app::views::{FullTx, PartialTx, SearchView, TxViewGroup};
 use rex_shared::models::Cent;
 use std::cmp::Ordering;
@@ -15,6 +17,7 @@ use crate::utility::{add_char_to, check_comparison};
 #[derive(Default)]
 pub struct TxData {
     pub date: String,
+    pub time: String,
     pub details: String,
     pub from_method: String,
     pub to_method: String,
@@ -38,6 +41,7 @@ impl TxData {
         let formatted_current_date = &current_date[0..10];
         TxData {
             date: formatted_current_date.to_string(),
+            time: String::new(),
             details: String::new(),
             from_method: String::new(),
             to_method: String::new(),
@@ -57,6 +61,7 @@ impl TxData {
     pub fn new_empty() -> Self {
         TxData {
             date: String::new(),
+            time: String::new(),
             details: String::new(),
             from_method: String::new(),
             to_method: String::new(),
@@ -74,8 +79,15 @@ impl TxData {
 
     #[must_use]
     pub fn from_full_tx(tx: &FullTx, edit: bool, from_search: bool) -> Self {
+        let time = if tx.has_time {
+            tx.date.format("%H:%M").to_string()
+        } else {
+            String::new()
+        };
+
         Self {
             date: tx.date.format("%Y-%m-%d").to_string(),
+            time,
             details: tx.details.clone().unwrap_or_default(),
             from_method: tx.from_method.name.clone(),
             to_method: tx.to_method.clone().map(|t| t.name).unwrap_or_default(),
@@ -122,6 +134,7 @@ impl TxData {
 
         TxData {
             date: new_date,
+            time: String::new(),
             details: details.to_string(),
             from_method: from_method.to_string(),
             to_method: to_method.to_string(),
@@ -149,6 +162,7 @@ impl TxData {
             &self.tx_type,
             &self.tags,
             &self.autofill,
+            &self.time,
         ]
     }
 
@@ -174,6 +188,11 @@ impl TxData {
         add_char_to(to_add, &mut self.current_index, &mut self.date);
     }
 
+    /// Insert or remove from time field according to the index point
+    pub fn edit_time(&mut self, to_add: Option<char>) {
+        add_char_to(to_add, &mut self.current_index, &mut self.time);
+    }
+
     /// Insert or remove from details field according to the index point
     pub fn edit_details(&mut self, to_add: Option<char>) {
         add_char_to(to_add, &mut self.current_index, &mut self.details);
@@ -209,8 +228,15 @@ impl TxData {
         self.check_all_fields()?;
 
         let editing_tx = self.editing_tx;
+
+        let date = if self.time.is_empty() {
+            self.date.clone()
+        } else {
+            format!("{} {}", self.date, self.time)
+        };
+
         let parsed_tx = parse_tx_fields(
-            &self.date,
+            &date,
             &self.details,
             &self.from_method,
             &self.to_method,
@@ -227,10 +253,12 @@ impl TxData {
                 &migrated_conn.fetch_tx_with_id(old_tx_id)?
             };
 
-            migrated_conn.edit_tx(old_tx, parsed_tx, &self.tags)
+            migrated_conn.edit_tx(old_tx, parsed_tx, &self.tags)?;
         } else {
-            migrated_conn.add_new_tx(parsed_tx, &self.tags)
+            migrated_conn.add_new_tx(parsed_tx, &self.tags)?;
         }
+
+        Ok(())
     }
 
     pub fn get_search_tx(&self, migrated_conn: &mut DbConn) -> AResult<SearchView> {
@@ -245,7 +273,7 @@ impl TxData {
             migrated_conn,
         )?;
 
-        migrated_conn.search_txs(new_search)
+        Ok(migrated_conn.search_txs(new_search)?)
     }
 
     /// Adds a value to tx status
@@ -265,12 +293,24 @@ impl TxData {
             TxTab::Details => conn.autofill().details(&self.details),
             TxTab::FromMethod => conn.autofill().tx_method(&self.from_method),
             TxTab::ToMethod => conn.autofill().tx_method(&self.to_method),
+            TxTab::Tags if self.tags.trim().is_empty() => self
+                .tag_suggestion(conn)
+                .map(|s| s.name)
+                .unwrap_or_default(),
             TxTab::Tags => conn.autofill().tags(&self.tags),
             TxTab::TxType => conn.autofill().tx_type(&self.tx_type),
             _ => String::new(),
         }
     }
 
+    /// Returns the most likely tag for the current tx based on the other fields
+    pub fn tag_suggestion(&self, conn: &mut DbConn) -> Option<TagSuggestion> {
+        conn.suggest_tags(&self.details, &self.from_method, &self.amount)
+            .ok()?
+            .into_iter()
+            .next()
+    }
+
     pub fn accept_autofill(&mut self, current_tab: &TxTab) {
         match current_tab {
             TxTab::Details => self.details = self.autofill.clone(),
@@ -305,6 +345,16 @@ impl TxData {
         status
     }
 
+    /// Checks the inputted Time by the user upon pressing Enter/Esc for various error.
+    pub fn check_time(&mut self, conn: &mut DbConn) -> Result<Output, VerifierError> {
+        let mut user_time = self.time.clone();
+        let status = conn.verify().time(&mut user_time);
+
+        self.time = user_time;
+        self.go_current_index(&TxTab::Time);
+        status
+    }
+
     /// Checks the inputted From Method by the user upon pressing Enter/Esc for various error.
     pub fn check_from_method(&mut self, conn: &mut DbConn) -> Result<Output, VerifierError> {
         let mut current_method = self.from_method.clone();
@@ -334,7 +384,7 @@ impl TxData {
         is_search: bool,
         conn: &mut DbConn,
     ) -> Result<Output, VerifierError> {
-        self.check_b_field(conn)?;
+        let balance = self.b_balance(conn)?;
 
         let mut comparison_symbol = None;
 
@@ -354,7 +404,7 @@ impl TxData {
             user_amount = user_amount.replace(symbol, "");
         }
 
-        let status = conn.verify().amount(&mut user_amount);
+        let status = conn.verify().amount_with_balance(&mut user_amount, balance);
 
         if let Some(symbol) = comparison_symbol {
             user_amount = format!("{symbol}{user_amount}");
@@ -426,6 +476,7 @@ impl TxData {
     pub fn check_all_empty(&self) -> bool {
         let all_data = [
             &self.date,
+            &self.time,
             &self.details,
             &self.from_method,
             &self.to_method,
@@ -442,124 +493,28 @@ impl TxData {
         false
     }
 
-    /// Checks for b on amount field to replace with the balance of the tx method field
-    fn check_b_field(&mut self, conn: &mut DbConn) -> Result<(), VerifierError> {
-        self.check_suffixes();
-        let user_amount = self.amount.to_lowercase();
-
+    /// Returns the balance of the tx method field if `b` is used in the amount field
+    fn b_balance(&self, conn: &mut DbConn) -> Result<Option<Cent>, VerifierError> {
         // 'b' represents the current balance of the original tx method
-        if user_amount.contains('b') && !self.from_method.is_empty() {
-            let final_balances = conn
-                .get_final_balances()
-                .map_err(|e| VerifierError::Others(e.to_string()))?;
-
-            let target_method = conn
-                .get_tx_method_by_name(self.from_method.as_str())
-                .map_err(|e| VerifierError::Others(e.to_string()))?;
-
-            // Get all the method's final balance, loop through the balances and match the tx method name
-
-            for balance in final_balances.values() {
-                if balance.method_id == target_method.id {
-                    self.amount = user_amount
-                        .replace('b', &(Cent::new(balance.balance).dollar()).to_string());
-                }
-            }
-        } else if user_amount.contains('b') && self.from_method.is_empty() {
-            return Err(VerifierError::InvalidBValue);
+        if !self.amount.to_lowercase().contains('b') {
+            return Ok(None);
         }
-        Ok(())
-    }
 
-    /// If `k` and `m` are present, multiplies the number 1 thousand and 1 million respectively
-    fn check_suffixes(&mut self) {
-        let mut user_amount = self.amount.to_lowercase();
-        let mut failed_to_parse = false;
-
-        // target char list
-        let target_letters = ['k', 'm'];
-
-        'starter: for letter in target_letters {
-            // How many times this target char is present in the string
-            let count = user_amount.chars().filter(|c| c == &letter).count();
-
-            // We will loop the `count` of times to ensure all the target are handled
-            for _ in 0..count {
-                // Convert the string to a vec of char for easier indexing
-                let amount_vec: Vec<char> = user_amount.chars().collect();
-
-                // The index where the 'k' or 'm' is in the string
-                let target_index = user_amount.find(letter).unwrap();
-                let mut gathered_value = String::new();
-
-                // Ending here would be smaller than the target index.
-                // We are looping from target index to the beginning of the string
-                let mut ending_index = 0;
-
-                for index in (0..target_index).rev() {
-                    let value = amount_vec[index];
-                    if value == ' ' {
-                        continue;
-                    }
-
-                    if value == '.' {
-                        gathered_value = format!("{value}{gathered_value}");
-                        continue;
-                    }
-
-                    // If the char is a number, convert to u16 and save it on gathered value
-                    if let Ok(num_amount) = value.to_string().parse::<u16>() {
-                        gathered_value = format!("{num_amount}{gathered_value}");
-                    } else {
-                        // 100 + 5k
-                        // If this is the + char, then break the loop. The ending index is index of '+' + 1
-                        // + 1 so we don't replace the original char itself after the calculation is done
-                        ending_index = index + 1;
-                        break;
-                    }
-                }
-
-                // In the case like this: 1k1, 5m12
-                // This is invalid. This ensure the next char where k or m was found is not a number
-                // If number, we can't parse it here
-                for value in amount_vec
-                    .iter()
-                    .take(user_amount.len())
-                    .skip(target_index + 1)
-                {
-                    if *value == ' ' {
-                        continue;
-                    }
-
-                    if value.to_string().parse::<u16>().is_ok() {
-                        failed_to_parse = true;
-                        break 'starter;
-                    }
-                    break;
-                }
-
-                if let Ok(parsed_value) = gathered_value.parse::<f64>() {
-                    let suffixed_added_value = match letter {
-                        'k' => parsed_value * 1_000.0,
-                        'm' => parsed_value * 1_000_000.0,
-                        _ => unreachable!(),
-                    };
-
-                    // Example string: 100 + 5k
-                    // Target index would be the index of 'k' and ending index is the index of '+' + 1
-                    // Replace everything in that range with the new calculated value
-                    user_amount = user_amount.replacen(
-                        &user_amount[ending_index..=target_index],
-                        &suffixed_added_value.to_string(),
-                        1,
-                    );
-                }
-            }
+        if self.from_method.is_empty() {
+            return Err(VerifierError::InvalidBValue);
         }
 
-        if !failed_to_parse {
-            self.amount = user_amount;
-        }
+        let final_balances = conn
+            .get_final_balances()
+            .map_err(|e| VerifierError::Others(e.to_string()))?;
+
+        let target_method = conn
+            .get_tx_method_by_name(self.from_method.as_str())
+            .map_err(|e| VerifierError::Others(e.to_string()))?;
+
+        Ok(final_balances
+            .get(&target_method.id)
+            .map(|balance| Cent::new(balance.balance)))
     }
 
     pub fn clear_date(&mut self) {
@@ -576,6 +531,7 @@ impl TxData {
     fn get_data_len(&self, current_tab: &TxTab) -> usize {
         match current_tab {
             TxTab::Date => self.date.len(),
+            TxTab::Time => self.time.len(),
             TxTab::Details => self.details.len(),
             TxTab::FromMethod => self.from_method.len(),
             TxTab::ToMethod => self.to_method.len(),
@@ -618,11 +574,14 @@ impl TxData {
         &mut self,
         date_type: DateType,
         step_type: StepType,
+        date_step: DateStep,
         conn: &mut DbConn,
     ) -> Result<(), SteppingError> {
         let mut user_date = self.date.clone();
 
-        let step_status = conn.step().date(&mut user_date, step_type, date_type);
+        let step_status = conn
+            .step()
+            .date_by(&mut user_date, step_type, date_type, date_step);
         self.date = user_date;
 
         // Reload index to the final point as some data just got added/changed
@@ -685,9 +644,9 @@ impl TxData {
         step_type: StepType,
         conn: &mut DbConn,
     ) -> Result<(), SteppingError> {
-        if self.check_b_field(conn).is_err() {
+        let Ok(balance) = self.b_balance(conn) else {
             return Err(SteppingError::UnknownBValue);
-        }
+        };
 
         let mut comparison_symbol = None;
 
@@ -707,7 +666,9 @@ impl TxData {
             user_amount = user_amount.replace(symbol, "");
         }
 
-        let step_status = conn.step().amount(&mut user_amount, step_type);
+        let step_status = conn
+            .step()
+            .amount_with_balance(&mut user_amount, step_type, balance);
 
         if let Some(symbol) = comparison_symbol {
             user_amount = format!("{symbol}{user_amount}");
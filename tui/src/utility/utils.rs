@@ -9,9 +9,11 @@ use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, BorderType, Borders, Tabs};
-use rex_app::conn::{get_conn, get_conn_old};
+use rex_app::conn::{try_get_conn, try_get_conn_old};
+use rex_app::crypto::encrypted_path;
 use rex_app::migration::start_migration;
 use std::collections::HashMap;
+use std::env;
 use std::io::{Stdout, stdout};
 use std::path::{Path, PathBuf};
 
@@ -30,6 +32,18 @@ pub const RESTRICTED: [&str; 8] = [
     "Daily Expense",
 ];
 
+/// Passphrase of an encrypted DB is read from here before asking for it so scripts can run
+pub const PASSPHRASE_ENV: &str = "REX_PASSPHRASE";
+
+/// Reads the DB passphrase from [`PASSPHRASE_ENV`] or asks for it without echoing the input
+pub fn read_passphrase(prompt: &str) -> Result<String> {
+    if let Ok(passphrase) = env::var(PASSPHRASE_ENV) {
+        return Ok(passphrase);
+    }
+
+    Ok(rpassword::prompt_password(prompt)?)
+}
+
 /// Enters raw mode so the TUI can render properly
 pub fn enter_tui_interface() -> Result<Terminal<CrosstermBackend<Stdout>>> {
     enable_raw_mode()?;
@@ -237,13 +251,15 @@ pub fn migrate_to_new_schema(old_conn_path: &Path, new_conn: &str) -> Result<boo
         return Ok(false);
     }
 
-    let old_conn = get_conn_old(old_conn_path.to_string_lossy().as_ref());
+    let old_conn = try_get_conn_old(old_conn_path.to_string_lossy().as_ref())?;
+
+    let new_path = PathBuf::from(new_conn);
 
-    if PathBuf::from(new_conn).exists() {
+    if new_path.exists() || encrypted_path(&new_path).exists() {
         return Ok(false);
     }
 
-    let mut new_conn = get_conn(new_conn);
+    let mut new_conn = try_get_conn(new_conn)?;
 
     start_migration(old_conn, &mut new_conn)?;
     Ok(true)